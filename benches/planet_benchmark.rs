@@ -1,4 +1,4 @@
-use astro_algos::{planets::Planet, time::JD};
+use astro_algos::{ephemeris::InterpolatedEphemeris, planets::Planet, time::JD};
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
 pub fn mercury_position_benchmark(c: &mut Criterion) {
@@ -49,6 +49,18 @@ pub fn neptune_position_benchmark(c: &mut Criterion) {
     });
 }
 
+pub fn interpolated_mercury_position_benchmark(c: &mut Criterion) {
+    let ephemeris = InterpolatedEphemeris::new(
+        Planet::Mercury,
+        &JD::from(2268900.0),
+        &JD::from(2268940.0),
+        1.0,
+    );
+    c.bench_function("interpolated mercury position", |b| {
+        b.iter(|| ephemeris.get_location(black_box(&JD::from(2268920.3))))
+    });
+}
+
 criterion_group!(
     benches,
     mercury_position_benchmark,
@@ -58,6 +70,7 @@ criterion_group!(
     jupiter_position_benchmark,
     saturn_position_benchmark,
     uranus_position_benchmark,
-    neptune_position_benchmark
+    neptune_position_benchmark,
+    interpolated_mercury_position_benchmark
 );
 criterion_main!(benches);
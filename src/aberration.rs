@@ -0,0 +1,248 @@
+//! Aberration of light.
+//!
+//! Because of the finite speed of light and the motion of the Earth around the Sun, a body
+//! appears displaced from its geometric position in the direction the Earth is moving. The
+//! functions here implement the corrections described in chapter 23.
+use crate::angle::Angle;
+use crate::coords::{Ecliptical, Equatorial, Equinox};
+use crate::linalg::Vec3;
+use crate::precession;
+use crate::time::{JulianCenturies, JD};
+
+/// The constant of aberration κ, in arcseconds
+pub const CONSTANT_OF_ABERRATION: f64 = 20.495_52;
+
+/// Applies the annual aberration to ecliptical coordinates of a star (or any body whose own motion
+/// can be ignored), using formula 23.2.
+///
+/// `sun_longitude` is the true geometric longitude of the Sun at `t`, referred to the same equinox
+/// as `position`.
+pub fn annual_ecliptical<E: Equinox>(
+    position: &Ecliptical<E>,
+    sun_longitude: Angle,
+    t: &JD,
+) -> Ecliptical<E> {
+    let kappa = Angle::from_arcseconds(CONSTANT_OF_ABERRATION).as_radians();
+    let (e, pi) = earth_orbit(t);
+
+    let sun = sun_longitude - position.lon().angle();
+    let perihelion = pi - position.lon().angle();
+
+    let delta_longitude =
+        (-kappa * sun.cos() + e * kappa * perihelion.cos()) / position.lat().angle().cos();
+    let delta_latitude = -kappa * position.lat().angle().sin() * (sun.sin() - e * perihelion.sin());
+
+    Ecliptical::from_angles_with_equinox(
+        position.lon().angle() + Angle::from_radians(delta_longitude),
+        position.lat().angle() + Angle::from_radians(delta_latitude),
+        position.equinox(),
+    )
+}
+
+/// Computes the eccentricity of the Earth's orbit and the longitude of its perihelion, which give
+/// the small elliptic part of the annual aberration.
+fn earth_orbit(t: &JD) -> (f64, Angle) {
+    let big_t = JulianCenturies::from(*t).0;
+    let e = 0.016_708_634 - 0.000_042_037 * big_t - 0.000_000_126_7 * big_t * big_t;
+    let pi = Angle::from_degrees(102.937_35 + 1.719_46 * big_t + 0.000_46 * big_t * big_t);
+    (e, pi)
+}
+
+/// Applies the annual aberration to equatorial coordinates of a star, using formula 23.3.
+///
+/// `sun_longitude` is the true geometric longitude of the Sun at `t`, and the obliquity of the
+/// equinox of `position` is used. The result is accurate to about 0.01".
+pub fn annual_equatorial<E: Equinox>(
+    position: &Equatorial<E>,
+    sun_longitude: Angle,
+    t: &JD,
+) -> Equatorial<E> {
+    let kappa = Angle::from_arcseconds(CONSTANT_OF_ABERRATION).as_radians();
+    let (e, pi) = earth_orbit(t);
+    let epsilon = position.equinox().obliquity();
+    let (sin_ra, cos_ra) = position.ra().angle().sin_cos();
+    let (sin_dec, cos_dec) = position.dec().angle().sin_cos();
+
+    // The circular and elliptic parts have the same form, with the Sun replaced by the perihelion
+    let shift = |coefficient: f64, longitude: Angle| {
+        let (sin_l, cos_l) = longitude.sin_cos();
+        let ra = coefficient * (cos_ra * cos_l * epsilon.cos() + sin_ra * sin_l) / cos_dec;
+        let dec = coefficient
+            * (cos_l * epsilon.cos() * (epsilon.tan() * cos_dec - sin_ra * sin_dec)
+                + cos_ra * sin_dec * sin_l);
+        (ra, dec)
+    };
+    let (sun_ra, sun_dec) = shift(-kappa, sun_longitude);
+    let (perihelion_ra, perihelion_dec) = shift(e * kappa, pi);
+
+    Equatorial::from_angles_with_equinox(
+        position.ra().angle() + Angle::from_radians(sun_ra + perihelion_ra),
+        position.dec().angle() + Angle::from_radians(sun_dec + perihelion_dec),
+        position.equinox(),
+    )
+}
+
+/// Multiples of the arguments, and coefficients of the X, Y and Z components of a periodic term
+type RonVondrakTerm = ([i8; 11], [(f64, f64); 6]);
+
+/// The periodic terms of the velocity of the Earth in the theory of Ron and Vondrák, from table
+/// 23.A.
+///
+/// Each term holds the multiples of the mean longitudes of Venus through Neptune (L2 to L8) and of
+/// the arguments LL, D, M' and F of the Moon, then the coefficients of the sine and cosine for the
+/// X, Y and Z components of the velocity, each with its rate of change per Julian century. The
+/// coefficients are in units of 10⁻⁸ AU per day.
+#[rustfmt::skip]
+const RON_VONDRAK_TERMS: [RonVondrakTerm; 36] = [
+    ([0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0], [(-1_719_914.0, -2.0), (-25.0, 0.0), (25.0, -13.0), (1_578_089.0, 156.0), (10.0, 32.0), (684_185.0, -358.0)]),
+    ([0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0], [(6_434.0, 141.0), (28_007.0, -107.0), (25_697.0, -95.0), (-5_904.0, -130.0), (11_141.0, -48.0), (-2_559.0, -55.0)]),
+    ([0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0], [(715.0, 0.0), (0.0, 0.0), (6.0, 0.0), (-657.0, 0.0), (-15.0, 0.0), (-282.0, 0.0)]),
+    ([0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0], [(715.0, 0.0), (0.0, 0.0), (0.0, 0.0), (-656.0, 0.0), (0.0, 0.0), (-285.0, 0.0)]),
+    ([0, 3, 0, 0, 0, 0, 0, 0, 0, 0, 0], [(486.0, -5.0), (-236.0, -4.0), (-216.0, -4.0), (-446.0, 5.0), (-94.0, 0.0), (-193.0, 0.0)]),
+    ([0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0], [(159.0, 0.0), (0.0, 0.0), (2.0, 0.0), (-147.0, 0.0), (-6.0, 0.0), (-61.0, 0.0)]),
+    ([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1], [(0.0, 0.0), (0.0, 0.0), (0.0, 0.0), (26.0, 0.0), (0.0, 0.0), (-59.0, 0.0)]),
+    ([0, 0, 0, 0, 0, 0, 0, 1, 0, 1, 0], [(39.0, 0.0), (0.0, 0.0), (0.0, 0.0), (-36.0, 0.0), (0.0, 0.0), (-16.0, 0.0)]),
+    ([0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0], [(33.0, 0.0), (-10.0, 0.0), (-9.0, 0.0), (-30.0, 0.0), (-5.0, 0.0), (-13.0, 0.0)]),
+    ([0, 2, 0, -1, 0, 0, 0, 0, 0, 0, 0], [(31.0, 0.0), (1.0, 0.0), (1.0, 0.0), (-28.0, 0.0), (0.0, 0.0), (-12.0, 0.0)]),
+    ([0, 3, -8, 3, 0, 0, 0, 0, 0, 0, 0], [(8.0, 0.0), (-28.0, 0.0), (25.0, 0.0), (8.0, 0.0), (11.0, 0.0), (3.0, 0.0)]),
+    ([0, 5, -8, 3, 0, 0, 0, 0, 0, 0, 0], [(8.0, 0.0), (-28.0, 0.0), (-25.0, 0.0), (-8.0, 0.0), (-11.0, 0.0), (-3.0, 0.0)]),
+    ([2, -1, 0, 0, 0, 0, 0, 0, 0, 0, 0], [(21.0, 0.0), (0.0, 0.0), (0.0, 0.0), (-19.0, 0.0), (0.0, 0.0), (-8.0, 0.0)]),
+    ([1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], [(-19.0, 0.0), (0.0, 0.0), (0.0, 0.0), (17.0, 0.0), (0.0, 0.0), (8.0, 0.0)]),
+    ([0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0], [(17.0, 0.0), (0.0, 0.0), (0.0, 0.0), (-16.0, 0.0), (0.0, 0.0), (-7.0, 0.0)]),
+    ([0, 1, 0, -2, 0, 0, 0, 0, 0, 0, 0], [(16.0, 0.0), (0.0, 0.0), (0.0, 0.0), (15.0, 0.0), (1.0, 0.0), (7.0, 0.0)]),
+    ([0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0], [(16.0, 0.0), (0.0, 0.0), (1.0, 0.0), (-15.0, 0.0), (-3.0, 0.0), (-6.0, 0.0)]),
+    ([0, 1, 0, 1, 0, 0, 0, 0, 0, 0, 0], [(11.0, 0.0), (-1.0, 0.0), (-1.0, 0.0), (-10.0, 0.0), (-1.0, 0.0), (-5.0, 0.0)]),
+    ([2, -2, 0, 0, 0, 0, 0, 0, 0, 0, 0], [(0.0, 0.0), (-11.0, 0.0), (-10.0, 0.0), (0.0, 0.0), (-4.0, 0.0), (0.0, 0.0)]),
+    ([0, 1, 0, -1, 0, 0, 0, 0, 0, 0, 0], [(-11.0, 0.0), (-2.0, 0.0), (-2.0, 0.0), (9.0, 0.0), (-1.0, 0.0), (4.0, 0.0)]),
+    ([0, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0], [(-7.0, 0.0), (-8.0, 0.0), (-8.0, 0.0), (6.0, 0.0), (-3.0, 0.0), (3.0, 0.0)]),
+    ([0, 3, 0, -2, 0, 0, 0, 0, 0, 0, 0], [(-10.0, 0.0), (0.0, 0.0), (0.0, 0.0), (9.0, 0.0), (0.0, 0.0), (4.0, 0.0)]),
+    ([1, -2, 0, 0, 0, 0, 0, 0, 0, 0, 0], [(-9.0, 0.0), (0.0, 0.0), (0.0, 0.0), (-9.0, 0.0), (0.0, 0.0), (-4.0, 0.0)]),
+    ([2, -3, 0, 0, 0, 0, 0, 0, 0, 0, 0], [(-9.0, 0.0), (0.0, 0.0), (0.0, 0.0), (-8.0, 0.0), (0.0, 0.0), (-4.0, 0.0)]),
+    ([0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0], [(0.0, 0.0), (-9.0, 0.0), (-8.0, 0.0), (0.0, 0.0), (-3.0, 0.0), (0.0, 0.0)]),
+    ([2, -4, 0, 0, 0, 0, 0, 0, 0, 0, 0], [(0.0, 0.0), (-9.0, 0.0), (8.0, 0.0), (0.0, 0.0), (3.0, 0.0), (0.0, 0.0)]),
+    ([0, 3, -2, 0, 0, 0, 0, 0, 0, 0, 0], [(8.0, 0.0), (0.0, 0.0), (0.0, 0.0), (-8.0, 0.0), (0.0, 0.0), (-3.0, 0.0)]),
+    ([0, 0, 0, 0, 0, 0, 0, 1, 2, -1, 0], [(8.0, 0.0), (0.0, 0.0), (0.0, 0.0), (-7.0, 0.0), (0.0, 0.0), (-3.0, 0.0)]),
+    ([8, -12, 0, 0, 0, 0, 0, 0, 0, 0, 0], [(-4.0, 0.0), (-7.0, 0.0), (-6.0, 0.0), (4.0, 0.0), (-3.0, 0.0), (2.0, 0.0)]),
+    ([8, -14, 0, 0, 0, 0, 0, 0, 0, 0, 0], [(-4.0, 0.0), (-7.0, 0.0), (6.0, 0.0), (-4.0, 0.0), (3.0, 0.0), (-2.0, 0.0)]),
+    ([0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0], [(-6.0, 0.0), (-5.0, 0.0), (-4.0, 0.0), (5.0, 0.0), (-2.0, 0.0), (2.0, 0.0)]),
+    ([3, -4, 0, 0, 0, 0, 0, 0, 0, 0, 0], [(-1.0, 0.0), (-1.0, 0.0), (-2.0, 0.0), (-7.0, 0.0), (1.0, 0.0), (-4.0, 0.0)]),
+    ([0, 2, 0, -2, 0, 0, 0, 0, 0, 0, 0], [(4.0, 0.0), (-6.0, 0.0), (-5.0, 0.0), (-4.0, 0.0), (-2.0, 0.0), (-2.0, 0.0)]),
+    ([3, -3, 0, 0, 0, 0, 0, 0, 0, 0, 0], [(0.0, 0.0), (-7.0, 0.0), (-6.0, 0.0), (0.0, 0.0), (-3.0, 0.0), (0.0, 0.0)]),
+    ([0, 2, -2, 0, 0, 0, 0, 0, 0, 0, 0], [(5.0, 0.0), (-5.0, 0.0), (-4.0, 0.0), (-5.0, 0.0), (-2.0, 0.0), (-2.0, 0.0)]),
+    ([0, 0, 0, 0, 0, 0, 0, 1, -2, 0, 0], [(5.0, 0.0), (0.0, 0.0), (0.0, 0.0), (-5.0, 0.0), (0.0, 0.0), (-2.0, 0.0)]),
+];
+
+/// The speed of light, in units of 10⁻⁸ AU per day
+const SPEED_OF_LIGHT: f64 = 17_314_463_350.0;
+
+/// Applies the annual aberration to equatorial coordinates of a star, using the velocity of the
+/// Earth from the theory of Ron and Vondrák.
+///
+/// This doesn't need the position of the Sun, and is more precise than `annual_equatorial` as the
+/// theory includes the perturbations of the Earth's motion by the Moon and the planets. The
+/// velocity of the theory is referred to the equator and equinox of J2000, and is precessed to the
+/// equinox of `position`.
+pub fn annual_equatorial_ron_vondrak<E: Equinox>(
+    position: &Equatorial<E>,
+    t: &JD,
+) -> Equatorial<E> {
+    let big_t = JulianCenturies::from(*t).0;
+
+    // The mean longitudes of Venus through Neptune, and the arguments of the Moon, in radians
+    let arguments = [
+        3.176_146_7 + 1_021.328_554_6 * big_t,
+        1.753_470_3 + 628.307_584_9 * big_t,
+        6.203_480_9 + 334.061_243_1 * big_t,
+        0.599_546_5 + 52.969_096_5 * big_t,
+        0.874_016_8 + 21.329_909_5 * big_t,
+        5.481_293_9 + 7.478_159_9 * big_t,
+        5.311_886_3 + 3.813_303_6 * big_t,
+        3.810_344_4 + 8_399.684_733_7 * big_t,
+        5.198_466_7 + 7_771.377_148_6 * big_t,
+        2.355_555_9 + 8_328.691_428_9 * big_t,
+        1.627_905_2 + 8_433.466_160_1 * big_t,
+    ];
+
+    let mut velocity = [0.0; 3];
+    for (multiples, coefficients) in RON_VONDRAK_TERMS.iter() {
+        let argument = multiples
+            .iter()
+            .zip(arguments.iter())
+            .map(|(&m, a)| f64::from(m) * a)
+            .sum::<f64>();
+        let (sin_arg, cos_arg) = argument.sin_cos();
+        for (component, pair) in velocity.iter_mut().zip(coefficients.chunks(2)) {
+            let (sin, sin_rate) = pair[0];
+            let (cos, cos_rate) = pair[1];
+            *component += (sin + sin_rate * big_t) * sin_arg + (cos + cos_rate * big_t) * cos_arg;
+        }
+    }
+
+    // Rotate the velocity into the equinox of the position
+    let p = precession::matrix(&JD::J2000, &position.equinox().epoch());
+    let Vec3 { x, y, z } = p * Vec3::new(velocity[0], velocity[1], velocity[2]);
+
+    let (sin_ra, cos_ra) = position.ra().angle().sin_cos();
+    let (sin_dec, cos_dec) = position.dec().angle().sin_cos();
+    let delta_ra = (y * cos_ra - x * sin_ra) / (SPEED_OF_LIGHT * cos_dec);
+    let delta_dec = -((x * cos_ra + y * sin_ra) * sin_dec - z * cos_dec) / SPEED_OF_LIGHT;
+
+    Equatorial::from_angles_with_equinox(
+        position.ra().angle() + Angle::from_radians(delta_ra),
+        position.dec().angle() + Angle::from_radians(delta_dec),
+        position.equinox(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coords::{MeanOfDate, J2000};
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn ecliptical_aberration() {
+        // A body towards the apex of the Earth's motion, 90° behind the Sun, is barely moved
+        // in longitude, while a body in the direction of the Sun is displaced by the full constant
+        // of aberration
+        let t = JD::from(2_451_545.0);
+        let sun = Angle::from_degrees(280.0);
+        let star =
+            Ecliptical::<J2000>::new(Angle::from_degrees(190.0), Angle::from_degrees(0.0)).unwrap();
+        let moved = annual_ecliptical(&star, sun, &t);
+        let shift = (moved.lon().angle() - star.lon().angle()).as_arcseconds();
+        assert!(shift.abs() < 0.4);
+        assert_approx_eq!(moved.lat().angle().as_degrees(), 0.0);
+
+        let star = Ecliptical::<J2000>::new(sun, Angle::from_degrees(0.0)).unwrap();
+        let moved = annual_ecliptical(&star, sun, &t);
+        let shift = (moved.lon().angle() - star.lon().angle()).as_arcseconds();
+        assert!((shift + CONSTANT_OF_ABERRATION).abs() < 0.4);
+    }
+
+    #[test]
+    fn equatorial_aberration() {
+        // Example 23.a, page 156: θ Persei on 2028 November 13.19 TD, at its mean place of date
+        let t = JD::from(2_462_088.69);
+        let star = Equatorial::mean_of_date(
+            Angle::from_degrees(41.547_214),
+            Angle::from_degrees(49.348_483),
+            &t,
+        )
+        .unwrap();
+
+        let moved = annual_equatorial(&star, Angle::from_degrees(231.328), &t);
+        let delta_ra = (moved.ra().angle() - star.ra().angle()).as_arcseconds();
+        let delta_dec = (moved.dec().angle() - star.dec().angle()).as_arcseconds();
+        assert_approx_eq!(delta_ra, 30.045, 5e-3);
+        assert_approx_eq!(delta_dec, 6.697, 5e-3);
+        assert_eq!(moved.equinox(), MeanOfDate(t));
+
+        // Both theories agree to a few hundredths of an arcsecond
+        let moved = annual_equatorial_ron_vondrak(&star, &t);
+        let delta_ra = (moved.ra().angle() - star.ra().angle()).as_arcseconds();
+        let delta_dec = (moved.dec().angle() - star.dec().angle()).as_arcseconds();
+        assert_approx_eq!(delta_ra, 30.045, 0.03);
+        assert_approx_eq!(delta_dec, 6.697, 0.03);
+    }
+}
@@ -2,24 +2,278 @@
 //!
 //! They are used everywhere and can have several representations. This module helps to handle the
 //! conversion between these representations into a common type.
+//!
+//! Angles can also be parsed from the sexagesimal notations catalogs use, such as `-12°34′56.7″`,
+//! `12d34m56.7s`, `18h 36m 56.3s` or `12:34:56.7`.
+use num_traits::Float;
+use std::str::FromStr;
 
-/// The core representation of an angle. Internally we simply use a `f64` for the representation in
+/// The core representation of an angle. Internally we simply use a float for the representation in
 /// radians but that isn't directly accessible. Instead, you should use the provided conversion
 /// functions.
+///
+/// The angle is generic over the floating point type, `f32` or `f64`. The rest of the crate works
+/// in double precision with the `Angle` alias, while `Angle32` is available where single precision
+/// is enough.
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
-pub struct Angle(f64);
+pub struct GenericAngle<F>(F);
 
-impl Angle {
-    /// Converts a bare `f64` into an `Angle`, treating the `f64` as if it were in units of degrees.
-    pub fn from_degrees(d: f64) -> Angle {
-        Angle(d.to_radians())
-    }
+/// An angle in double precision
+pub type Angle = GenericAngle<f64>;
 
-    /// Converts a bare `f64` into an `Angle`, treating the `f64` as if it were in units of radians.
-    pub fn from_radians(r: f64) -> Angle {
-        Angle(r)
-    }
+/// An angle in single precision
+pub type Angle32 = GenericAngle<f32>;
+
+// The inherent methods are implemented separately for each float type, rather than once over
+// `Float`, so that the constructors can be `const fn`. The conversions multiply by the same
+// factors as `to_radians` and `to_degrees`, which can only be called in a const fn since Rust
+// 1.85.
+macro_rules! impl_angle {
+    ($f:ident, $degrees_per_radian:expr) => {
+        impl GenericAngle<$f> {
+            /// An angle of zero
+            pub const ZERO: Self = Self(0.0);
+            /// An angle of 90°
+            pub const RIGHT_ANGLE: Self = Self(std::$f::consts::FRAC_PI_2);
+            /// An angle of 180°
+            pub const STRAIGHT_ANGLE: Self = Self(std::$f::consts::PI);
+            /// An angle of 360°
+            pub const FULL_TURN: Self = Self(std::$f::consts::TAU);
+
+            /// Converts a bare float into an angle, treating the float as if it were in units of
+            /// degrees.
+            pub const fn from_degrees(d: $f) -> Self {
+                Self(d * (std::$f::consts::PI / 180.0))
+            }
+
+            /// Converts a bare float into an angle, treating the float as if it were in units of
+            /// radians.
+            pub const fn from_radians(r: $f) -> Self {
+                Self(r)
+            }
+
+            /// Converts a bare float into an angle, treating the float as if it were in units of
+            /// arcseconds.
+            pub const fn from_arcseconds(s: $f) -> Self {
+                Self::from_degrees(s / 3600.0)
+            }
+
+            /// Converts a bare float into an angle, treating the float as if it were in units of
+            /// milliarcseconds.
+            pub const fn from_mas(mas: $f) -> Self {
+                Self::from_arcseconds(mas / 1000.0)
+            }
+
+            /// Converts a bare float into an angle, treating the float as if it were in units of
+            /// hours, of 15° each, as hour angles and right ascensions are usually given.
+            pub const fn from_hours(h: $f) -> Self {
+                Self::from_degrees(h * 15.0)
+            }
+
+            /// Converts a bare float into an angle, treating the float as if it were in units of
+            /// full turns.
+            pub const fn from_turns(turns: $f) -> Self {
+                Self(turns * std::$f::consts::TAU)
+            }
+
+            /// Converts a bare float into an angle, treating the float as if it were in units of
+            /// gons, of which there are 400 in a full turn.
+            pub const fn from_gradians(gon: $f) -> Self {
+                Self::from_degrees(gon * 0.9)
+            }
+
+            /// Converts an angle into a bare float that is in units of radians
+            pub const fn as_radians(&self) -> $f {
+                self.0
+            }
+
+            /// Converts an angle into a bare float that is in units of degrees
+            pub const fn as_degrees(&self) -> $f {
+                self.0 * $degrees_per_radian
+            }
+
+            /// Converts an angle into a bare float that is in units of arcseconds
+            pub const fn as_arcseconds(&self) -> $f {
+                self.as_degrees() * 3600.0
+            }
+
+            /// Converts an angle into a bare float that is in units of milliarcseconds
+            pub const fn as_mas(&self) -> $f {
+                self.as_arcseconds() * 1000.0
+            }
+
+            /// Converts an angle into a bare float that is in units of hours
+            pub const fn as_hours(&self) -> $f {
+                self.as_degrees() / 15.0
+            }
+
+            /// Converts an angle into a bare float that is in units of full turns
+            pub const fn as_turns(&self) -> $f {
+                self.0 / std::$f::consts::TAU
+            }
+
+            /// Converts an angle into a bare float that is in units of gons
+            pub const fn as_gradians(&self) -> $f {
+                self.as_degrees() / 0.9
+            }
+
+            /// Gets the sine of the angle.
+            pub fn sin(&self) -> $f {
+                self.0.sin()
+            }
+
+            /// Gets the cosine of the angle.
+            pub fn cos(&self) -> $f {
+                self.0.cos()
+            }
+
+            /// Gets both the sine and the cosine of the angle, which is cheaper than computing them
+            /// separately.
+            pub fn sin_cos(&self) -> ($f, $f) {
+                self.0.sin_cos()
+            }
+
+            /// Gets the tangent of the angle.
+            pub fn tan(&self) -> $f {
+                self.0.tan()
+            }
+
+            /// Gets the arcsine angle of a value
+            pub fn asin(item: $f) -> Self {
+                Self(item.asin())
+            }
+
+            /// Gets the arccosine angle of a value
+            pub fn acos(item: $f) -> Self {
+                Self(item.acos())
+            }
+
+            /// Gets the arctangent angle of a value
+            pub fn atan(item: $f) -> Self {
+                Self(item.atan())
+            }
+
+            /// Gets the arctangent angle of a value, using the typical atan2 function
+            pub fn atan2(num: $f, denom: $f) -> Self {
+                Self(num.atan2(denom))
+            }
 
+            /// Wraps the value of an angle so that is is between the two given limits, by adding or
+            /// subtracting whole multiples of the range between them.
+            ///
+            /// In certain circumstances it is customary to keep the value of an angle between
+            /// certain values, but the limits of the values are dependent on the use case. For
+            /// example longitude values are between -180 and 180 degrees.
+            ///
+            /// The range includes the low limit but not the high one, so wrapping into
+            /// [-180°, 180°) turns 180° into -180°. An angle that is NaN or infinite wraps to NaN.
+            ///
+            /// # Errors
+            /// Returns an error if the high limit isn't greater than the low one, or either is not
+            /// finite
+            ///
+            /// # Examples
+            /// ```
+            /// use astro_algos::angle::Angle;
+            /// let far_east = Angle::from_degrees(180.0);
+            /// let far_west = Angle::from_degrees(-180.0);
+            /// let longitude = Angle::from_degrees(190.0).wrap(&far_west, &far_east).unwrap();
+            /// assert!((longitude.as_degrees() + 170.0).abs() < 1e-12);
+            /// ```
+            pub fn wrap(
+                self,
+                low_limit: &Self,
+                high_limit: &Self,
+            ) -> Result<Self, InvalidRangeError> {
+                let range = (high_limit - low_limit).0;
+                if !(range > 0.0 && range.is_finite()) {
+                    return Err(InvalidRangeError);
+                }
+
+                let offset = (self - *low_limit).0.rem_euclid(range);
+                // A tiny negative offset can round to exactly the whole range
+                let offset = if offset >= range { 0.0 } else { offset };
+                Ok(*low_limit + Self(offset))
+            }
+
+            /// Normalizes the angle into the range [0°, 360°), as is customary for longitudes and
+            /// right ascensions
+            pub fn normalize_0_360(self) -> Self {
+                let r = self.0.rem_euclid(Self::FULL_TURN.0);
+                // A tiny negative angle can round to exactly a full turn
+                Self(if r >= Self::FULL_TURN.0 { 0.0 } else { r })
+            }
+
+            /// Normalizes the angle into the range [-180°, 180°), as is customary for hour angles
+            /// and differences of longitudes
+            pub fn normalize_pm180(self) -> Self {
+                (self + Self::STRAIGHT_ANGLE).normalize_0_360() - Self::STRAIGHT_ANGLE
+            }
+
+            /// Finds the signed difference `self - other` along the shorter arc, in the range
+            /// (-180°, 180°]. A plain subtraction goes wrong when the two angles lie on either side
+            /// of the wrap, for example 1° minus 359° is 2°, not -358°.
+            pub fn difference(&self, other: &Self) -> Self {
+                let d = (*self - *other).normalize_pm180();
+                if d == -Self::STRAIGHT_ANGLE {
+                    Self::STRAIGHT_ANGLE
+                } else {
+                    d
+                }
+            }
+
+            /// Gets the absolute value of the angle
+            pub fn abs(self) -> Self {
+                Self(self.0.abs())
+            }
+
+            /// Restricts the angle to lie between `min` and `max`, with no wrapping around.
+            ///
+            /// # Panics
+            /// Panics if `min` is greater than `max`, or either is NaN, as `clamp` on floats does
+            pub fn clamp(self, min: Self, max: Self) -> Self {
+                Self(self.0.clamp(min.0, max.0))
+            }
+
+            /// Rounds the angle to the nearest multiple of `resolution`, for example to match the
+            /// rounding of published tables. A zero resolution gives NaN.
+            pub fn round_to(self, resolution: Self) -> Self {
+                Self((self.0 / resolution.0).round() * resolution.0)
+            }
+
+            /// Rounds the angle to the nearest multiple of `precision` arcseconds
+            pub fn round_to_arcseconds(self, precision: $f) -> Self {
+                self.round_to(Self::from_arcseconds(precision))
+            }
+
+            /// Rounds the angle to the nearest multiple of `precision` seconds of time, of which
+            /// there are 86400 in a full turn
+            pub fn round_to_seconds_of_time(self, precision: $f) -> Self {
+                self.round_to(Self::from_hours(precision / 3600.0))
+            }
+
+            /// Interpolates linearly from `a` to `b`, where `t == 0.0` gives `a` and `t == 1.0`
+            /// gives `b`.
+            ///
+            /// The interpolation goes along the shorter arc between the two angles, so that
+            /// interpolating from 359° to 1° passes through 0° rather than 180°. The result isn't
+            /// normalized, in that example it lies between 359° and 361°.
+            pub fn lerp(a: Self, b: Self, t: $f) -> Self {
+                a + (b - a).normalize_pm180() * t
+            }
+
+            /// Normalizes the angle into the range [0, 2π). This is the same as `normalize_0_360`.
+            pub fn normalize_0_2pi(self) -> Self {
+                self.normalize_0_360()
+            }
+        }
+    };
+}
+
+impl_angle!(f32, 57.295_78);
+impl_angle!(f64, 180.0 / std::f64::consts::PI);
+
+impl Angle {
     /// Converts an angle represented as degrees, minutes, and second into an `Angle`.
     pub fn from_dms(angle: DegreesMinutesSeconds) -> Angle {
         angle.as_angle()
@@ -30,195 +284,674 @@ impl Angle {
         angle.as_angle()
     }
 
-    /// Converts an `Angle` into a bare `f64` that is in units of radians
-    pub fn as_radians(&self) -> f64 {
-        self.0
-    }
-
-    /// Converts an `Angle` into a bare `f64` that is in units of degrees
-    pub fn as_degrees(&self) -> f64 {
-        self.0.to_degrees()
-    }
-
     /// Converts an `Angle` into a `DegreesMinutesSeconds`
     pub fn as_dms(&self) -> DegreesMinutesSeconds {
-        DegreesMinutesSeconds::from_angle(self.clone())
+        DegreesMinutesSeconds::from_angle(*self)
     }
 
     /// Converts an `Angle` into a `HoursMinutesSeconds`
     pub fn as_hms(&self) -> HoursMinutesSeconds {
-        HoursMinutesSeconds::from_angle(self.clone())
+        HoursMinutesSeconds::from_angle(*self)
+    }
+}
+
+/// Computes the angular separation between two directions on the sky, given by their right
+/// ascensions and declinations, or equally by any other pair of longitude and latitude.
+///
+/// This uses the formula from chapter 17 which works through the arctangent of the ratio of the
+/// sine and the cosine of the separation, so that it stays accurate for directions very close to
+/// each other as well as for nearly opposite ones.
+pub fn separation(ra1: Angle, dec1: Angle, ra2: Angle, dec2: Angle) -> Angle {
+    let (sin_d1, cos_d1) = dec1.sin_cos();
+    let (sin_d2, cos_d2) = dec2.sin_cos();
+    let (sin_da, cos_da) = (ra2 - ra1).sin_cos();
+
+    let x = cos_d1 * sin_d2 - sin_d1 * cos_d2 * cos_da;
+    let y = cos_d2 * sin_da;
+    let z = sin_d1 * sin_d2 + cos_d1 * cos_d2 * cos_da;
+    Angle::atan2(x.hypot(y), z)
+}
+
+/// Computes the position angle of the second direction as seen from the first, given by their
+/// right ascensions and declinations. The angle is measured from the north towards the east, in
+/// the range [0°, 360°).
+pub fn position_angle(ra1: Angle, dec1: Angle, ra2: Angle, dec2: Angle) -> Angle {
+    let (sin_d1, cos_d1) = dec1.sin_cos();
+    let (sin_d2, cos_d2) = dec2.sin_cos();
+    let (sin_da, cos_da) = (ra2 - ra1).sin_cos();
+
+    Angle::atan2(cos_d2 * sin_da, cos_d1 * sin_d2 - sin_d1 * cos_d2 * cos_da).normalize_0_360()
+}
+
+impl<F: Float> std::ops::Add for GenericAngle<F> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0)
     }
+}
 
-    /// Gets the sine of the angle.
-    pub fn sin(&self) -> f64 {
-        self.0.sin()
+impl<F: Float> std::ops::Add for &GenericAngle<F> {
+    type Output = GenericAngle<F>;
+
+    fn add(self, rhs: Self) -> GenericAngle<F> {
+        GenericAngle(self.0 + rhs.0)
     }
+}
 
-    /// Gets the cosine of the angle.
-    pub fn cos(&self) -> f64 {
-        self.0.cos()
+impl<F: Float> std::ops::AddAssign for GenericAngle<F> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 = self.0 + rhs.0;
     }
+}
+
+impl<F: Float> std::ops::Sub for GenericAngle<F> {
+    type Output = Self;
 
-    /// Gets the tangent of the angle.
-    pub fn tan(&self) -> f64 {
-        self.0.tan()
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 - rhs.0)
     }
+}
+
+impl<F: Float> std::ops::Sub for &GenericAngle<F> {
+    type Output = GenericAngle<F>;
 
-    /// Gets the arcsine angle of a value
-    pub fn asin(item: f64) -> Angle {
-        Angle(item.asin())
+    fn sub(self, rhs: Self) -> GenericAngle<F> {
+        GenericAngle(self.0 - rhs.0)
     }
+}
 
-    /// Gets the arccosine angle of a value
-    pub fn acos(item: f64) -> Angle {
-        Angle(item.acos())
+impl<F: Float> std::ops::SubAssign for GenericAngle<F> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 = self.0 - rhs.0;
     }
+}
 
-    /// Gets the arctangent angle of a value
-    pub fn atan(item: f64) -> Angle {
-        Angle(item.atan())
+impl<F: Float> std::ops::Mul<F> for GenericAngle<F> {
+    type Output = Self;
+
+    fn mul(self, rhs: F) -> Self {
+        Self(self.0 * rhs)
     }
+}
 
-    /// Gets the arctangent angle of a value, using the typical atan2 function
-    pub fn atan2(num: f64, denom: f64) -> Angle {
-        Angle(num.atan2(denom))
+impl std::ops::Mul<Angle> for f64 {
+    type Output = Angle;
+
+    fn mul(self, rhs: Angle) -> Angle {
+        GenericAngle(self * rhs.0)
     }
+}
 
-    /// Wraps the value of an angle so that is is between the two given limits
-    ///
-    /// In certain circumstances it is customary to keep the value of an angle between certain
-    /// values, but the limits of the values are dependent on the use case. For example longitude
-    /// values are between -180 and 180 degrees, while latitude values are between -90 and 90
-    /// degrees.
-    /// # Examples
-    /// ```
-    /// use astro_algos::angle::Angle;
-    /// let far_east = Angle::from_degrees(180.0);
-    /// let far_west = Angle::from_degrees(-180.0);
-    /// let longitude = Angle::from_degrees(190.0).wrap(&far_west, &far_east); // Makes `longitude` == -170 degrees
-    /// ```
-    pub fn wrap(mut self, low_limit: &Angle, high_limit: &Angle) -> Angle {
-        assert!(high_limit > low_limit);
-        let range = high_limit - low_limit;
-        while self > *high_limit {
-            self -= range;
-        }
+impl std::ops::Mul<Angle32> for f32 {
+    type Output = Angle32;
 
-        while self < *low_limit {
-            self += range;
-        }
+    fn mul(self, rhs: Angle32) -> Angle32 {
+        GenericAngle(self * rhs.0)
+    }
+}
 
-        self
+impl<F: Float> std::ops::MulAssign<F> for GenericAngle<F> {
+    fn mul_assign(&mut self, rhs: F) {
+        self.0 = self.0 * rhs;
     }
 }
 
-impl std::ops::Add for Angle {
+impl<F: Float> std::ops::Div<F> for GenericAngle<F> {
     type Output = Self;
 
-    fn add(self, rhs: Self) -> Self {
-        Self(self.0 + rhs.0)
+    fn div(self, rhs: F) -> Self {
+        Self(self.0 / rhs)
     }
 }
 
-impl std::ops::Add for &Angle {
-    type Output = Angle;
-
-    fn add(self, rhs: Self) -> Angle {
-        Angle(self.0 + rhs.0)
+impl<F: Float> std::ops::DivAssign<F> for GenericAngle<F> {
+    fn div_assign(&mut self, rhs: F) {
+        self.0 = self.0 / rhs;
     }
 }
 
-impl std::ops::AddAssign for Angle {
-    fn add_assign(&mut self, rhs: Self) {
-        self.0 += rhs.0;
+/// The ratio of two angles
+impl<F: Float> std::ops::Div for GenericAngle<F> {
+    type Output = F;
+
+    fn div(self, rhs: Self) -> F {
+        self.0 / rhs.0
     }
 }
 
-impl std::ops::Sub for Angle {
+impl<F: Float> std::ops::Neg for GenericAngle<F> {
     type Output = Self;
 
-    fn sub(self, rhs: Self) -> Self {
-        Self(self.0 - rhs.0)
+    fn neg(self) -> Self {
+        Self(-self.0)
     }
 }
 
-impl std::ops::Sub for &Angle {
-    type Output = Angle;
+/// The remainder of the division by another angle, with the sign of the dividend as for floats.
+/// Use `wrap` or the `normalize` methods for a result that is always positive.
+impl<F: Float> std::ops::Rem for GenericAngle<F> {
+    type Output = Self;
 
-    fn sub(self, rhs: Self) -> Angle {
-        Angle(self.0 - rhs.0)
+    fn rem(self, rhs: Self) -> Self {
+        Self(self.0 % rhs.0)
     }
 }
 
-impl std::ops::SubAssign for Angle {
-    fn sub_assign(&mut self, rhs: Self) {
-        self.0 -= rhs.0;
+impl<F: Float> std::ops::RemAssign for GenericAngle<F> {
+    fn rem_assign(&mut self, rhs: Self) {
+        self.0 = self.0 % rhs.0;
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+/// The sign of an angle written in sexagesimal notation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Sign {
+    Positive,
+    Negative,
+}
+
+impl Sign {
+    fn of(value: f64) -> Sign {
+        if value.is_sign_negative() {
+            Sign::Negative
+        } else {
+            Sign::Positive
+        }
+    }
+
+    fn apply(self, value: f64) -> f64 {
+        match self {
+            Sign::Positive => value,
+            Sign::Negative => -value,
+        }
+    }
+}
+
+/// An angle as degrees, minutes and seconds. The components are never negative, the sign of the
+/// whole angle is kept separately so that angles such as −0°30′ can be represented.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DegreesMinutesSeconds {
-    pub degrees: i32,
-    pub minutes: i32,
+    pub sign: Sign,
+    pub degrees: u32,
+    pub minutes: u32,
     pub seconds: f64,
 }
 
 impl DegreesMinutesSeconds {
+    /// Builds the angle from its components, checking that the minutes and seconds are within
+    /// [0, 60).
+    pub fn new(
+        sign: Sign,
+        degrees: u32,
+        minutes: u32,
+        seconds: f64,
+    ) -> Result<Self, SexagesimalError> {
+        check_sexagesimal(minutes, seconds)?;
+        Ok(Self {
+            sign,
+            degrees,
+            minutes,
+            seconds,
+        })
+    }
+
+    /// Builds the angle from a total number of arcseconds, splitting it into normalized components
+    pub fn from_arcseconds(total: f64) -> Self {
+        let (degrees, minutes, seconds) = split_seconds(total.abs());
+        Self {
+            sign: Sign::of(total),
+            degrees,
+            minutes,
+            seconds,
+        }
+    }
+
     pub fn from_angle(angle: Angle) -> Self {
         let degrees = angle.as_degrees();
-        let minutes = degrees.fract().abs() * 60.0;
-        let seconds = minutes.fract().abs() * 60.0;
+        let (whole, minutes, seconds) = split_sexagesimal(degrees.abs());
 
         Self {
-            degrees: degrees.trunc() as i32,
-            minutes: minutes.trunc() as i32,
+            sign: Sign::of(degrees),
+            degrees: whole,
+            minutes,
+            seconds,
+        }
+    }
+
+    /// Rounds the seconds to the given number of decimals, carrying a full minute of seconds
+    /// into the minutes and a full hour or degree of minutes into the degrees, so that the
+    /// result never shows 60 seconds or minutes.
+    pub fn rounded(&self, decimals: usize) -> Self {
+        let (degrees, minutes, seconds) =
+            round_sexagesimal(self.degrees, self.minutes, self.seconds, decimals);
+        Self {
+            sign: self.sign,
+            degrees,
+            minutes,
             seconds,
         }
     }
 
     pub fn as_angle(&self) -> Angle {
         let deg = (self.degrees as f64) + (self.minutes as f64) / 60.0 + (self.seconds / 3600.0);
-        Angle::from_degrees(deg)
+        Angle::from_degrees(self.sign.apply(deg))
+    }
+}
+
+impl PartialOrd for DegreesMinutesSeconds {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.as_angle().partial_cmp(&other.as_angle())
     }
 }
 
 impl std::fmt::Display for DegreesMinutesSeconds {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}°{}′{:.3}″", self.degrees, self.minutes, self.seconds)
+        if self.sign == Sign::Negative {
+            write!(f, "-")?;
+        }
+        let decimals = f.precision().unwrap_or(3);
+        let rounded = self.rounded(decimals);
+        write!(
+            f,
+            "{}°{}′{:.*}″",
+            rounded.degrees, rounded.minutes, decimals, rounded.seconds
+        )
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+/// An angle as hours, minutes and seconds. The components are never negative, the sign of the
+/// whole angle is kept separately so that angles such as −0ʰ30ᵐ can be represented.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HoursMinutesSeconds {
-    pub hours: i32,
-    pub minutes: i32,
+    pub sign: Sign,
+    pub hours: u32,
+    pub minutes: u32,
     pub seconds: f64,
 }
 
 impl HoursMinutesSeconds {
+    /// Builds the angle from its components, checking that the minutes and seconds are within
+    /// [0, 60).
+    pub fn new(
+        sign: Sign,
+        hours: u32,
+        minutes: u32,
+        seconds: f64,
+    ) -> Result<Self, SexagesimalError> {
+        check_sexagesimal(minutes, seconds)?;
+        Ok(Self {
+            sign,
+            hours,
+            minutes,
+            seconds,
+        })
+    }
+
+    /// Builds the angle from a total number of seconds of time, splitting it into normalized
+    /// components
+    pub fn from_seconds(total: f64) -> Self {
+        let (hours, minutes, seconds) = split_seconds(total.abs());
+        Self {
+            sign: Sign::of(total),
+            hours,
+            minutes,
+            seconds,
+        }
+    }
+
     pub fn from_angle(angle: Angle) -> Self {
-        let hours = angle.as_degrees() / 15.0;
-        let minutes = hours.fract().abs() * 60.0;
-        let seconds = minutes.fract().abs() * 60.0;
+        let hours = angle.as_hours();
+        let (whole, minutes, seconds) = split_sexagesimal(hours.abs());
 
         Self {
-            hours: hours.trunc() as i32,
-            minutes: minutes.trunc() as i32,
+            sign: Sign::of(hours),
+            hours: whole,
+            minutes,
+            seconds,
+        }
+    }
+
+    /// Rounds the seconds to the given number of decimals, carrying a full minute of seconds
+    /// into the minutes and a full hour or degree of minutes into the hours, so that the
+    /// result never shows 60 seconds or minutes.
+    pub fn rounded(&self, decimals: usize) -> Self {
+        let (hours, minutes, seconds) =
+            round_sexagesimal(self.hours, self.minutes, self.seconds, decimals);
+        Self {
+            sign: self.sign,
+            hours,
+            minutes,
             seconds,
         }
     }
 
     pub fn as_angle(&self) -> Angle {
-        let deg =
-            ((self.hours as f64) + (self.minutes as f64) / 60.0 + (self.seconds / 3600.0)) * 15.0;
-        Angle::from_degrees(deg)
+        let hours = (self.hours as f64) + (self.minutes as f64) / 60.0 + (self.seconds / 3600.0);
+        Angle::from_hours(self.sign.apply(hours))
+    }
+}
+
+impl PartialOrd for HoursMinutesSeconds {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.as_angle().partial_cmp(&other.as_angle())
     }
 }
 
 impl std::fmt::Display for HoursMinutesSeconds {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}ʰ{}ᵐ{:.3}ˢ", self.hours, self.minutes, self.seconds)
+        if self.sign == Sign::Negative {
+            write!(f, "-")?;
+        }
+        let decimals = f.precision().unwrap_or(3);
+        let rounded = self.rounded(decimals);
+        write!(
+            f,
+            "{}ʰ{}ᵐ{:.*}ˢ",
+            rounded.hours, rounded.minutes, decimals, rounded.seconds
+        )
+    }
+}
+
+/// Splits a non-negative value into its whole part, whole sixtieths and the remaining
+/// three-thousand-six-hundredths
+fn split_sexagesimal(value: f64) -> (u32, u32, f64) {
+    let minutes = value.fract() * 60.0;
+    let seconds = minutes.fract() * 60.0;
+    (value.trunc() as u32, minutes.trunc() as u32, seconds)
+}
+
+/// Rounds the seconds of a sexagesimal value to the given number of decimals, carrying into the
+/// minutes and the whole part
+fn round_sexagesimal(whole: u32, minutes: u32, seconds: f64, decimals: usize) -> (u32, u32, f64) {
+    let scale = 10f64.powi(decimals as i32);
+    let mut seconds = (seconds * scale).round() / scale;
+    let (mut whole, mut minutes) = (whole, minutes);
+    if seconds >= 60.0 {
+        seconds -= 60.0;
+        minutes += 1;
+    }
+    if minutes >= 60 {
+        minutes -= 60;
+        whole += 1;
+    }
+    (whole, minutes, seconds)
+}
+
+/// Splits a non-negative number of seconds into whole units of 3600 seconds, whole minutes and the
+/// remaining seconds
+fn split_seconds(total: f64) -> (u32, u32, f64) {
+    let whole = (total / 3600.0).floor();
+    let rest = total - whole * 3600.0;
+    let minutes = (rest / 60.0).floor();
+    (whole as u32, minutes as u32, rest - minutes * 60.0)
+}
+
+/// The reasons the components of a sexagesimal angle can be invalid.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SexagesimalError {
+    /// The minutes are 60 or more
+    InvalidMinutes(u32),
+    /// The seconds are negative, 60 or more, or not a number
+    InvalidSeconds(f64),
+}
+
+impl std::fmt::Display for SexagesimalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SexagesimalError::InvalidMinutes(minutes) => {
+                write!(f, "invalid minutes {}, must be less than 60", minutes)
+            }
+            SexagesimalError::InvalidSeconds(seconds) => {
+                write!(f, "invalid seconds {}, must be within [0, 60)", seconds)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SexagesimalError {}
+
+fn check_sexagesimal(minutes: u32, seconds: f64) -> Result<(), SexagesimalError> {
+    if minutes >= 60 {
+        return Err(SexagesimalError::InvalidMinutes(minutes));
+    }
+    if !(0.0..60.0).contains(&seconds) {
+        return Err(SexagesimalError::InvalidSeconds(seconds));
+    }
+    Ok(())
+}
+
+/// The error returned by `Angle::wrap` when the limits don't make a valid range.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InvalidRangeError;
+
+impl std::fmt::Display for InvalidRangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the high limit of the range must be finite and greater than the low limit"
+        )
+    }
+}
+
+impl std::error::Error for InvalidRangeError {}
+
+/// The reasons a string can fail to parse as an angle.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseAngleError {
+    /// There is no number in the string
+    Empty,
+    /// A character that is neither part of a number nor a unit
+    UnexpectedCharacter(char),
+    /// A number that can't be parsed
+    InvalidNumber(String),
+    /// The components are out of order, repeated, or only the last one has a fractional part
+    InvalidFormat,
+    /// The minutes or seconds are 60 or more
+    OutOfRange,
+    /// Hours where degrees are expected, or the other way around
+    WrongUnit,
+}
+
+impl std::fmt::Display for ParseAngleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseAngleError::Empty => write!(f, "No angle to parse"),
+            ParseAngleError::UnexpectedCharacter(c) => write!(f, "Unexpected character '{}'", c),
+            ParseAngleError::InvalidNumber(number) => write!(f, "Invalid number \"{}\"", number),
+            ParseAngleError::InvalidFormat => write!(f, "Invalid sexagesimal format"),
+            ParseAngleError::OutOfRange => write!(f, "Minutes and seconds must be less than 60"),
+            ParseAngleError::WrongUnit => write!(f, "Unexpected unit"),
+        }
+    }
+}
+
+impl std::error::Error for ParseAngleError {}
+
+/// The unit of the first component of a sexagesimal string
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Unit {
+    Unspecified,
+    Degrees,
+    Hours,
+}
+
+/// The parts of a sexagesimal string
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Sexagesimal {
+    sign: Sign,
+    unit: Unit,
+    components: [f64; 3],
+}
+
+impl Sexagesimal {
+    /// The value in the unit of the first component
+    fn value(&self) -> f64 {
+        let [first, minutes, seconds] = self.components;
+        self.sign.apply(first + minutes / 60.0 + seconds / 3600.0)
+    }
+}
+
+fn parse_sexagesimal(s: &str) -> Result<Sexagesimal, ParseAngleError> {
+    let mut chars = s.trim().chars().peekable();
+    let sign = match chars.peek() {
+        Some('-') | Some('−') => {
+            chars.next();
+            Sign::Negative
+        }
+        Some('+') => {
+            chars.next();
+            Sign::Positive
+        }
+        _ => Sign::Positive,
+    };
+
+    let mut unit = Unit::Unspecified;
+    let mut components = [0.0; 3];
+    // The index of the next component, and whether the last one read had a fractional part
+    let mut next = 0;
+    let mut fractional = false;
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        let mut number = String::new();
+        while let Some(&c) = chars.peek() {
+            if !(c.is_ascii_digit() || c == '.') {
+                break;
+            }
+            number.push(c);
+            chars.next();
+        }
+        if number.is_empty() {
+            match chars.next() {
+                None if next == 0 => return Err(ParseAngleError::Empty),
+                None => break,
+                Some(c) => return Err(ParseAngleError::UnexpectedCharacter(c)),
+            }
+        }
+        let value: f64 = number
+            .parse()
+            .map_err(|_| ParseAngleError::InvalidNumber(number.clone()))?;
+
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        // Each unit symbol or separator is consumed along with the number it follows
+        let index = match chars.peek() {
+            Some('°') | Some('d') | Some('D') => {
+                chars.next();
+                unit = Unit::Degrees;
+                0
+            }
+            Some('h') | Some('H') | Some('ʰ') => {
+                chars.next();
+                unit = Unit::Hours;
+                0
+            }
+            Some('′') | Some('m') | Some('ᵐ') => {
+                chars.next();
+                1
+            }
+            Some('″') | Some('"') | Some('s') | Some('ˢ') => {
+                chars.next();
+                2
+            }
+            Some('\'') => {
+                // A single quote marks the minutes, and two of them the seconds
+                chars.next();
+                if chars.peek() == Some(&'\'') {
+                    chars.next();
+                    2
+                } else {
+                    1
+                }
+            }
+            Some(':') => {
+                chars.next();
+                next
+            }
+            // A number without a unit, followed by the next one or the end
+            Some(c) if c.is_ascii_digit() || *c == '.' => next,
+            None => next,
+            Some(&c) => return Err(ParseAngleError::UnexpectedCharacter(c)),
+        };
+
+        if index < next || index > 2 || fractional {
+            return Err(ParseAngleError::InvalidFormat);
+        }
+        components[index] = value;
+        fractional = value.fract() != 0.0;
+        next = index + 1;
+    }
+
+    if components[1] >= 60.0 || components[2] >= 60.0 {
+        return Err(ParseAngleError::OutOfRange);
+    }
+    Ok(Sexagesimal {
+        sign,
+        unit,
+        components,
+    })
+}
+
+/// Parses an angle in degrees, or in hours if the first component is marked with an `h`
+impl FromStr for Angle {
+    type Err = ParseAngleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parsed = parse_sexagesimal(s)?;
+        match parsed.unit {
+            Unit::Hours => Ok(Angle::from_hours(parsed.value())),
+            _ => Ok(Angle::from_degrees(parsed.value())),
+        }
+    }
+}
+
+/// Parses degrees, minutes and seconds
+impl FromStr for DegreesMinutesSeconds {
+    type Err = ParseAngleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parsed = parse_sexagesimal(s)?;
+        if parsed.unit == Unit::Hours {
+            return Err(ParseAngleError::WrongUnit);
+        }
+        let [degrees, minutes, seconds] = parsed.components;
+        if degrees.fract() != 0.0 || minutes.fract() != 0.0 {
+            return Ok(Angle::from_degrees(parsed.value()).as_dms());
+        }
+        Ok(DegreesMinutesSeconds {
+            sign: parsed.sign,
+            degrees: degrees as u32,
+            minutes: minutes as u32,
+            seconds,
+        })
+    }
+}
+
+/// Parses hours, minutes and seconds
+impl FromStr for HoursMinutesSeconds {
+    type Err = ParseAngleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parsed = parse_sexagesimal(s)?;
+        if parsed.unit == Unit::Degrees {
+            return Err(ParseAngleError::WrongUnit);
+        }
+        let [hours, minutes, seconds] = parsed.components;
+        if hours.fract() != 0.0 || minutes.fract() != 0.0 {
+            return Ok(Angle::from_hours(parsed.value()).as_hms());
+        }
+        Ok(HoursMinutesSeconds {
+            sign: parsed.sign,
+            hours: hours as u32,
+            minutes: minutes as u32,
+            seconds,
+        })
     }
 }
 
@@ -229,15 +962,50 @@ mod tests {
 
     #[test]
     fn from_degrees() {
-        assert_eq!(Angle::from_degrees(0.0), Angle(0.0));
-        assert_eq!(Angle::from_degrees(1.0), Angle(0.01745329251994329577));
-        assert_eq!(Angle::from_degrees(25.4345), Angle(0.44391576859849775626));
+        assert_eq!(Angle::from_degrees(0.0), GenericAngle(0.0));
+        assert_eq!(
+            Angle::from_degrees(1.0),
+            GenericAngle(0.017_453_292_519_943_295)
+        );
+        assert_eq!(
+            Angle::from_degrees(25.4345),
+            GenericAngle(0.443_915_768_598_497_74)
+        );
+    }
+
+    #[test]
+    fn single_precision() {
+        let angle = Angle32::from_degrees(30.0);
+        assert!((angle.sin() - 0.5).abs() < 1e-6);
+        assert!(((angle * 2.0).as_degrees() - 60.0).abs() < 1e-5);
+        assert_eq!(2.0 * angle, angle + angle);
+        assert!((Angle32::from_degrees(-10.0).normalize_0_360().as_degrees() - 350.0).abs() < 1e-4);
+        assert_eq!(Angle32::FULL_TURN / Angle32::STRAIGHT_ANGLE, 2.0);
+    }
+
+    #[test]
+    fn other_units() {
+        assert_approx_eq!(Angle::from_hours(6.5).as_degrees(), 97.5, 1e-12);
+        assert_approx_eq!(Angle::from_degrees(-45.0).as_hours(), -3.0, 1e-12);
+        assert_eq!(Angle::from_turns(0.5), Angle::STRAIGHT_ANGLE);
+        assert_approx_eq!(Angle::from_degrees(90.0).as_turns(), 0.25, 1e-12);
+        assert_approx_eq!(Angle::from_gradians(100.0).as_degrees(), 90.0, 1e-12);
+        assert_approx_eq!(Angle::FULL_TURN.as_gradians(), 400.0, 1e-12);
+    }
+
+    #[test]
+    fn arcseconds() {
+        assert_approx_eq!(Angle::from_arcseconds(3600.0).as_degrees(), 1.0, 1e-12);
+        assert_approx_eq!(Angle::from_mas(1500.0).as_arcseconds(), 1.5, 1e-12);
+        assert_approx_eq!(Angle::from_degrees(-0.5).as_arcseconds(), -1800.0, 1e-9);
+        assert_approx_eq!(Angle::from_arcseconds(0.25).as_mas(), 250.0, 1e-9);
     }
 
     #[test]
     fn from_dms() {
         assert_eq!(
             Angle::from_dms(DegreesMinutesSeconds {
+                sign: Sign::Positive,
                 degrees: 0,
                 minutes: 0,
                 seconds: 0.0
@@ -246,6 +1014,7 @@ mod tests {
         );
         assert_eq!(
             Angle::from_dms(DegreesMinutesSeconds {
+                sign: Sign::Positive,
                 degrees: 1,
                 minutes: 0,
                 seconds: 0.0
@@ -254,28 +1023,112 @@ mod tests {
         );
         assert_eq!(
             Angle::from_dms(DegreesMinutesSeconds {
+                sign: Sign::Positive,
                 degrees: 0,
                 minutes: 1,
                 seconds: 0.0
             }),
-            Angle::from_degrees(0.01666666666666666667)
+            Angle::from_degrees(0.016_666_666_666_666_666)
         );
         assert_eq!(
             Angle::from_dms(DegreesMinutesSeconds {
+                sign: Sign::Positive,
                 degrees: 0,
                 minutes: 0,
                 seconds: 1.0
             }),
-            Angle::from_degrees(0.00027777777777777778)
+            Angle::from_degrees(0.000_277_777_777_777_777_8)
         );
         assert_eq!(
             Angle::from_dms(DegreesMinutesSeconds {
+                sign: Sign::Positive,
                 degrees: 34,
                 minutes: 55,
                 seconds: 25.5436353
             }),
-            Angle::from_degrees(34.92376212091666666667)
+            Angle::from_degrees(34.923_762_120_916_66)
+        );
+    }
+
+    #[test]
+    fn arcturus_and_spica() {
+        // Example 17.a
+        let d = separation(
+            Angle::from_degrees(213.9154),
+            Angle::from_degrees(19.1825),
+            Angle::from_degrees(201.2983),
+            Angle::from_degrees(-11.1614),
+        );
+        assert_approx_eq!(d.as_degrees(), 32.7930, 1e-4);
+    }
+
+    #[test]
+    fn small_and_large_separations() {
+        let ra = Angle::from_degrees(10.0);
+        let dec = Angle::from_degrees(40.0);
+        let d = separation(ra, dec, ra, dec + Angle::from_mas(1.0));
+        assert_approx_eq!(d.as_mas(), 1.0, 1e-6);
+
+        let d = separation(
+            ra,
+            dec,
+            ra + Angle::STRAIGHT_ANGLE,
+            -dec + Angle::from_mas(1.0),
+        );
+        assert_approx_eq!((Angle::STRAIGHT_ANGLE - d).as_mas(), 1.0, 1e-3);
+        assert_eq!(separation(ra, dec, ra, dec), Angle::ZERO);
+    }
+
+    #[test]
+    fn position_angles() {
+        let ra = Angle::from_degrees(150.0);
+        let dec = Angle::from_degrees(30.0);
+        let step = Angle::from_arcseconds(1.0);
+        let pa = |ra2, dec2| position_angle(ra, dec, ra2, dec2).as_degrees();
+        assert_approx_eq!(pa(ra, dec + step), 0.0, 1e-9);
+        assert_approx_eq!(pa(ra + step, dec), 90.0, 1e-3);
+        assert_approx_eq!(pa(ra, dec - step), 180.0, 1e-9);
+        assert_approx_eq!(pa(ra - step, dec), 270.0, 1e-3);
+
+        // Arcturus is about north east of Spica, see example 17.a
+        let pa = position_angle(
+            Angle::from_degrees(201.2983),
+            Angle::from_degrees(-11.1614),
+            Angle::from_degrees(213.9154),
+            Angle::from_degrees(19.1825),
+        );
+        assert!(pa.as_degrees() > 0.0 && pa.as_degrees() < 90.0);
+
+        // Seen from the celestial pole, south along the hour circle of right ascension zero, the
+        // position angle is 180° − α
+        let pa = position_angle(
+            Angle::ZERO,
+            Angle::RIGHT_ANGLE,
+            Angle::from_degrees(40.0),
+            Angle::from_degrees(10.0),
         );
+        assert_approx_eq!(pa.as_degrees(), 140.0, 1e-9);
+    }
+
+    #[test]
+    fn constants() {
+        const TABLE: [Angle; 3] = [
+            Angle::from_degrees(90.0),
+            Angle::from_arcseconds(648_000.0),
+            Angle::from_radians(std::f64::consts::TAU),
+        ];
+        assert_eq!(TABLE[0], Angle::RIGHT_ANGLE);
+        assert_approx_eq!(TABLE[1].as_radians(), Angle::STRAIGHT_ANGLE.as_radians());
+        assert_eq!(TABLE[2], Angle::FULL_TURN);
+        assert_eq!(Angle::ZERO.as_degrees(), 0.0);
+    }
+
+    #[test]
+    fn sin_cos() {
+        let angle = Angle::from_degrees(123.4);
+        let (sin, cos) = angle.sin_cos();
+        assert_eq!(sin, angle.sin());
+        assert_eq!(cos, angle.cos());
     }
 
     #[test]
@@ -304,8 +1157,8 @@ mod tests {
         assert_eq!(Angle::atan(1.0), Angle::from_degrees(45.0));
         assert_eq!(Angle::atan(-1.0), Angle::from_degrees(-45.0));
 
-        assert_eq!(Angle::atan(std::f64::MAX), Angle::from_degrees(90.0));
-        assert_eq!(Angle::atan(std::f64::MIN), Angle::from_degrees(-90.0));
+        assert_eq!(Angle::atan(f64::MAX), Angle::from_degrees(90.0));
+        assert_eq!(Angle::atan(f64::MIN), Angle::from_degrees(-90.0));
     }
 
     #[test]
@@ -318,9 +1171,237 @@ mod tests {
         assert_eq!(Angle::atan2(-1.0, 0.0), Angle::from_degrees(-90.0));
         assert_eq!(Angle::atan2(-1.0, -1.0), Angle::from_degrees(-135.0));
 
+        assert_eq!(Angle::atan2(f64::MAX, f64::MAX), Angle::from_degrees(45.0));
+    }
+
+    #[test]
+    fn wrap() {
+        let low = Angle::from_degrees(-180.0);
+        let high = Angle::from_degrees(180.0);
+        let wrap = |d: f64| {
+            Angle::from_degrees(d)
+                .wrap(&low, &high)
+                .unwrap()
+                .as_degrees()
+        };
+        assert_approx_eq!(wrap(190.0), -170.0, 1e-12);
+        assert_approx_eq!(wrap(-190.0), 170.0, 1e-12);
+        assert_approx_eq!(wrap(45.0), 45.0, 1e-12);
+        assert_eq!(wrap(-180.0), -180.0);
+        assert_approx_eq!(wrap(180.0), -180.0, 1e-12);
+        assert_approx_eq!(wrap(360.0 * 1e9 + 10.0), 10.0, 1e-3);
+        assert!(wrap(f64::INFINITY).is_nan());
+
+        let quarter = Angle::from_degrees(90.0);
+        assert_approx_eq!(
+            Angle::from_degrees(-100.0)
+                .wrap(&Angle::ZERO, &quarter)
+                .unwrap()
+                .as_degrees(),
+            80.0,
+            1e-12
+        );
+
+        assert_eq!(Angle::ZERO.wrap(&high, &low), Err(InvalidRangeError));
+        assert_eq!(Angle::ZERO.wrap(&low, &low), Err(InvalidRangeError));
         assert_eq!(
-            Angle::atan2(std::f64::MAX, std::f64::MAX),
-            Angle::from_degrees(45.0)
+            Angle::ZERO.wrap(&low, &Angle::from_degrees(f64::INFINITY)),
+            Err(InvalidRangeError)
+        );
+    }
+
+    #[test]
+    fn normalize() {
+        let degrees = |d: f64| Angle::from_degrees(d);
+        assert_approx_eq!(degrees(370.0).normalize_0_360().as_degrees(), 10.0);
+        assert_approx_eq!(degrees(-10.0).normalize_0_360().as_degrees(), 350.0);
+        assert_approx_eq!(degrees(-730.0).normalize_0_360().as_degrees(), 350.0);
+        assert_eq!(degrees(360.0).normalize_0_360(), degrees(0.0));
+        assert_eq!(
+            Angle::from_radians(-1e-20).normalize_0_360(),
+            GenericAngle(0.0)
+        );
+        assert_approx_eq!(degrees(190.0).normalize_pm180().as_degrees(), -170.0);
+        assert_approx_eq!(degrees(-190.0).normalize_pm180().as_degrees(), 170.0);
+        assert_approx_eq!(degrees(180.0).normalize_pm180().as_degrees(), -180.0);
+        assert_approx_eq!(degrees(45.0).normalize_pm180().as_degrees(), 45.0);
+        assert_approx_eq!(
+            Angle::from_radians(7.0).normalize_0_2pi().as_radians(),
+            7.0 - std::f64::consts::TAU
+        );
+    }
+
+    #[test]
+    fn differences() {
+        let difference = |a: f64, b: f64| {
+            Angle::from_degrees(a)
+                .difference(&Angle::from_degrees(b))
+                .as_degrees()
+        };
+        assert_approx_eq!(difference(1.0, 359.0), 2.0, 1e-12);
+        assert_approx_eq!(difference(359.0, 1.0), -2.0, 1e-12);
+        assert_approx_eq!(difference(30.0, 10.0), 20.0, 1e-12);
+        assert_approx_eq!(difference(-170.0, 170.0), 20.0, 1e-12);
+        assert_approx_eq!(difference(720.0, 0.0), 0.0, 1e-12);
+        assert_eq!(difference(180.0, 0.0), 180.0);
+        assert_eq!(difference(0.0, 180.0), 180.0);
+    }
+
+    #[test]
+    fn rem_clamp_and_abs() {
+        let turn = Angle::FULL_TURN;
+        assert_approx_eq!(
+            (Angle::from_degrees(370.0) % turn).as_degrees(),
+            10.0,
+            1e-12
+        );
+        assert_approx_eq!(
+            (Angle::from_degrees(-370.0) % turn).as_degrees(),
+            -10.0,
+            1e-12
+        );
+        let mut angle = Angle::from_degrees(100.0);
+        angle %= Angle::RIGHT_ANGLE;
+        assert_approx_eq!(angle.as_degrees(), 10.0, 1e-12);
+
+        let clamp = |d: f64| {
+            Angle::from_degrees(d)
+                .clamp(-Angle::RIGHT_ANGLE, Angle::RIGHT_ANGLE)
+                .as_degrees()
+        };
+        assert_approx_eq!(clamp(95.0), 90.0, 1e-12);
+        assert_approx_eq!(clamp(-95.0), -90.0, 1e-12);
+        assert_approx_eq!(clamp(45.0), 45.0, 1e-12);
+
+        assert_eq!(Angle::from_degrees(-30.0).abs(), Angle::from_degrees(30.0));
+        assert_eq!(Angle::from_degrees(30.0).abs(), Angle::from_degrees(30.0));
+    }
+
+    #[test]
+    fn quantization() {
+        let angle = Angle::from_degrees(12.0 + 34.0 / 60.0 + 56.789 / 3600.0);
+        let rounded = angle.round_to_arcseconds(0.1).as_dms();
+        assert_eq!((rounded.degrees, rounded.minutes), (12, 34));
+        assert_approx_eq!(rounded.seconds, 56.8, 1e-9);
+        assert_approx_eq!(
+            angle.round_to_arcseconds(60.0).as_degrees(),
+            12.0 + 35.0 / 60.0,
+            1e-12
+        );
+        assert_approx_eq!(
+            angle.round_to(Angle::from_degrees(0.5)).as_degrees(),
+            12.5,
+            1e-12
+        );
+
+        let ra = Angle::from_hours(7.0 + 45.0 / 60.0 + 18.946 / 3600.0);
+        let rounded = ra.round_to_seconds_of_time(0.01).as_hms();
+        assert_eq!((rounded.hours, rounded.minutes), (7, 45));
+        assert_approx_eq!(rounded.seconds, 18.95, 1e-9);
+
+        let negative = Angle::from_arcseconds(-2.6).round_to_arcseconds(1.0);
+        assert_approx_eq!(negative.as_arcseconds(), -3.0, 1e-12);
+    }
+
+    #[test]
+    fn interpolation() {
+        let lerp = |a: f64, b: f64, t| {
+            Angle::lerp(Angle::from_degrees(a), Angle::from_degrees(b), t)
+                .normalize_0_360()
+                .as_degrees()
+        };
+        assert_approx_eq!(lerp(10.0, 30.0, 0.25), 15.0, 1e-12);
+        assert_approx_eq!(lerp(359.0, 1.0, 0.25), 359.5, 1e-12);
+        assert_approx_eq!(lerp(359.0, 1.0, 0.75), 0.5, 1e-12);
+        assert_approx_eq!(lerp(1.0, 359.0, 0.75), 359.5, 1e-12);
+        assert_approx_eq!(lerp(-170.0, 170.0, 0.5), 180.0, 1e-12);
+        assert_approx_eq!(lerp(10.0, 30.0, 1.5), 40.0, 1e-12);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let angle = Angle::from_degrees(30.0);
+        assert_approx_eq!((angle * 3.0).as_degrees(), 90.0);
+        assert_approx_eq!((3.0 * angle).as_degrees(), 90.0);
+        assert_approx_eq!((angle / 2.0).as_degrees(), 15.0);
+        assert_approx_eq!(angle / Angle::from_degrees(7.5), 4.0);
+        assert_eq!(-angle, Angle::from_degrees(-30.0));
+        assert_eq!(-(-angle), angle);
+
+        let mut angle = Angle::from_degrees(10.0);
+        angle *= 4.0;
+        assert_approx_eq!(angle.as_degrees(), 40.0);
+        angle /= 8.0;
+        assert_approx_eq!(angle.as_degrees(), 5.0);
+    }
+
+    #[test]
+    fn parsing() {
+        let degrees = |s: &str| s.parse::<Angle>().unwrap().as_degrees();
+        let expected = 12.0 + 34.0 / 60.0 + 56.7 / 3600.0;
+        assert_approx_eq!(degrees("-12°34′56.7″"), -expected);
+        assert_approx_eq!(degrees("−12°34′56.7″"), -expected);
+        assert_approx_eq!(degrees("12d34m56.7s"), expected);
+        assert_approx_eq!(degrees("+12° 34' 56.7\""), expected);
+        assert_approx_eq!(degrees("12°34'56.7''"), expected);
+        assert_approx_eq!(degrees("12:34:56.7"), expected);
+        assert_approx_eq!(degrees("12 34 56.7"), expected);
+        assert_approx_eq!(degrees("-0°30′"), -0.5);
+        assert_approx_eq!(degrees("12.5"), 12.5);
+        assert_approx_eq!(degrees("45′"), 0.75);
+        // Right ascensions are in hours, like that of Vega
+        assert_approx_eq!(degrees("18h 36m 56.3s"), 279.234_583_333);
+        assert_approx_eq!(degrees("9ʰ14ᵐ55.8ˢ"), 138.732_5);
+
+        assert_eq!("".parse::<Angle>(), Err(ParseAngleError::Empty));
+        assert_eq!(
+            "12°x".parse::<Angle>(),
+            Err(ParseAngleError::UnexpectedCharacter('x'))
+        );
+        assert_eq!(
+            "12'x".parse::<Angle>(),
+            Err(ParseAngleError::UnexpectedCharacter('x'))
+        );
+        assert_eq!(
+            "12°30′,".parse::<Angle>(),
+            Err(ParseAngleError::UnexpectedCharacter(','))
+        );
+        assert_eq!(
+            "12.3.4".parse::<Angle>(),
+            Err(ParseAngleError::InvalidNumber("12.3.4".to_string()))
+        );
+        assert_eq!(
+            "12″34′".parse::<Angle>(),
+            Err(ParseAngleError::InvalidFormat)
+        );
+        assert_eq!(
+            "12.5°30′".parse::<Angle>(),
+            Err(ParseAngleError::InvalidFormat)
+        );
+        assert_eq!("12°61′".parse::<Angle>(), Err(ParseAngleError::OutOfRange));
+    }
+
+    #[test]
+    fn parsing_sexagesimal() {
+        let dms: DegreesMinutesSeconds = "-12°34′56.7″".parse().unwrap();
+        assert_eq!(dms.sign, Sign::Negative);
+        assert_eq!((dms.degrees, dms.minutes), (12, 34));
+        assert_approx_eq!(dms.seconds, 56.7);
+        let dms: DegreesMinutesSeconds = "12.5°".parse().unwrap();
+        assert_eq!((dms.degrees, dms.minutes), (12, 30));
+        assert_eq!(
+            "18h 36m 56.3s".parse::<DegreesMinutesSeconds>(),
+            Err(ParseAngleError::WrongUnit)
+        );
+
+        let hms: HoursMinutesSeconds = "18h 36m 56.3s".parse().unwrap();
+        assert_eq!((hms.hours, hms.minutes), (18, 36));
+        assert_approx_eq!(hms.seconds, 56.3);
+        let hms: HoursMinutesSeconds = "7:45:18.946".parse().unwrap();
+        assert_approx_eq!(hms.as_angle().as_degrees(), 116.328_942);
+        assert_eq!(
+            "12°34′".parse::<HoursMinutesSeconds>(),
+            Err(ParseAngleError::WrongUnit)
         );
     }
 
@@ -328,6 +1409,7 @@ mod tests {
     fn dms_conversions() {
         assert_eq!(
             DegreesMinutesSeconds {
+                sign: Sign::Positive,
                 degrees: 0,
                 minutes: 0,
                 seconds: 0.0
@@ -337,6 +1419,7 @@ mod tests {
         );
         assert_eq!(
             DegreesMinutesSeconds {
+                sign: Sign::Positive,
                 degrees: 1,
                 minutes: 0,
                 seconds: 0.0
@@ -346,34 +1429,116 @@ mod tests {
         );
         assert_eq!(
             DegreesMinutesSeconds {
+                sign: Sign::Positive,
                 degrees: 0,
                 minutes: 1,
                 seconds: 0.0
             },
-            DegreesMinutesSeconds::from_angle(Angle::from_degrees(0.01666666666666666667))
+            DegreesMinutesSeconds::from_angle(Angle::from_degrees(0.016_666_666_666_666_666))
         );
         assert_eq!(
             DegreesMinutesSeconds {
+                sign: Sign::Positive,
                 degrees: 0,
                 minutes: 0,
                 seconds: 1.0
             },
-            DegreesMinutesSeconds::from_angle(Angle::from_degrees(0.00027777777777777778))
+            DegreesMinutesSeconds::from_angle(Angle::from_degrees(0.000_277_777_777_777_777_8))
         );
         assert_eq!(
             DegreesMinutesSeconds {
+                sign: Sign::Positive,
                 degrees: 34,
                 minutes: 55,
                 seconds: 25.543635299987955
             },
-            Angle::from_degrees(34.92376212091666666667).as_dms()
+            Angle::from_degrees(34.923_762_120_916_66).as_dms()
         );
     }
 
+    #[test]
+    fn negative_sexagesimal() {
+        let dms = Angle::from_degrees(-0.5).as_dms();
+        assert_eq!(dms.sign, Sign::Negative);
+        assert_eq!((dms.degrees, dms.minutes), (0, 30));
+        assert_approx_eq!(dms.seconds, 0.0, 1e-9);
+        assert_approx_eq!(dms.as_angle().as_degrees(), -0.5, 1e-12);
+        assert_eq!(dms.to_string(), "-0°30′0.000″");
+
+        let dms = DegreesMinutesSeconds {
+            sign: Sign::Negative,
+            degrees: 12,
+            minutes: 30,
+            seconds: 0.0,
+        };
+        assert_approx_eq!(dms.as_angle().as_degrees(), -12.5, 1e-12);
+        assert!(dms < Angle::from_degrees(-12.0).as_dms());
+
+        let parsed: DegreesMinutesSeconds = "-0°30′".parse().unwrap();
+        assert_eq!(parsed.sign, Sign::Negative);
+        assert_approx_eq!(parsed.as_angle().as_degrees(), -0.5, 1e-12);
+        let parsed: HoursMinutesSeconds = "-0h30m".parse().unwrap();
+        assert_approx_eq!(parsed.as_angle().as_degrees(), -7.5, 1e-12);
+    }
+
+    #[test]
+    fn validated_construction() {
+        let dms = DegreesMinutesSeconds::new(Sign::Negative, 12, 34, 56.7).unwrap();
+        assert_approx_eq!(
+            dms.as_angle().as_degrees(),
+            -(12.0 + 34.0 / 60.0 + 56.7 / 3600.0),
+            1e-12
+        );
+        assert_eq!(
+            DegreesMinutesSeconds::new(Sign::Positive, 12, 60, 0.0),
+            Err(SexagesimalError::InvalidMinutes(60))
+        );
+        assert_eq!(
+            HoursMinutesSeconds::new(Sign::Positive, 1, 2, 60.0),
+            Err(SexagesimalError::InvalidSeconds(60.0))
+        );
+        assert_eq!(
+            HoursMinutesSeconds::new(Sign::Positive, 1, 2, -0.5),
+            Err(SexagesimalError::InvalidSeconds(-0.5))
+        );
+        assert!(DegreesMinutesSeconds::new(Sign::Positive, 1, 2, f64::NAN).is_err());
+
+        let dms = DegreesMinutesSeconds::from_arcseconds(-3725.5);
+        assert_eq!(dms.sign, Sign::Negative);
+        assert_eq!((dms.degrees, dms.minutes), (1, 2));
+        assert_approx_eq!(dms.seconds, 5.5, 1e-9);
+
+        let hms = HoursMinutesSeconds::from_seconds(86_399.0);
+        assert_eq!((hms.hours, hms.minutes), (23, 59));
+        assert_approx_eq!(hms.seconds, 59.0, 1e-9);
+        assert_approx_eq!(hms.as_angle().as_degrees(), 360.0 - 15.0 / 3600.0, 1e-9);
+    }
+
+    #[test]
+    fn rounding_carries() {
+        let dms = Angle::from_degrees(13.0 - 1e-9).as_dms();
+        assert_eq!((dms.degrees, dms.minutes), (12, 59));
+        let rounded = dms.rounded(3);
+        assert_eq!((rounded.degrees, rounded.minutes), (13, 0));
+        assert_eq!(rounded.seconds, 0.0);
+        assert_eq!(dms.to_string(), "13°0′0.000″");
+        assert_eq!(format!("{:.1}", dms), "13°0′0.0″");
+        assert_eq!(format!("{:.6}", dms), "12°59′59.999996″");
+
+        let dms = Angle::from_degrees(-(12.0 + 29.0 / 60.0 + 59.96 / 3600.0)).as_dms();
+        assert_eq!(format!("{:.1}", dms), "-12°30′0.0″");
+        assert_eq!(format!("{:.2}", dms), "-12°29′59.96″");
+
+        let hms = Angle::from_hours(24.0 - 1e-9).as_hms();
+        assert_eq!(format!("{:.0}", hms), "24ʰ0ᵐ0ˢ");
+        assert_eq!(hms.rounded(2).as_angle(), Angle::from_hours(24.0));
+    }
+
     #[test]
     fn hms_conversions() {
         assert_approx_eq!(
             HoursMinutesSeconds {
+                sign: Sign::Positive,
                 hours: 7,
                 minutes: 45,
                 seconds: 18.946
@@ -384,10 +1549,12 @@ mod tests {
         );
         let angle = HoursMinutesSeconds::from_angle(Angle::from_degrees(-295.647_867));
         let hms = HoursMinutesSeconds {
-            hours: -19,
+            sign: Sign::Negative,
+            hours: 19,
             minutes: 42,
             seconds: 35.488,
         };
+        assert_eq!(angle.sign, hms.sign);
         assert_eq!(angle.hours, hms.hours);
         assert_eq!(angle.minutes, hms.minutes);
         assert_approx_eq!(angle.seconds, hms.seconds, 1e-4);
@@ -0,0 +1,213 @@
+//! Comparisons of angles and coordinates with the `approx` crate, enabled by the `approx` feature.
+//!
+//! Angles are compared in radians, so the tolerances are given in radians too. The radius of
+//! `HeliocentricSpherical` is compared with the same tolerance, in astronomical units.
+use crate::angle::Angle;
+use crate::coords::{Ecliptical, Equatorial, Equinox, HeliocentricSpherical};
+
+use ::approx::{AbsDiffEq, RelativeEq, UlpsEq};
+
+impl AbsDiffEq for Angle {
+    type Epsilon = f64;
+
+    fn default_epsilon() -> f64 {
+        f64::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.as_radians().abs_diff_eq(&other.as_radians(), epsilon)
+    }
+}
+
+impl RelativeEq for Angle {
+    fn default_max_relative() -> f64 {
+        f64::default_max_relative()
+    }
+
+    fn relative_eq(&self, other: &Self, epsilon: f64, max_relative: f64) -> bool {
+        self.as_radians()
+            .relative_eq(&other.as_radians(), epsilon, max_relative)
+    }
+}
+
+impl UlpsEq for Angle {
+    fn default_max_ulps() -> u32 {
+        f64::default_max_ulps()
+    }
+
+    fn ulps_eq(&self, other: &Self, epsilon: f64, max_ulps: u32) -> bool {
+        self.as_radians()
+            .ulps_eq(&other.as_radians(), epsilon, max_ulps)
+    }
+}
+
+impl<E: Equinox + PartialEq> AbsDiffEq for Equatorial<E> {
+    type Epsilon = f64;
+
+    fn default_epsilon() -> f64 {
+        f64::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.ra().angle().abs_diff_eq(&other.ra().angle(), epsilon)
+            && self
+                .dec()
+                .angle()
+                .abs_diff_eq(&other.dec().angle(), epsilon)
+    }
+}
+
+impl<E: Equinox + PartialEq> RelativeEq for Equatorial<E> {
+    fn default_max_relative() -> f64 {
+        f64::default_max_relative()
+    }
+
+    fn relative_eq(&self, other: &Self, epsilon: f64, max_relative: f64) -> bool {
+        self.ra()
+            .angle()
+            .relative_eq(&other.ra().angle(), epsilon, max_relative)
+            && self
+                .dec()
+                .angle()
+                .relative_eq(&other.dec().angle(), epsilon, max_relative)
+    }
+}
+
+impl<E: Equinox + PartialEq> UlpsEq for Equatorial<E> {
+    fn default_max_ulps() -> u32 {
+        f64::default_max_ulps()
+    }
+
+    fn ulps_eq(&self, other: &Self, epsilon: f64, max_ulps: u32) -> bool {
+        self.ra()
+            .angle()
+            .ulps_eq(&other.ra().angle(), epsilon, max_ulps)
+            && self
+                .dec()
+                .angle()
+                .ulps_eq(&other.dec().angle(), epsilon, max_ulps)
+    }
+}
+
+impl<E: Equinox + PartialEq> AbsDiffEq for Ecliptical<E> {
+    type Epsilon = f64;
+
+    fn default_epsilon() -> f64 {
+        f64::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.lon()
+            .angle()
+            .abs_diff_eq(&other.lon().angle(), epsilon)
+            && self
+                .lat()
+                .angle()
+                .abs_diff_eq(&other.lat().angle(), epsilon)
+    }
+}
+
+impl<E: Equinox + PartialEq> RelativeEq for Ecliptical<E> {
+    fn default_max_relative() -> f64 {
+        f64::default_max_relative()
+    }
+
+    fn relative_eq(&self, other: &Self, epsilon: f64, max_relative: f64) -> bool {
+        self.lon()
+            .angle()
+            .relative_eq(&other.lon().angle(), epsilon, max_relative)
+            && self
+                .lat()
+                .angle()
+                .relative_eq(&other.lat().angle(), epsilon, max_relative)
+    }
+}
+
+impl<E: Equinox + PartialEq> UlpsEq for Ecliptical<E> {
+    fn default_max_ulps() -> u32 {
+        f64::default_max_ulps()
+    }
+
+    fn ulps_eq(&self, other: &Self, epsilon: f64, max_ulps: u32) -> bool {
+        self.lon()
+            .angle()
+            .ulps_eq(&other.lon().angle(), epsilon, max_ulps)
+            && self
+                .lat()
+                .angle()
+                .ulps_eq(&other.lat().angle(), epsilon, max_ulps)
+    }
+}
+
+impl AbsDiffEq for HeliocentricSpherical {
+    type Epsilon = f64;
+
+    fn default_epsilon() -> f64 {
+        f64::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.latitude.abs_diff_eq(&other.latitude, epsilon)
+            && self.longitude.abs_diff_eq(&other.longitude, epsilon)
+            && self.radius.abs_diff_eq(&other.radius, epsilon)
+    }
+}
+
+impl RelativeEq for HeliocentricSpherical {
+    fn default_max_relative() -> f64 {
+        f64::default_max_relative()
+    }
+
+    fn relative_eq(&self, other: &Self, epsilon: f64, max_relative: f64) -> bool {
+        self.latitude
+            .relative_eq(&other.latitude, epsilon, max_relative)
+            && self
+                .longitude
+                .relative_eq(&other.longitude, epsilon, max_relative)
+            && self
+                .radius
+                .relative_eq(&other.radius, epsilon, max_relative)
+    }
+}
+
+impl UlpsEq for HeliocentricSpherical {
+    fn default_max_ulps() -> u32 {
+        f64::default_max_ulps()
+    }
+
+    fn ulps_eq(&self, other: &Self, epsilon: f64, max_ulps: u32) -> bool {
+        self.latitude.ulps_eq(&other.latitude, epsilon, max_ulps)
+            && self.longitude.ulps_eq(&other.longitude, epsilon, max_ulps)
+            && self.radius.ulps_eq(&other.radius, epsilon, max_ulps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coords::J2000;
+    use ::approx::{assert_abs_diff_eq, assert_relative_eq, assert_ulps_ne};
+
+    #[test]
+    fn angles() {
+        let a = Angle::from_degrees(10.0);
+        let b = Angle::from_degrees(10.0 + 1e-9);
+        assert_abs_diff_eq!(a, b, epsilon = 1e-10);
+        assert_relative_eq!(a, b, max_relative = 1e-9);
+        assert_ulps_ne!(a, b);
+        assert!(a.abs_diff_ne(&b, 1e-12));
+    }
+
+    #[test]
+    fn coordinates() {
+        let a =
+            Equatorial::<J2000>::new(Angle::from_degrees(10.0), Angle::from_degrees(-5.0)).unwrap();
+        let b = Equatorial::<J2000>::new(Angle::from_degrees(10.0), Angle::from_degrees(-5.001))
+            .unwrap();
+        assert_abs_diff_eq!(a, b, epsilon = Angle::from_degrees(0.002).as_radians());
+        assert!(a.abs_diff_ne(&b, Angle::from_degrees(0.0005).as_radians()));
+
+        let a = a.to_ecliptical();
+        assert_relative_eq!(a, a.to_equatorial().to_ecliptical(), epsilon = 1e-12);
+    }
+}
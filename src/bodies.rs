@@ -0,0 +1,56 @@
+//! The bodies of the solar system whose apparent positions can be computed.
+use crate::coords::J2000;
+use crate::moon;
+use crate::planets::{Aberration, GeocentricPosition, Planet, LIGHT_TIME_PER_AU};
+use crate::sun;
+use crate::time::JD;
+
+/// A body of the solar system, as seen from the Earth.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Body {
+    Sun,
+    Moon,
+    Planet(Planet),
+}
+
+impl Body {
+    /// Computes the position of the body as seen from the center of the Earth, for the J2000.0
+    /// equinox. See `Planet::get_geocentric_position` for the meaning of `aberration`.
+    ///
+    /// The Moon travels around the Sun with the Earth, so only the light-time is applied to it
+    /// whichever correction is asked for. The annual aberration doesn't apply to it.
+    pub fn get_geocentric_position(&self, t: &JD, aberration: Aberration) -> GeocentricPosition {
+        match self {
+            Body::Sun => sun::get_geocentric_position(t, aberration),
+            Body::Moon => moon_position(t, aberration),
+            Body::Planet(planet) => planet.get_geocentric_position(t, aberration),
+        }
+    }
+}
+
+impl From<Planet> for Body {
+    fn from(item: Planet) -> Self {
+        Body::Planet(item)
+    }
+}
+
+/// The position of the Moon from chapter 47, precessed to the equinox of J2000.0 and, unless no
+/// correction is asked for, taken at the moment the light left the Moon
+fn moon_position(t: &JD, aberration: Aberration) -> GeocentricPosition {
+    let light_time = match aberration {
+        Aberration::None => 0.0,
+        Aberration::Stellar | Aberration::Planetary => {
+            moon::geocentric_position(t).distance * LIGHT_TIME_PER_AU
+        }
+    };
+    let position = moon::geocentric_position(&(*t - light_time));
+    GeocentricPosition {
+        ecliptical: position
+            .direction
+            .to_equatorial()
+            .to_equinox(J2000 {})
+            .to_ecliptical(),
+        distance: position.distance,
+        light_time,
+    }
+}
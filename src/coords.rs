@@ -1,111 +1,1592 @@
 //! Coordinates are how you represent a location in a reference frame.
 //!
 //! This module implements several coordinate types that are commonly used in astronomy.
-use crate::angle::Angle;
+use crate::angle::{self, Angle};
+use crate::frames;
+use crate::linalg::{Mat3, Vec3};
+use crate::nutation;
+use crate::observer::{self, Observer};
+use crate::planets;
+use crate::precession;
+use crate::time::{JD, UT};
+use crate::METERS_PER_AU;
+use std::convert::TryFrom;
+use std::fmt;
 
-pub trait Equinox {
-    const OBLIQUITY: f64;
+/// The equinox, and the mean equator of the same epoch, that coordinates are referred to.
+///
+/// The standard equinoxes are empty types, so coordinates referred to them take no extra space
+/// and mixing them up is caught at compile time. `MeanOfDate` and `TrueOfDate` carry their epoch
+/// at runtime instead, for coordinates referred to an equinox that is only known while running.
+pub trait Equinox: Copy {
+    /// The epoch of the equinox, as a Julian Ephemeris Day
+    fn epoch(&self) -> JD;
+
+    /// The obliquity of the ecliptic at the epoch of the equinox, the angle between the equator
+    /// and the ecliptic the coordinates are referred to
+    fn obliquity(&self) -> Angle;
+
+    /// The rotation of rectangular coordinates referred to this equinox into the mean equator and
+    /// equinox of its epoch. This is the identity for the mean equinoxes.
+    fn mean_rotation(&self) -> Mat3 {
+        Mat3::identity()
+    }
+
+    /// The difference between the sidereal time measured from this equinox and the mean sidereal
+    /// time. This is zero for the mean equinoxes.
+    fn equation_of_the_equinoxes(&self) -> Angle {
+        Angle::ZERO
+    }
 }
 
+#[derive(Debug, Default, Copy, Clone, PartialEq, PartialOrd)]
 pub struct J2000 {}
+
+impl J2000 {
+    /// The mean obliquity of the ecliptic at J2000.0, in radians
+    pub const OBLIQUITY: f64 = 0.409_092_804_028_403_5;
+}
+
 impl Equinox for J2000 {
-    const OBLIQUITY: f64 = 0.40909280402840346503;
+    fn epoch(&self) -> JD {
+        JD::J2000
+    }
+
+    fn obliquity(&self) -> Angle {
+        Angle::from_radians(J2000::OBLIQUITY)
+    }
 }
 
+#[derive(Debug, Default, Copy, Clone, PartialEq, PartialOrd)]
 pub struct B1950 {}
+
+impl B1950 {
+    /// The mean obliquity of the ecliptic at B1950.0, in radians
+    pub const OBLIQUITY: f64 = 0.409_206_212_032_539_54;
+}
+
 impl Equinox for B1950 {
-    const OBLIQUITY: f64 = 0.40920621203253955258;
+    fn epoch(&self) -> JD {
+        JD::from(2_433_282.423_5)
+    }
+
+    fn obliquity(&self) -> Angle {
+        Angle::from_radians(B1950::OBLIQUITY)
+    }
+}
+
+/// The International Celestial Reference System, which modern catalogs such as Hipparcos and Gaia
+/// are referred to.
+///
+/// Its axes are within a few tens of milliarcseconds of the mean equator and equinox of J2000, so
+/// it shares the epoch and obliquity of `J2000`. The small frame bias between them is applied by
+/// `to_j2000` and `to_icrs`, as well as by `to_equinox`.
+#[derive(Debug, Default, Copy, Clone, PartialEq, PartialOrd)]
+pub struct ICRS {}
+
+impl Equinox for ICRS {
+    fn epoch(&self) -> JD {
+        JD::J2000
+    }
+
+    fn obliquity(&self) -> Angle {
+        Angle::from_radians(J2000::OBLIQUITY)
+    }
+
+    fn mean_rotation(&self) -> Mat3 {
+        frames::frame_bias()
+    }
+}
+
+/// The mean equator and equinox of the given epoch, a Julian Ephemeris Day.
+///
+/// Apparent places are referred to the equinox of the date of the observation, which is what
+/// this is for. The obliquity is computed with `precession::mean_obliquity`.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct MeanOfDate(pub JD);
+
+impl Equinox for MeanOfDate {
+    fn epoch(&self) -> JD {
+        self.0
+    }
+
+    fn obliquity(&self) -> Angle {
+        precession::mean_obliquity(&self.0)
+    }
+}
+
+/// The true equator and equinox of the given epoch, a Julian Ephemeris Day.
+///
+/// These differ from the mean ones by the nutation, and are what the apparent places published in
+/// almanacs are referred to. The obliquity is the true one, so ecliptical coordinates are referred
+/// to the true equinox too.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct TrueOfDate(pub JD);
+
+impl Equinox for TrueOfDate {
+    fn epoch(&self) -> JD {
+        self.0
+    }
+
+    fn obliquity(&self) -> Angle {
+        nutation::true_obliquity(&self.0)
+    }
+
+    fn mean_rotation(&self) -> Mat3 {
+        nutation::matrix(&self.0).transpose()
+    }
+
+    fn equation_of_the_equinoxes(&self) -> Angle {
+        nutation::equation_of_the_equinoxes(&self.0)
+    }
+}
+
+/// An equinox chosen at runtime, for applications that only know which one they need while
+/// running, such as from a configuration file.
+///
+/// Coordinates referred to it behave like the ones referred to the matching equinox type.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum EquinoxValue {
+    J2000,
+    B1950,
+    ICRS,
+    /// The mean equator and equinox of the given Julian Ephemeris Day
+    MeanOfDate(JD),
+    /// The true equator and equinox of the given Julian Ephemeris Day
+    TrueOfDate(JD),
+}
+
+/// Calls a method of the `Equinox` trait on the equinox type matching an `EquinoxValue`
+macro_rules! forward_equinox {
+    ($value:expr, $method:ident) => {
+        match $value {
+            EquinoxValue::J2000 => J2000 {}.$method(),
+            EquinoxValue::B1950 => B1950 {}.$method(),
+            EquinoxValue::ICRS => ICRS {}.$method(),
+            EquinoxValue::MeanOfDate(t) => MeanOfDate(*t).$method(),
+            EquinoxValue::TrueOfDate(t) => TrueOfDate(*t).$method(),
+        }
+    };
+}
+
+impl Equinox for EquinoxValue {
+    fn epoch(&self) -> JD {
+        forward_equinox!(self, epoch)
+    }
+
+    fn obliquity(&self) -> Angle {
+        forward_equinox!(self, obliquity)
+    }
+
+    fn mean_rotation(&self) -> Mat3 {
+        forward_equinox!(self, mean_rotation)
+    }
+
+    fn equation_of_the_equinoxes(&self) -> Angle {
+        forward_equinox!(self, equation_of_the_equinoxes)
+    }
+}
+
+impl From<J2000> for EquinoxValue {
+    fn from(_: J2000) -> Self {
+        EquinoxValue::J2000
+    }
+}
+
+impl From<B1950> for EquinoxValue {
+    fn from(_: B1950) -> Self {
+        EquinoxValue::B1950
+    }
+}
+
+impl From<ICRS> for EquinoxValue {
+    fn from(_: ICRS) -> Self {
+        EquinoxValue::ICRS
+    }
+}
+
+impl From<MeanOfDate> for EquinoxValue {
+    fn from(item: MeanOfDate) -> Self {
+        EquinoxValue::MeanOfDate(item.0)
+    }
+}
+
+impl From<TrueOfDate> for EquinoxValue {
+    fn from(item: TrueOfDate) -> Self {
+        EquinoxValue::TrueOfDate(item.0)
+    }
+}
+
+/// The error returned when an angle isn't a valid value of a coordinate, because it is NaN,
+/// infinite or out of the range of the coordinate.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InvalidCoordinateError;
+
+impl fmt::Display for InvalidCoordinateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the angle is not a finite value within the range of the coordinate"
+        )
+    }
+}
+
+impl std::error::Error for InvalidCoordinateError {}
+
+macro_rules! circular_coordinate {
+    ($(#[$doc:meta])* $name:ident) => {
+        $(#[$doc])*
+        ///
+        /// The angle is normalized into the range [0°, 360°) when it is built.
+        #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+        #[cfg_attr(
+            feature = "serde",
+            derive(serde::Serialize, serde::Deserialize),
+            serde(into = "Angle", try_from = "Angle")
+        )]
+        pub struct $name(Angle);
+
+        impl $name {
+            /// Returns `None` if the angle is NaN or infinite
+            pub fn new(angle: Angle) -> Option<$name> {
+                if angle.as_radians().is_finite() {
+                    Some($name(angle.normalize_0_360()))
+                } else {
+                    None
+                }
+            }
+
+            pub fn angle(&self) -> Angle {
+                self.0
+            }
+
+            /// Normalizes a computed value, keeping a NaN coming out of a time or vector that
+            /// wasn't finite
+            fn computed(angle: Angle) -> $name {
+                $name::new(angle).unwrap_or($name(angle))
+            }
+        }
+
+        impl From<$name> for Angle {
+            fn from(item: $name) -> Angle {
+                item.0
+            }
+        }
+
+        impl TryFrom<Angle> for $name {
+            type Error = InvalidCoordinateError;
+
+            fn try_from(angle: Angle) -> Result<$name, InvalidCoordinateError> {
+                $name::new(angle).ok_or(InvalidCoordinateError)
+            }
+        }
+    };
+}
+
+macro_rules! polar_coordinate {
+    ($(#[$doc:meta])* $name:ident) => {
+        $(#[$doc])*
+        ///
+        /// The angle must be within the range [-90°, 90°].
+        #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+        #[cfg_attr(
+            feature = "serde",
+            derive(serde::Serialize, serde::Deserialize),
+            serde(into = "Angle", try_from = "Angle")
+        )]
+        pub struct $name(Angle);
+
+        impl $name {
+            /// Returns `None` if the angle is NaN or outside of [-90°, 90°]
+            pub fn new(angle: Angle) -> Option<$name> {
+                if angle.abs() <= Angle::RIGHT_ANGLE {
+                    Some($name(angle))
+                } else {
+                    None
+                }
+            }
+
+            pub fn angle(&self) -> Angle {
+                self.0
+            }
+
+            /// Clamps a slightly out of range value, such as one coming out of a rounding error.
+            /// Returns `None` if the angle is NaN or infinite.
+            fn clamped(angle: Angle) -> Option<$name> {
+                if angle.as_radians().is_finite() {
+                    Some($name(angle.clamp(-Angle::RIGHT_ANGLE, Angle::RIGHT_ANGLE)))
+                } else {
+                    None
+                }
+            }
+
+            /// Clamps a computed value, keeping a NaN coming out of a time or vector that wasn't
+            /// finite
+            fn computed(angle: Angle) -> $name {
+                $name::clamped(angle).unwrap_or($name(angle))
+            }
+        }
+
+        impl From<$name> for Angle {
+            fn from(item: $name) -> Angle {
+                item.0
+            }
+        }
+
+        impl TryFrom<Angle> for $name {
+            type Error = InvalidCoordinateError;
+
+            fn try_from(angle: Angle) -> Result<$name, InvalidCoordinateError> {
+                $name::new(angle).ok_or(InvalidCoordinateError)
+            }
+        }
+    };
+}
+
+circular_coordinate!(
+    /// A right ascension, measured eastwards along the celestial equator from the equinox
+    RightAscension
+);
+polar_coordinate!(
+    /// A declination, measured from the celestial equator, positive towards the north
+    Declination
+);
+circular_coordinate!(
+    /// An ecliptic longitude, measured along the ecliptic from the equinox
+    EclipticLongitude
+);
+polar_coordinate!(
+    /// An ecliptic latitude, measured from the ecliptic, positive towards the north
+    EclipticLatitude
+);
+
+/// Writes a pair of labelled sexagesimal components. The precision of the formatter applies to the
+/// seconds of both, otherwise each uses its default number of decimals.
+fn write_components(
+    f: &mut fmt::Formatter<'_>,
+    first: (&str, &dyn fmt::Display, usize),
+    second: (&str, &dyn fmt::Display, usize),
+) -> fmt::Result {
+    let (label, value, decimals) = first;
+    let decimals = f.precision().unwrap_or(decimals);
+    write!(f, "{} {:.*}", label, decimals, value)?;
+    let (label, value, decimals) = second;
+    let decimals = f.precision().unwrap_or(decimals);
+    write!(f, " {} {:.*}", label, decimals, value)
 }
 
 /// Spherical coordinates centered on the sun, relative to the J2000.0 epoch.
 /// The radius is in units of astronomical units (i.e. 149597870700 meters)
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HeliocentricSpherical {
     pub latitude: Angle,
     pub longitude: Angle,
     pub radius: f64,
 }
 
+/// Rectangular coordinates centered on the sun, with the x axis towards the equinox and the z
+/// axis towards the north pole of the ecliptic. The coordinates are in astronomical units.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HeliocentricRectangular {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl HeliocentricRectangular {
+    pub fn new(x: f64, y: f64, z: f64) -> HeliocentricRectangular {
+        HeliocentricRectangular { x, y, z }
+    }
+
+    /// The distance from the sun, in astronomical units
+    pub fn radius(&self) -> f64 {
+        Vec3::from(*self).norm()
+    }
+
+    /// The distance to another position, in astronomical units
+    pub fn distance_to(&self, other: &HeliocentricRectangular) -> f64 {
+        (Vec3::from(*other) - Vec3::from(*self)).norm()
+    }
+}
+
+impl From<HeliocentricSpherical> for HeliocentricRectangular {
+    fn from(item: HeliocentricSpherical) -> Self {
+        let (sin_b, cos_b) = item.latitude.sin_cos();
+        let (sin_l, cos_l) = item.longitude.sin_cos();
+        HeliocentricRectangular {
+            x: item.radius * cos_b * cos_l,
+            y: item.radius * cos_b * sin_l,
+            z: item.radius * sin_b,
+        }
+    }
+}
+
+impl From<HeliocentricRectangular> for HeliocentricSpherical {
+    fn from(item: HeliocentricRectangular) -> Self {
+        HeliocentricSpherical {
+            longitude: Angle::atan2(item.y, item.x).normalize_0_360(),
+            latitude: Angle::atan2(item.z, item.x.hypot(item.y)),
+            radius: item.radius(),
+        }
+    }
+}
+
+impl fmt::Display for HeliocentricSpherical {
+    /// Writes the longitude and latitude in degrees, minutes and seconds, and the radius in
+    /// astronomical units
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_components(
+            f,
+            ("L", &self.longitude.normalize_0_360().as_dms(), 2),
+            ("B", &self.latitude.as_dms(), 2),
+        )?;
+        write!(f, " R {:.6} AU", self.radius)
+    }
+}
+
+impl From<HeliocentricRectangular> for Vec3 {
+    fn from(item: HeliocentricRectangular) -> Self {
+        Vec3::new(item.x, item.y, item.z)
+    }
+}
+
+impl From<Vec3> for HeliocentricRectangular {
+    fn from(item: Vec3) -> Self {
+        HeliocentricRectangular::new(item.x, item.y, item.z)
+    }
+}
+
+/// A direction in equatorial coordinates, referred to the equinox `E`.
+///
+/// The right ascension and declination are checked when the coordinates are built from angles,
+/// so they are always finite and in range. Only a computation from a time or vector that isn't
+/// finite, such as the position of a body at a NaN instant, gives NaN coordinates.
 #[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Equatorial<E: Equinox> {
-    pub right_ascention: Angle,
-    pub declination: Angle,
-    phantom: std::marker::PhantomData<E>,
+    right_ascension: RightAscension,
+    declination: Declination,
+    equinox: E,
+}
+
+impl<E> Equatorial<E>
+where
+    E: Equinox + Default,
+{
+    /// Returns `None` if either angle is NaN or infinite, or the declination is outside of
+    /// [-90°, 90°]. The right ascension is normalized into [0°, 360°).
+    pub fn new(right_ascension: Angle, declination: Angle) -> Option<Equatorial<E>> {
+        Equatorial::with_equinox(right_ascension, declination, E::default())
+    }
+
+    /// Builds the coordinates from typed components, which can't be swapped by mistake
+    pub fn from_typed(right_ascension: RightAscension, declination: Declination) -> Equatorial<E> {
+        Equatorial {
+            right_ascension,
+            declination,
+            equinox: E::default(),
+        }
+    }
+
+    /// Builds the coordinates from a rectangular vector pointing in the same direction. The length
+    /// of the vector doesn't matter.
+    ///
+    /// Returns `None` if the vector isn't finite.
+    pub fn from_rectangular(v: &Vec3) -> Option<Equatorial<E>> {
+        Equatorial::from_rectangular_with_equinox(v, E::default())
+    }
+
+    /// Builds the coordinates from a vector computed from other coordinates
+    pub(crate) fn from_vector(v: &Vec3) -> Equatorial<E> {
+        Equatorial::from_vector_with_equinox(v, E::default())
+    }
+
+    /// Builds the coordinates from angles computed from other coordinates
+    pub(crate) fn from_angles(right_ascension: Angle, declination: Angle) -> Equatorial<E> {
+        Equatorial::from_angles_with_equinox(right_ascension, declination, E::default())
+    }
+}
+
+impl Equatorial<ICRS> {
+    /// Rotates the coordinates into the dynamical mean equator and equinox of J2000, applying the
+    /// frame bias
+    pub fn to_j2000(&self) -> Equatorial<J2000> {
+        Equatorial::from_vector(&frames::icrs_to_j2000(&self.to_rectangular()))
+    }
+}
+
+impl Equatorial<J2000> {
+    /// Rotates the coordinates into the ICRS, removing the frame bias
+    pub fn to_icrs(&self) -> Equatorial<ICRS> {
+        Equatorial::from_vector(&frames::j2000_to_icrs(&self.to_rectangular()))
+    }
+
+    /// Precesses the coordinates back to the mean equinox of B1950.
+    ///
+    /// Only the precession is accounted for. To compare with a catalog in the older FK4 system use
+    /// `frames::fk5_to_fk4`, which also adds the E-terms of aberration and corrects the equinox.
+    pub fn to_b1950(&self) -> Equatorial<B1950> {
+        self.to_equinox(B1950 {})
+    }
+}
+
+impl Equatorial<B1950> {
+    /// Precesses the coordinates to the mean equinox of J2000.
+    ///
+    /// Only the precession is accounted for. Positions from an FK4 catalog should be converted
+    /// with `frames::fk4_to_fk5` instead.
+    pub fn to_j2000(&self) -> Equatorial<J2000> {
+        self.to_equinox(J2000 {})
+    }
+}
+
+impl Equatorial<MeanOfDate> {
+    /// Builds coordinates referred to the mean equator and equinox of `epoch`. Returns `None` for
+    /// the same angles as `new`.
+    pub fn mean_of_date(
+        right_ascension: Angle,
+        declination: Angle,
+        epoch: &JD,
+    ) -> Option<Equatorial<MeanOfDate>> {
+        Equatorial::with_equinox(right_ascension, declination, MeanOfDate(*epoch))
+    }
 }
 
 impl<E> Equatorial<E>
 where
     E: Equinox,
 {
-    pub fn to_ecliptical(&self) -> Ecliptical<E> {
-        Ecliptical {
-            longitude: Angle::atan2(
-                self.right_ascention.sin() * E::OBLIQUITY.cos()
-                    + self.declination.tan() * E::OBLIQUITY.sin(),
-                self.right_ascention.cos(),
-            ),
-            latitude: Angle::asin(
-                self.declination.sin() * E::OBLIQUITY.cos()
-                    - self.declination.cos() * E::OBLIQUITY.sin() * self.right_ascention.sin(),
-            ),
-            phantom: std::marker::PhantomData,
+    /// Builds coordinates referred to the given equinox. Returns `None` for the same angles as
+    /// `new`.
+    pub fn with_equinox(
+        right_ascension: Angle,
+        declination: Angle,
+        equinox: E,
+    ) -> Option<Equatorial<E>> {
+        Some(Equatorial {
+            right_ascension: RightAscension::new(right_ascension)?,
+            declination: Declination::new(declination)?,
+            equinox,
+        })
+    }
+
+    /// Builds coordinates referred to the given equinox from a rectangular vector pointing in the
+    /// same direction. Returns `None` if the vector isn't finite.
+    pub fn from_rectangular_with_equinox(v: &Vec3, equinox: E) -> Option<Equatorial<E>> {
+        if v.x.is_finite() && v.y.is_finite() && v.z.is_finite() {
+            Some(Equatorial::from_vector_with_equinox(v, equinox))
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn from_vector_with_equinox(v: &Vec3, equinox: E) -> Equatorial<E> {
+        Equatorial::from_angles_with_equinox(
+            Angle::atan2(v.y, v.x),
+            Angle::atan2(v.z, (v.x * v.x + v.y * v.y).sqrt()),
+            equinox,
+        )
+    }
+
+    /// Builds coordinates from angles computed from other coordinates, which can only be out of
+    /// range through rounding errors
+    pub(crate) fn from_angles_with_equinox(
+        right_ascension: Angle,
+        declination: Angle,
+        equinox: E,
+    ) -> Equatorial<E> {
+        Equatorial {
+            right_ascension: RightAscension::computed(right_ascension),
+            declination: Declination::computed(declination),
+            equinox,
         }
     }
+
+    /// The equinox the coordinates are referred to
+    pub fn equinox(&self) -> E {
+        self.equinox
+    }
+
+    /// Precesses the coordinates to another equinox, with the rigorous rotation of chapter 21.
+    /// The nutation is added or removed when going to or from a true equinox, and the frame bias
+    /// when going to or from the ICRS.
+    ///
+    /// Only the rotation of the frame is accounted for, the proper motion of a star has to be
+    /// applied separately.
+    pub fn to_equinox<T: Equinox>(&self, equinox: T) -> Equatorial<T> {
+        let p = precession::matrix(&self.equinox.epoch(), &equinox.epoch());
+        let m = equinox.mean_rotation().transpose() * p * self.equinox.mean_rotation();
+        Equatorial::from_vector_with_equinox(&(m * self.to_rectangular()), equinox)
+    }
+
+    /// Precesses the coordinates from their equinox to the mean equinox of `to`, a Julian
+    /// Ephemeris Day, with the angles ζ, z and θ of formula 21.4.
+    ///
+    /// The coordinates are first referred to the mean equinox of the epoch of their own equinox,
+    /// removing the nutation or the frame bias, so this gives the same result as `to_equinox`.
+    pub fn precess(&self, to: &JD) -> Equatorial<MeanOfDate> {
+        let from = self.equinox.epoch();
+        let mean = self.to_equinox(MeanOfDate(from));
+        let angles = precession::PrecessionAngles::new(&from, to);
+        let (sin_ra, cos_ra) = (mean.right_ascension.angle() + angles.zeta).sin_cos();
+        let (sin_dec, cos_dec) = mean.declination.angle().sin_cos();
+        let (sin_theta, cos_theta) = angles.theta.sin_cos();
+
+        let a = cos_dec * sin_ra;
+        let b = cos_theta * cos_dec * cos_ra - sin_theta * sin_dec;
+        let c = sin_theta * cos_dec * cos_ra + cos_theta * sin_dec;
+
+        // Taking the declination from its tangent keeps the precision close to the poles
+        Equatorial::from_angles_with_equinox(
+            Angle::atan2(a, b) + angles.z,
+            Angle::atan2(c, (a * a + b * b).sqrt()),
+            MeanOfDate(*to),
+        )
+    }
+
+    /// The right ascension, in the range [0°, 360°)
+    pub fn ra(&self) -> RightAscension {
+        self.right_ascension
+    }
+
+    /// The declination, in the range [-90°, 90°]
+    pub fn dec(&self) -> Declination {
+        self.declination
+    }
+
+    /// Converts the direction into a unit vector, with the x axis pointing towards the equinox and
+    /// the z axis pointing towards the celestial north pole.
+    pub fn to_rectangular(&self) -> Vec3 {
+        Vec3::new(
+            self.declination.angle().cos() * self.right_ascension.angle().cos(),
+            self.declination.angle().cos() * self.right_ascension.angle().sin(),
+            self.declination.angle().sin(),
+        )
+    }
+
+    /// Computes the angular distance to another direction
+    pub fn separation(&self, other: &Equatorial<E>) -> Angle {
+        angle::separation(
+            self.right_ascension.angle(),
+            self.declination.angle(),
+            other.right_ascension.angle(),
+            other.declination.angle(),
+        )
+    }
+
+    /// Computes the position angle of another direction, measured from the north towards the
+    /// east, in the range [0°, 360°)
+    pub fn position_angle(&self, other: &Equatorial<E>) -> Angle {
+        angle::position_angle(
+            self.right_ascension.angle(),
+            self.declination.angle(),
+            other.right_ascension.angle(),
+            other.declination.angle(),
+        )
+    }
+
+    /// Interpolates along the great circle from this direction to `other`, moving at a constant
+    /// rate. A `fraction` of 0 gives this direction and 1 gives `other`, while values outside of
+    /// [0, 1] extrapolate along the same circle.
+    ///
+    /// The great circle isn't defined between opposite directions, which give a NaN.
+    pub fn slerp(&self, other: &Equatorial<E>, fraction: f64) -> Equatorial<E> {
+        let a = self.to_rectangular();
+        let b = other.to_rectangular();
+        let omega = Angle::atan2(a.cross(&b).norm(), a.dot(&b)).as_radians();
+
+        // Very close directions can be interpolated linearly, which avoids dividing by zero
+        let v = if omega < 1e-9 {
+            a * (1.0 - fraction) + b * fraction
+        } else {
+            (a * ((1.0 - fraction) * omega).sin() + b * (fraction * omega).sin())
+                * (1.0 / omega.sin())
+        };
+        Equatorial::from_vector_with_equinox(&v, self.equinox)
+    }
+
+    /// Computes the local hour angle of the direction for `observer` at the instant `t`, positive
+    /// west of the meridian, in the range [-180°, 180°).
+    ///
+    /// The sidereal time is measured from the equinox of the coordinates, so coordinates referred
+    /// to the true equinox of date use the apparent sidereal time.
+    pub fn hour_angle(&self, observer: &Observer, t: &JD<UT>) -> Angle {
+        (observer.hour_angle(t, self.right_ascension.angle())
+            + self.equinox.equation_of_the_equinoxes())
+        .normalize_pm180()
+    }
+
+    /// Converts the direction into the local horizontal coordinates of `observer` at the instant
+    /// `t`, following chapter 13.
+    ///
+    /// The coordinates should be referred to the equator and equinox of date. Neither refraction
+    /// nor parallax is accounted for.
+    pub fn to_horizontal(&self, observer: &Observer, t: &JD<UT>) -> Horizontal {
+        let (sin_h, cos_h) = self.hour_angle(observer, t).sin_cos();
+        let (sin_d, cos_d) = self.declination.angle().sin_cos();
+        let (sin_phi, cos_phi) = observer.latitude.sin_cos();
+
+        Horizontal {
+            azimuth: Angle::atan2(-cos_d * sin_h, sin_d * cos_phi - cos_d * cos_h * sin_phi)
+                .normalize_0_360(),
+            altitude: Angle::asin(sin_phi * sin_d + cos_phi * cos_d * cos_h),
+        }
+    }
+
+    pub fn to_ecliptical(&self) -> Ecliptical<E> {
+        let (sin_e, cos_e) = self.equinox.obliquity().sin_cos();
+        let (ra, dec) = (self.right_ascension.angle(), self.declination.angle());
+        Ecliptical::from_angles_with_equinox(
+            Angle::atan2(ra.sin() * cos_e + dec.tan() * sin_e, ra.cos()),
+            Angle::asin(dec.sin() * cos_e - dec.cos() * sin_e * ra.sin()),
+            self.equinox,
+        )
+    }
+}
+
+impl<E: Equinox> fmt::Display for Equatorial<E> {
+    /// Writes the right ascension in hours, minutes and seconds and the declination in degrees,
+    /// minutes and seconds
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_components(
+            f,
+            ("α", &self.right_ascension.angle().as_hms(), 3),
+            ("δ", &self.declination.angle().as_dms(), 2),
+        )
+    }
 }
 
+/// A direction in ecliptical coordinates, referred to the equinox `E`.
+///
+/// The longitude and latitude are checked the same way as the components of `Equatorial`.
 #[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ecliptical<E: Equinox> {
-    pub longitude: Angle,
-    pub latitude: Angle,
-    phantom: std::marker::PhantomData<E>,
+    longitude: EclipticLongitude,
+    latitude: EclipticLatitude,
+    equinox: E,
+}
+
+impl<E> Ecliptical<E>
+where
+    E: Equinox + Default,
+{
+    /// Returns `None` if either angle is NaN or infinite, or the latitude is outside of
+    /// [-90°, 90°]. The longitude is normalized into [0°, 360°).
+    pub fn new(longitude: Angle, latitude: Angle) -> Option<Ecliptical<E>> {
+        Ecliptical::with_equinox(longitude, latitude, E::default())
+    }
+
+    /// Builds the coordinates from typed components, which can't be swapped by mistake
+    pub fn from_typed(longitude: EclipticLongitude, latitude: EclipticLatitude) -> Ecliptical<E> {
+        Ecliptical {
+            longitude,
+            latitude,
+            equinox: E::default(),
+        }
+    }
+
+    /// Builds the coordinates from angles computed from other coordinates
+    pub(crate) fn from_angles(longitude: Angle, latitude: Angle) -> Ecliptical<E> {
+        Ecliptical::from_angles_with_equinox(longitude, latitude, E::default())
+    }
+}
+
+impl Ecliptical<MeanOfDate> {
+    /// Builds coordinates referred to the mean ecliptic and equinox of `epoch`. Returns `None` for
+    /// the same angles as `new`.
+    pub fn mean_of_date(
+        longitude: Angle,
+        latitude: Angle,
+        epoch: &JD,
+    ) -> Option<Ecliptical<MeanOfDate>> {
+        Ecliptical::with_equinox(longitude, latitude, MeanOfDate(*epoch))
+    }
 }
 
 impl<E> Ecliptical<E>
 where
     E: Equinox,
 {
+    /// Builds coordinates referred to the given equinox. Returns `None` for the same angles as
+    /// `new`.
+    pub fn with_equinox(longitude: Angle, latitude: Angle, equinox: E) -> Option<Ecliptical<E>> {
+        Some(Ecliptical {
+            longitude: EclipticLongitude::new(longitude)?,
+            latitude: EclipticLatitude::new(latitude)?,
+            equinox,
+        })
+    }
+
+    /// Builds coordinates from angles computed from other coordinates, which can only be out of
+    /// range through rounding errors
+    pub(crate) fn from_angles_with_equinox(
+        longitude: Angle,
+        latitude: Angle,
+        equinox: E,
+    ) -> Ecliptical<E> {
+        Ecliptical {
+            longitude: EclipticLongitude::computed(longitude),
+            latitude: EclipticLatitude::computed(latitude),
+            equinox,
+        }
+    }
+
+    /// The equinox the coordinates are referred to
+    pub fn equinox(&self) -> E {
+        self.equinox
+    }
+
+    /// The longitude, in the range [0°, 360°)
+    pub fn lon(&self) -> EclipticLongitude {
+        self.longitude
+    }
+
+    /// The latitude, in the range [-90°, 90°]
+    pub fn lat(&self) -> EclipticLatitude {
+        self.latitude
+    }
+
+    /// Computes the angular distance to another direction
+    pub fn separation(&self, other: &Ecliptical<E>) -> Angle {
+        angle::separation(
+            self.longitude.angle(),
+            self.latitude.angle(),
+            other.longitude.angle(),
+            other.latitude.angle(),
+        )
+    }
+
+    /// Computes the position angle of another direction, measured from the north pole of the
+    /// ecliptic towards increasing longitudes, in the range [0°, 360°)
+    pub fn position_angle(&self, other: &Ecliptical<E>) -> Angle {
+        angle::position_angle(
+            self.longitude.angle(),
+            self.latitude.angle(),
+            other.longitude.angle(),
+            other.latitude.angle(),
+        )
+    }
+
     pub fn to_equatorial(&self) -> Equatorial<E> {
-        Equatorial {
-            right_ascention: Angle::atan2(
-                self.longitude.sin() * E::OBLIQUITY.cos()
-                    - self.latitude.tan() * E::OBLIQUITY.sin(),
-                self.longitude.cos(),
-            ),
-            declination: Angle::asin(
-                self.latitude.sin() * E::OBLIQUITY.cos()
-                    + self.latitude.cos() * E::OBLIQUITY.sin() * self.longitude.sin(),
-            ),
-            phantom: std::marker::PhantomData,
+        let (sin_e, cos_e) = self.equinox.obliquity().sin_cos();
+        let (lon, lat) = (self.longitude.angle(), self.latitude.angle());
+        Equatorial::from_angles_with_equinox(
+            Angle::atan2(lon.sin() * cos_e - lat.tan() * sin_e, lon.cos()),
+            Angle::asin(lat.sin() * cos_e + lat.cos() * sin_e * lon.sin()),
+            self.equinox,
+        )
+    }
+}
+
+impl<E: Equinox> fmt::Display for Ecliptical<E> {
+    /// Writes the longitude and latitude in degrees, minutes and seconds
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_components(
+            f,
+            ("λ", &self.longitude.angle().as_dms(), 2),
+            ("β", &self.latitude.angle().as_dms(), 2),
+        )
+    }
+}
+
+/// A direction in equatorial coordinates together with the distance to the body, in astronomical
+/// units.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EquatorialPosition<E: Equinox> {
+    pub direction: Equatorial<E>,
+    pub distance: f64,
+}
+
+impl<E: Equinox + Default> EquatorialPosition<E> {
+    /// Builds the position from a rectangular vector, in astronomical units
+    pub fn from_rectangular(v: &Vec3) -> EquatorialPosition<E> {
+        EquatorialPosition::new(Equatorial::from_vector(v), v.norm())
+    }
+}
+
+impl<E: Equinox> EquatorialPosition<E> {
+    pub fn new(direction: Equatorial<E>, distance: f64) -> EquatorialPosition<E> {
+        EquatorialPosition {
+            direction,
+            distance,
         }
     }
+
+    /// Converts the position into a rectangular vector, in astronomical units
+    pub fn to_rectangular(&self) -> Vec3 {
+        self.direction.to_rectangular() * self.distance
+    }
+
+    pub fn to_ecliptical(&self) -> EclipticalPosition<E> {
+        EclipticalPosition::new(self.direction.to_ecliptical(), self.distance)
+    }
+
+    /// The time taken by the light to travel the distance, in days
+    pub fn light_time(&self) -> f64 {
+        self.distance * planets::LIGHT_TIME_PER_AU
+    }
+
+    /// Computes the position seen by `observer` at the instant `t` instead of from the center of
+    /// the Earth, correcting both the direction and the distance for the parallax.
+    ///
+    /// The position should be referred to the equator and equinox of date.
+    pub fn to_topocentric(&self, observer: &Observer, t: &JD<UT>) -> EquatorialPosition<E> {
+        let radius = observer::EQUATORIAL_RADIUS / METERS_PER_AU;
+        let sidereal_time =
+            observer.local_sidereal_time(t) + self.direction.equinox.equation_of_the_equinoxes();
+        let (sin_theta, cos_theta) = sidereal_time.sin_cos();
+        let site = Vec3::new(
+            observer.rho_cos_phi_prime() * cos_theta,
+            observer.rho_cos_phi_prime() * sin_theta,
+            observer.rho_sin_phi_prime(),
+        ) * radius;
+
+        let v = self.to_rectangular() - site;
+        EquatorialPosition::new(
+            Equatorial::from_vector_with_equinox(&v, self.direction.equinox()),
+            v.norm(),
+        )
+    }
+
+    /// Computes the phase angle of the body, the angle between the Earth and the Sun as seen from
+    /// the body, given the position of the Sun referred to the same equinox
+    pub fn phase_angle(&self, sun: &EquatorialPosition<E>) -> Angle {
+        phase_angle(&self.to_rectangular(), &sun.to_rectangular())
+    }
+}
+
+/// A direction in ecliptical coordinates together with the distance to the body, in astronomical
+/// units.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EclipticalPosition<E: Equinox> {
+    pub direction: Ecliptical<E>,
+    pub distance: f64,
+}
+
+impl<E: Equinox> EclipticalPosition<E> {
+    pub fn new(direction: Ecliptical<E>, distance: f64) -> EclipticalPosition<E> {
+        EclipticalPosition {
+            direction,
+            distance,
+        }
+    }
+
+    /// Converts the position into a rectangular vector referred to the ecliptic, in astronomical
+    /// units
+    pub fn to_rectangular(&self) -> Vec3 {
+        let (sin_l, cos_l) = self.direction.longitude.angle().sin_cos();
+        let (sin_b, cos_b) = self.direction.latitude.angle().sin_cos();
+        Vec3::new(cos_b * cos_l, cos_b * sin_l, sin_b) * self.distance
+    }
+
+    pub fn to_equatorial(&self) -> EquatorialPosition<E> {
+        EquatorialPosition::new(self.direction.to_equatorial(), self.distance)
+    }
+
+    /// The time taken by the light to travel the distance, in days
+    pub fn light_time(&self) -> f64 {
+        self.distance * planets::LIGHT_TIME_PER_AU
+    }
+
+    /// Computes the phase angle of the body, the angle between the Earth and the Sun as seen from
+    /// the body, given the position of the Sun referred to the same equinox
+    pub fn phase_angle(&self, sun: &EclipticalPosition<E>) -> Angle {
+        phase_angle(&self.to_rectangular(), &sun.to_rectangular())
+    }
+}
+
+/// Computes the angle between the directions from a body to the Earth and to the Sun, given the
+/// positions of the body and of the Sun relative to the Earth
+fn phase_angle(body: &Vec3, sun: &Vec3) -> Angle {
+    let to_earth = *body * -1.0;
+    let to_sun = *sun - *body;
+    Angle::atan2(to_earth.cross(&to_sun).norm(), to_earth.dot(&to_sun))
+}
+
+/// Local horizontal coordinates.
+///
+/// The azimuth is measured from the north towards the east, as is usual for observers. Note that
+/// Meeus measures it from the south towards the west instead, so his azimuths differ by 180°.
+#[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Horizontal {
+    pub azimuth: Angle,
+    /// The altitude above the horizon, negative below it
+    pub altitude: Angle,
+}
+
+impl Horizontal {
+    pub fn new(azimuth: Angle, altitude: Angle) -> Horizontal {
+        Horizontal { azimuth, altitude }
+    }
+
+    /// The angle between the direction and the zenith, 90° minus the altitude
+    pub fn zenith_distance(&self) -> Angle {
+        Angle::RIGHT_ANGLE - self.altitude
+    }
+
+    /// Whether the direction is above the mathematical horizon. Refraction and the size of the
+    /// body aren't accounted for, so use `is_above` for the usual altitudes of rising and setting.
+    pub fn is_above_horizon(&self) -> bool {
+        self.altitude > Angle::ZERO
+    }
+
+    /// Whether the direction is higher than the altitude `min_altitude`
+    pub fn is_above(&self, min_altitude: Angle) -> bool {
+        self.altitude > min_altitude
+    }
+
+    /// Computes the angular distance to another direction
+    pub fn separation(&self, other: &Horizontal) -> Angle {
+        angle::separation(self.azimuth, self.altitude, other.azimuth, other.altitude)
+    }
+
+    /// Computes the position angle of another direction, measured from the zenith towards
+    /// increasing azimuths, in the range [0°, 360°)
+    pub fn position_angle(&self, other: &Horizontal) -> Angle {
+        angle::position_angle(self.azimuth, self.altitude, other.azimuth, other.altitude)
+    }
+
+    /// Converts the local horizontal coordinates of `observer` at the instant `t` back into
+    /// equatorial coordinates, referred to the equator and equinox of date
+    pub fn to_equatorial(&self, observer: &Observer, t: &JD<UT>) -> Equatorial<MeanOfDate> {
+        let (sin_a, cos_a) = self.azimuth.sin_cos();
+        let (sin_h, cos_h) = self.altitude.sin_cos();
+        let (sin_phi, cos_phi) = observer.latitude.sin_cos();
+
+        let hour_angle = Angle::atan2(-cos_h * sin_a, sin_h * cos_phi - cos_h * cos_a * sin_phi);
+        Equatorial::from_angles_with_equinox(
+            observer.local_sidereal_time(t) - hour_angle,
+            Angle::asin(sin_phi * sin_h + cos_phi * cos_h * cos_a),
+            MeanOfDate(t.to_scale()),
+        )
+    }
+}
+
+impl fmt::Display for Horizontal {
+    /// Writes the azimuth and altitude in degrees, minutes and seconds
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_components(
+            f,
+            ("A", &self.azimuth.normalize_0_360().as_dms(), 1),
+            ("h", &self.altitude.as_dms(), 1),
+        )
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::angle::{DegreesMinutesSeconds, HoursMinutesSeconds};
+    use crate::angle::{DegreesMinutesSeconds, HoursMinutesSeconds, Sign};
     use assert_approx_eq::assert_approx_eq;
 
     #[test]
     fn ecliptical_to_equatorial() {
         // Example 13.a, page 95
-        let ecliptical = Equatorial::<J2000> {
-            right_ascention: HoursMinutesSeconds {
+        let ecliptical = Equatorial::<J2000>::new(
+            HoursMinutesSeconds {
+                sign: Sign::Positive,
                 hours: 7,
                 minutes: 45,
                 seconds: 18.946,
             }
             .as_angle(),
-            declination: DegreesMinutesSeconds {
+            DegreesMinutesSeconds {
+                sign: Sign::Positive,
                 degrees: 28,
                 minutes: 1,
                 seconds: 34.26,
             }
             .as_angle(),
-            phantom: std::marker::PhantomData,
-        }
+        )
+        .unwrap()
         .to_ecliptical();
-        assert_approx_eq!(ecliptical.longitude.as_degrees(), 113.215_630);
-        assert_approx_eq!(ecliptical.latitude.as_degrees(), 6.684170);
+        assert_approx_eq!(ecliptical.lon().angle().as_degrees(), 113.215_630);
+        assert_approx_eq!(ecliptical.lat().angle().as_degrees(), 6.684170);
+    }
+
+    #[test]
+    fn typed_components() {
+        let ra = RightAscension::new(Angle::from_degrees(-10.0)).unwrap();
+        assert_approx_eq!(ra.angle().as_degrees(), 350.0, 1e-12);
+        assert!(RightAscension::new(Angle::from_degrees(f64::INFINITY)).is_none());
+        assert!(Declination::new(Angle::from_degrees(90.0)).is_some());
+        assert!(Declination::new(Angle::from_degrees(-90.5)).is_none());
+        assert!(EclipticLatitude::new(Angle::from_degrees(f64::NAN)).is_none());
+        assert!(Declination::clamped(Angle::from_degrees(f64::NAN)).is_none());
+        assert_eq!(
+            Declination::clamped(Angle::from_degrees(90.000_001)),
+            Declination::new(Angle::RIGHT_ANGLE)
+        );
+
+        // The coordinates can only be built from valid angles
+        let (right, nan) = (Angle::RIGHT_ANGLE, Angle::from_degrees(f64::NAN));
+        assert!(Equatorial::<J2000>::new(nan, Angle::ZERO).is_none());
+        assert!(Equatorial::<J2000>::new(Angle::ZERO, right * 1.01).is_none());
+        assert!(Ecliptical::<J2000>::new(Angle::ZERO, nan).is_none());
+        let nan_vector = Vec3::new(f64::NAN, 0.0, 1.0);
+        assert!(Equatorial::<J2000>::from_rectangular(&nan_vector).is_none());
+        let negative = Equatorial::<J2000>::new(Angle::from_degrees(-10.0), right).unwrap();
+        assert_eq!(negative.ra(), ra);
+
+        let dec = Declination::new(Angle::from_degrees(-20.0)).unwrap();
+        let equatorial = Equatorial::<J2000>::from_typed(ra, dec);
+        assert_eq!(equatorial.ra(), ra);
+        assert_eq!(equatorial.dec(), dec);
+        assert_eq!(Angle::from(equatorial.dec()), Angle::from_degrees(-20.0));
+
+        let ecliptical = equatorial.to_ecliptical();
+        let typed = Ecliptical::<J2000>::from_typed(ecliptical.lon(), ecliptical.lat());
+        assert_approx_eq!(
+            typed.to_equatorial().dec().angle().as_degrees(),
+            -20.0,
+            1e-9
+        );
+        assert!(typed.lon().angle() >= Angle::ZERO);
+    }
+
+    #[test]
+    fn equatorial_to_horizontal() {
+        // Example 13.b, Venus seen from the US Naval Observatory. The position is the apparent
+        // one, so the hour angle is reckoned from the apparent sidereal time as in the book.
+        let observer = Observer::new(
+            DegreesMinutesSeconds::new(Sign::Positive, 38, 55, 17.0)
+                .unwrap()
+                .as_angle(),
+            DegreesMinutesSeconds::new(Sign::Negative, 77, 3, 56.0)
+                .unwrap()
+                .as_angle(),
+            0.0,
+        );
+        let t = JD::from_parts(2_446_895.5, 19.35 / 24.0);
+        let venus = Equatorial::with_equinox(
+            HoursMinutesSeconds::new(Sign::Positive, 23, 9, 16.641)
+                .unwrap()
+                .as_angle(),
+            DegreesMinutesSeconds::new(Sign::Negative, 6, 43, 11.61)
+                .unwrap()
+                .as_angle(),
+            TrueOfDate(t.to_scale()),
+        )
+        .unwrap();
+
+        let horizontal = venus.to_horizontal(&observer, &t);
+        assert_approx_eq!(horizontal.azimuth.as_degrees(), 68.0337 + 180.0, 1e-3);
+        assert_approx_eq!(horizontal.altitude.as_degrees(), 15.1249, 1e-3);
+
+        // Going back reckons the hour angle from the mean sidereal time
+        let back = horizontal.to_equatorial(&observer, &t);
+        assert_approx_eq!(
+            (back.ra().angle() + venus.equinox().equation_of_the_equinoxes()).as_degrees(),
+            venus.ra().angle().as_degrees(),
+            1e-9
+        );
+        assert_approx_eq!(
+            back.dec().angle().as_degrees(),
+            venus.dec().angle().as_degrees(),
+            1e-9
+        );
+    }
+
+    #[test]
+    fn mean_of_date() {
+        // Example 21.b, page 135, θ Persei with its proper motion already applied
+        let star = Equatorial::<J2000>::new(
+            Angle::from_degrees(41.054_063),
+            Angle::from_degrees(49.227_750),
+        )
+        .unwrap();
+        let epoch = JD::from(2_462_088.69);
+        let of_date = star.to_equinox(MeanOfDate(epoch));
+        assert_eq!(of_date.equinox().epoch(), epoch);
+        assert_approx_eq!(of_date.ra().angle().as_degrees(), 41.547_214, 1e-5);
+        assert_approx_eq!(of_date.dec().angle().as_degrees(), 49.348_483, 1e-5);
+
+        // The angle formulas and the rotation matrix are the same transformation
+        let precessed = star.precess(&epoch);
+        assert_eq!(precessed.equinox(), MeanOfDate(epoch));
+        assert_approx_eq!(
+            precessed.ra().angle().as_degrees(),
+            of_date.ra().angle().as_degrees(),
+            1e-9
+        );
+        assert_approx_eq!(
+            precessed.dec().angle().as_degrees(),
+            of_date.dec().angle().as_degrees(),
+            1e-9
+        );
+
+        // Both start from the equinox of the coordinates, including the nutation of a true one
+        let apparent = star.to_equinox(TrueOfDate(JD::from(2_451_910.5)));
+        let from_true = apparent.precess(&epoch);
+        assert_approx_eq!(
+            from_true.ra().angle().as_degrees(),
+            of_date.ra().angle().as_degrees(),
+            1e-9
+        );
+        assert_approx_eq!(
+            from_true.dec().angle().as_degrees(),
+            of_date.dec().angle().as_degrees(),
+            1e-9
+        );
+
+        let back = of_date.to_equinox(J2000 {});
+        assert_approx_eq!(back.ra().angle().as_degrees(), 41.054_063, 1e-9);
+        assert_approx_eq!(back.dec().angle().as_degrees(), 49.227_750, 1e-9);
+        let back = precessed.precess(&JD::J2000);
+        assert_approx_eq!(back.ra().angle().as_degrees(), 41.054_063, 1e-9);
+        assert_approx_eq!(back.dec().angle().as_degrees(), 49.227_750, 1e-9);
+
+        // The equinox of date at J2000.0 is the J2000 equinox
+        let at_j2000 =
+            Equatorial::mean_of_date(star.ra().angle(), star.dec().angle(), &JD::J2000).unwrap();
+        let ecliptical = at_j2000.to_ecliptical();
+        assert_approx_eq!(
+            ecliptical.lon().angle().as_degrees(),
+            star.to_ecliptical().lon().angle().as_degrees(),
+            1e-6
+        );
+        assert_approx_eq!(
+            ecliptical.lat().angle().as_degrees(),
+            star.to_ecliptical().lat().angle().as_degrees(),
+            1e-6
+        );
+        assert_eq!(ecliptical.to_equatorial().equinox(), MeanOfDate(JD::J2000));
+    }
+
+    #[test]
+    fn icrs() {
+        // In the dynamical frame, the origin of the ICRS is 14.6 milliarcseconds east of the mean
+        // equinox of J2000, and 16.6 milliarcseconds south of the mean equator
+        let origin = Equatorial::<ICRS>::new(Angle::ZERO, Angle::ZERO).unwrap();
+        let dynamical = origin.to_j2000();
+        assert_approx_eq!(dynamical.ra().angle().normalize_pm180().as_mas(), 14.6, 0.1);
+        assert_approx_eq!(dynamical.dec().angle().as_mas(), -16.617, 1e-3);
+
+        let back = dynamical.to_icrs();
+        assert_approx_eq!(back.ra().angle().as_degrees(), 0.0, 1e-12);
+        assert_approx_eq!(back.dec().angle().as_degrees(), 0.0, 1e-12);
+    }
+
+    #[test]
+    fn b1950_and_j2000() {
+        // Over the 50 years a point on the equator at the equinox gains about 3.07s of right
+        // ascension and 20.04" of declination per year
+        let start = Equatorial::<B1950>::new(Angle::ZERO, Angle::ZERO).unwrap();
+        let end = start.to_j2000();
+        assert_approx_eq!(end.ra().angle().as_hours() * 3600.0, 153.7, 0.1);
+        assert_approx_eq!(end.dec().angle().as_arcseconds(), 1002.0, 0.5);
+
+        let back = end.to_b1950();
+        assert_approx_eq!(back.ra().angle().normalize_pm180().as_degrees(), 0.0, 1e-12);
+        assert_approx_eq!(back.dec().angle().as_degrees(), 0.0, 1e-12);
+    }
+
+    #[test]
+    fn separation_and_position_angle() {
+        // Example 17.a, page 110: Arcturus and Spica are 32°47.5' apart
+        let arcturus =
+            Equatorial::<J2000>::new(Angle::from_degrees(213.9154), Angle::from_degrees(19.1825))
+                .unwrap();
+        let spica =
+            Equatorial::<J2000>::new(Angle::from_degrees(201.2983), Angle::from_degrees(-11.1614))
+                .unwrap();
+        assert_approx_eq!(arcturus.separation(&spica).as_degrees(), 32.7930, 1e-4);
+        assert_approx_eq!(
+            arcturus
+                .to_ecliptical()
+                .separation(&spica.to_ecliptical())
+                .as_degrees(),
+            32.7930,
+            1e-4
+        );
+
+        // Spica is to the south and slightly to the west of Arcturus, and Arcturus to the north and
+        // slightly to the east of Spica. The two position angles don't differ by exactly 180°, as
+        // the great circle between them crosses the meridians at different angles. The values
+        // are from tan P = sin Δα / (cos δ1 tan δ2 - sin δ1 cos Δα) with the coordinates of the
+        // example.
+        assert_approx_eq!(arcturus.position_angle(&spica).as_degrees(), 203.31, 0.01);
+        assert_approx_eq!(spica.position_angle(&arcturus).as_degrees(), 22.39, 0.01);
+
+        // The zenith is straight up from any point of the horizon
+        let east = Horizontal::new(Angle::RIGHT_ANGLE, Angle::ZERO);
+        let zenith = Horizontal::new(Angle::ZERO, Angle::RIGHT_ANGLE);
+        assert_approx_eq!(east.separation(&zenith).as_degrees(), 90.0, 1e-12);
+        assert_approx_eq!(east.position_angle(&zenith).as_degrees(), 0.0, 1e-12);
+    }
+
+    #[test]
+    fn display() {
+        let star = Equatorial::<J2000>::new(
+            HoursMinutesSeconds::new(Sign::Positive, 2, 44, 11.986)
+                .unwrap()
+                .as_angle(),
+            DegreesMinutesSeconds::new(Sign::Negative, 49, 13, 42.48)
+                .unwrap()
+                .as_angle(),
+        )
+        .unwrap();
+        assert_eq!(star.to_string(), "α 2ʰ44ᵐ11.986ˢ δ -49°13′42.48″");
+        assert_eq!(format!("{:.1}", star), "α 2ʰ44ᵐ12.0ˢ δ -49°13′42.5″");
+
+        let ecliptical = Ecliptical::<J2000>::new(Angle::from_degrees(-0.5), Angle::ZERO).unwrap();
+        assert_eq!(ecliptical.to_string(), "λ 359°30′0.00″ β 0°0′0.00″");
+
+        let horizontal =
+            Horizontal::new(Angle::from_degrees(248.0337), Angle::from_degrees(15.1249));
+        assert_eq!(horizontal.to_string(), "A 248°2′1.3″ h 15°7′29.6″");
+
+        let spherical = HeliocentricSpherical {
+            longitude: Angle::from_degrees(26.11428),
+            latitude: Angle::from_degrees(-2.62070),
+            radius: 0.724_603,
+        };
+        assert_eq!(
+            spherical.to_string(),
+            "L 26°6′51.41″ B -2°37′14.52″ R 0.724603 AU"
+        );
+    }
+
+    #[test]
+    fn positions_with_distance() {
+        // Example 41.a, page 284: Venus on 1992 December 20 at 0h TD has a phase angle of 72.96°
+        let t = JD::from(2_448_976.5);
+        let venus = planets::Planet::Venus.get_geocentric_position(&t, planets::Aberration::None);
+        let sun = crate::sun::get_geocentric_position(&t, planets::Aberration::None);
+        let phase = venus.position().phase_angle(&sun.position());
+        assert_approx_eq!(phase.as_degrees(), 72.96, 0.02);
+        let equatorial = venus.position().to_equatorial();
+        assert_approx_eq!(
+            equatorial
+                .phase_angle(&sun.position().to_equatorial())
+                .as_degrees(),
+            phase.as_degrees(),
+            1e-9
+        );
+        assert_approx_eq!(
+            equatorial.light_time(),
+            venus.distance * 0.005_775_518_3,
+            1e-15
+        );
+
+        // Example 40.a, page 280: Mars seen from Palomar agrees with the parallax formulas, up to
+        // the slightly different radius of the Earth behind the solar parallax of 8.794"
+        let palomar = Observer::new(
+            DegreesMinutesSeconds::new(Sign::Positive, 33, 21, 22.0)
+                .unwrap()
+                .as_angle(),
+            HoursMinutesSeconds::new(Sign::Negative, 7, 47, 27.0)
+                .unwrap()
+                .as_angle(),
+            1706.0,
+        );
+        let t = JD::from_parts(2_452_879.5, (3.0 + 17.0 / 60.0) / 24.0);
+        let mars = EquatorialPosition::new(
+            Equatorial::with_equinox(
+                Angle::from_degrees(339.530_208),
+                Angle::from_degrees(-15.771_083),
+                TrueOfDate(t.to_scale()),
+            )
+            .unwrap(),
+            0.37276,
+        );
+        let topocentric = mars.to_topocentric(&palomar, &t);
+        let expected =
+            crate::parallax::topocentric_equatorial(&mars.direction, 0.37276, &palomar, &t);
+        assert_approx_eq!(
+            topocentric.direction.ra().angle().as_degrees(),
+            expected.ra().angle().as_degrees(),
+            1e-6
+        );
+        assert_approx_eq!(
+            topocentric.direction.dec().angle().as_degrees(),
+            expected.dec().angle().as_degrees(),
+            1e-6
+        );
+
+        // Mars is above the horizon, so it is closer to the observer than to the center of the
+        // Earth, by at most the radius of the Earth
+        let difference = mars.distance - topocentric.distance;
+        assert!(difference > 0.0 && difference < 6_378_140.0 / METERS_PER_AU);
+
+        let v = mars.to_rectangular();
+        let back = Equatorial::from_rectangular_with_equinox(&v, mars.direction.equinox()).unwrap();
+        assert_approx_eq!(v.norm(), 0.37276, 1e-12);
+        assert_approx_eq!(
+            back.ra().angle().as_degrees(),
+            mars.direction.ra().angle().as_degrees(),
+            1e-9
+        );
+    }
+
+    #[test]
+    fn visibility() {
+        let low = Horizontal::new(Angle::from_degrees(120.0), Angle::from_degrees(-0.5));
+        assert!(!low.is_above_horizon());
+        assert!(low.is_above(Angle::from_degrees(-0.8333)));
+        assert_approx_eq!(low.zenith_distance().as_degrees(), 90.5, 1e-12);
+
+        let high = Horizontal::new(Angle::ZERO, Angle::from_degrees(60.0));
+        assert!(high.is_above_horizon());
+        assert!(!high.is_above(Angle::from_degrees(60.0)));
+        assert_approx_eq!(high.zenith_distance().as_degrees(), 30.0, 1e-12);
+    }
+
+    #[test]
+    fn slerp() {
+        let start = Equatorial::<J2000>::new(Angle::from_degrees(350.0), Angle::ZERO).unwrap();
+        let end = Equatorial::<J2000>::new(Angle::from_degrees(20.0), Angle::ZERO).unwrap();
+
+        // Along the equator the right ascension moves uniformly, across 0h
+        let middle = start.slerp(&end, 0.5);
+        assert_approx_eq!(middle.ra().angle().as_degrees(), 5.0, 1e-12);
+        assert_approx_eq!(middle.dec().angle().as_degrees(), 0.0, 1e-12);
+        let beyond = start.slerp(&end, 2.0);
+        assert_approx_eq!(beyond.ra().angle().as_degrees(), 50.0, 1e-12);
+
+        // Over the pole, the path goes through the pole instead of along a parallel
+        let start = Equatorial::<J2000>::new(Angle::ZERO, Angle::from_degrees(80.0)).unwrap();
+        let end =
+            Equatorial::<J2000>::new(Angle::STRAIGHT_ANGLE, Angle::from_degrees(80.0)).unwrap();
+        assert_approx_eq!(
+            start.slerp(&end, 0.5).dec().angle().as_degrees(),
+            90.0,
+            1e-9
+        );
+        let quarter = start.slerp(&end, 0.25);
+        assert_approx_eq!(quarter.separation(&start).as_degrees(), 5.0, 1e-9);
+        assert_eq!(start.slerp(&start, 0.3), start);
+    }
+
+    #[test]
+    fn true_of_date() {
+        // Example 23.a, page 156: the nutation moves θ Persei by 15.843" in right ascension and
+        // 6.218" in declination on 2028 November 13.19 TD
+        let t = JD::from(2_462_088.69);
+        let star = Equatorial::<J2000>::new(
+            Angle::from_degrees(41.054_063),
+            Angle::from_degrees(49.227_750),
+        )
+        .unwrap();
+        let mean = star.to_equinox(MeanOfDate(t));
+        let apparent = star.to_equinox(TrueOfDate(t));
+        assert_approx_eq!(
+            (apparent.ra().angle() - mean.ra().angle()).as_arcseconds(),
+            15.843,
+            0.01
+        );
+        assert_approx_eq!(
+            (apparent.dec().angle() - mean.dec().angle()).as_arcseconds(),
+            6.218,
+            0.01
+        );
+
+        // The true ecliptic differs from the mean one by the nutation in longitude
+        let shift = apparent.to_ecliptical().lon().angle() - mean.to_ecliptical().lon().angle();
+        let nutation = nutation::nutation(&t);
+        assert_approx_eq!(
+            shift.as_arcseconds(),
+            nutation.delta_psi.as_arcseconds(),
+            1e-6
+        );
+
+        let back = apparent.to_equinox(J2000 {});
+        assert_approx_eq!(back.ra().angle().as_degrees(), 41.054_063, 1e-9);
+        assert_approx_eq!(back.dec().angle().as_degrees(), 49.227_750, 1e-9);
+
+        // The ICRS takes the same route
+        let icrs = star.to_icrs();
+        let through_icrs = icrs.to_equinox(TrueOfDate(t));
+        assert_approx_eq!(
+            through_icrs.ra().angle().as_degrees(),
+            apparent.ra().angle().as_degrees(),
+            1e-9
+        );
+    }
+
+    #[test]
+    fn equinox_value() {
+        let star = Equatorial::<J2000>::new(
+            Angle::from_degrees(41.054_063),
+            Angle::from_degrees(49.227_750),
+        )
+        .unwrap();
+        let t = JD::from(2_462_088.69);
+
+        // Going through the equinox chosen at runtime is the same as going through its type
+        let of_date = star.to_equinox(EquinoxValue::from(MeanOfDate(t)));
+        let typed = star.to_equinox(MeanOfDate(t));
+        assert_approx_eq!(
+            of_date.ra().angle().as_degrees(),
+            typed.ra().angle().as_degrees(),
+            1e-12
+        );
+        assert_approx_eq!(
+            of_date.to_ecliptical().lat().angle().as_degrees(),
+            typed.to_ecliptical().lat().angle().as_degrees(),
+            1e-12
+        );
+        assert_eq!(of_date.equinox(), EquinoxValue::MeanOfDate(t));
+
+        let back = of_date.to_equinox(EquinoxValue::J2000);
+        assert_approx_eq!(back.ra().angle().as_degrees(), 41.054_063, 1e-9);
+        assert_approx_eq!(back.dec().angle().as_degrees(), 49.227_750, 1e-9);
+
+        let b1950 = star.to_equinox(EquinoxValue::from(B1950 {}));
+        assert_approx_eq!(
+            b1950.dec().angle().as_degrees(),
+            star.to_b1950().dec().angle().as_degrees(),
+            1e-12
+        );
+
+        // The nutation and the frame bias are applied too
+        let check = |value: EquinoxValue, typed: (RightAscension, Declination)| {
+            let dynamic = star.to_equinox(value);
+            assert_approx_eq!(
+                dynamic.ra().angle().as_degrees(),
+                typed.0.angle().as_degrees(),
+                1e-12
+            );
+            assert_approx_eq!(
+                dynamic.dec().angle().as_degrees(),
+                typed.1.angle().as_degrees(),
+                1e-12
+            );
+        };
+        let apparent = star.to_equinox(TrueOfDate(t));
+        check(TrueOfDate(t).into(), (apparent.ra(), apparent.dec()));
+        let icrs = star.to_icrs();
+        check(ICRS {}.into(), (icrs.ra(), icrs.dec()));
+        assert_eq!(
+            EquinoxValue::TrueOfDate(t).equation_of_the_equinoxes(),
+            TrueOfDate(t).equation_of_the_equinoxes()
+        );
+    }
+
+    #[test]
+    fn heliocentric_rectangular() {
+        let spherical = HeliocentricSpherical {
+            latitude: Angle::from_degrees(-2.0),
+            longitude: Angle::from_degrees(300.0),
+            radius: 1.5,
+        };
+        let rectangular = HeliocentricRectangular::from(spherical);
+        assert_approx_eq!(rectangular.radius(), 1.5, 1e-12);
+        assert_approx_eq!(rectangular.x, 1.5 * (-2f64).to_radians().cos() * 0.5, 1e-12);
+
+        let back = HeliocentricSpherical::from(rectangular);
+        assert_approx_eq!(back.longitude.as_degrees(), 300.0, 1e-9);
+        assert_approx_eq!(back.latitude.as_degrees(), -2.0, 1e-9);
+        assert_approx_eq!(back.radius, 1.5, 1e-12);
+
+        let other = HeliocentricRectangular::new(rectangular.x, rectangular.y, rectangular.z + 2.0);
+        assert_approx_eq!(rectangular.distance_to(&other), 2.0, 1e-12);
     }
 }
@@ -0,0 +1,142 @@
+//! Precomputed ephemerides for fast repeated queries.
+//!
+//! Evaluating the full VSOP87 series is fairly expensive. When the same body is queried many times
+//! over a limited span of time, for example every frame of an animation, it is much cheaper to
+//! evaluate the series once on a coarse grid and interpolate between the grid points.
+use crate::angle::Angle;
+use crate::coords::HeliocentricSpherical;
+use crate::interpolation::interpolate5;
+use crate::planets::Planet;
+use crate::time::JD;
+
+/// A table of a planet's heliocentric positions at equally spaced times, which answers queries at
+/// arbitrary times within the table by five point interpolation.
+///
+/// With a step of one day for Mercury and a few days for the other planets, the interpolated
+/// positions agree with the full series to within a few hundredths of an arcsecond, at a fraction
+/// of the cost.
+#[derive(Debug, Clone)]
+pub struct InterpolatedEphemeris {
+    planet: Planet,
+    start: JD,
+    step: f64,
+    // Longitudes are stored without wrapping so that they vary smoothly between samples
+    samples: Vec<(f64, f64, f64)>,
+}
+
+impl InterpolatedEphemeris {
+    /// Tabulates the positions of `planet` from `start` to at least `end`, every `step` days.
+    ///
+    /// # Panics
+    /// Panics if `step` is not positive or if `end` is before `start`
+    pub fn new(planet: Planet, start: &JD, end: &JD, step: f64) -> InterpolatedEphemeris {
+        assert!(step > 0.0, "Invalid ephemeris step: {}", step);
+        assert!(*end - *start >= 0.0);
+
+        // Include two extra samples on each side so the whole range can use centered values
+        let count = ((*end - *start) / step).ceil() as usize + 5;
+        let first = *start - 2.0 * step;
+
+        let mut samples: Vec<(f64, f64, f64)> = Vec::with_capacity(count);
+        for i in 0..count {
+            let position = planet.get_location(&(first + i as f64 * step));
+            let mut longitude = position.longitude.as_radians();
+            if let Some(previous) = samples.last() {
+                let turns = ((previous.0 - longitude) / std::f64::consts::TAU).round();
+                longitude += turns * std::f64::consts::TAU;
+            }
+            samples.push((longitude, position.latitude.as_radians(), position.radius));
+        }
+
+        InterpolatedEphemeris {
+            planet,
+            start: first,
+            step,
+            samples,
+        }
+    }
+
+    /// The planet this ephemeris was computed for
+    pub fn planet(&self) -> Planet {
+        self.planet
+    }
+
+    /// Interpolates the position of the planet at the given time.
+    ///
+    /// Returns `None` if `t` falls outside of the span the ephemeris was computed for, or isn't a
+    /// finite time.
+    pub fn get_location(&self, t: &JD) -> Option<HeliocentricSpherical> {
+        let offset = (*t - self.start) / self.step;
+        let center = offset.round();
+        if !center.is_finite() || center < 2.0 || center > (self.samples.len() - 3) as f64 {
+            return None;
+        }
+
+        let center = center as usize;
+        let n = offset - center as f64;
+        let window = &self.samples[center - 2..=center + 2];
+        let column = |f: fn(&(f64, f64, f64)) -> f64| {
+            interpolate5(
+                &[
+                    f(&window[0]),
+                    f(&window[1]),
+                    f(&window[2]),
+                    f(&window[3]),
+                    f(&window[4]),
+                ],
+                n,
+            )
+        };
+
+        Some(HeliocentricSpherical {
+            longitude: Angle::from_radians(column(|s| s.0)).normalize_0_360(),
+            latitude: Angle::from_radians(column(|s| s.1)),
+            radius: column(|s| s.2),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn matches_full_series() {
+        let start = JD::from(2_451_545.0);
+        let end = JD::from(2_451_545.0 + 200.0);
+        let ephemeris = InterpolatedEphemeris::new(Planet::Mercury, &start, &end, 1.0);
+
+        for i in 0..400 {
+            let t = JD::from(2_451_545.0 + i as f64 * 0.5 + 0.123);
+            if t - end > 0.0 {
+                break;
+            }
+            let expected = Planet::Mercury.get_location(&t);
+            let actual = ephemeris.get_location(&t).unwrap();
+            // The wrap at 360° means the longitudes can differ by a full turn right at the edge
+            let dl = (actual.longitude - expected.longitude).as_radians();
+            let dl = dl - (dl / std::f64::consts::TAU).round() * std::f64::consts::TAU;
+            assert!(dl.abs() < 5e-7);
+            assert_approx_eq!(
+                actual.latitude.as_radians(),
+                expected.latitude.as_radians(),
+                5e-7
+            );
+            assert_approx_eq!(actual.radius, expected.radius, 1e-7);
+        }
+    }
+
+    #[test]
+    fn outside_of_range() {
+        let start = JD::from(2_451_545.0);
+        let end = JD::from(2_451_555.0);
+        let ephemeris = InterpolatedEphemeris::new(Planet::Mars, &start, &end, 2.0);
+        assert!(ephemeris.get_location(&start).is_some());
+        assert!(ephemeris.get_location(&end).is_some());
+        assert!(ephemeris.get_location(&JD::from(2_451_530.0)).is_none());
+        assert!(ephemeris.get_location(&JD::from(2_451_570.0)).is_none());
+        assert!(ephemeris.get_location(&JD::from(f64::NAN)).is_none());
+        assert!(ephemeris.get_location(&JD::from(f64::INFINITY)).is_none());
+    }
+}
@@ -0,0 +1,475 @@
+//! A calendar of noteworthy astronomical events.
+//!
+//! The calendar gathers the events that can be predicted from the positions computed by this
+//! crate into a single chronological list, which is handy for building almanacs or planning
+//! observations. Each kind of event is found by sampling the relevant quantity over the requested
+//! span of time and refining the moments where it crosses a value or reaches an extremum.
+//!
+//! The events of the Moon are taken from the series of chapters 49 to 51 instead. Eclipses aren't
+//! included yet.
+use crate::angle::Angle;
+use crate::moon::{self, Apsis, Node, Phase};
+use crate::planets::{Aberration, Planet};
+pub use crate::seasons::Season;
+use crate::sun;
+use crate::time::datetime::{DateTime, Precision};
+use crate::time::JD;
+
+/// The mean lengths of the anomalistic and draconic months, in days
+const ANOMALISTIC_MONTH: f64 = 27.554_55;
+const DRACONIC_MONTH: f64 = 27.212_22;
+
+/// The planets other than the Earth, which can be seen from the Earth
+const VISIBLE_PLANETS: [Planet; 7] = [
+    Planet::Mercury,
+    Planet::Venus,
+    Planet::Mars,
+    Planet::Jupiter,
+    Planet::Saturn,
+    Planet::Uranus,
+    Planet::Neptune,
+];
+
+/// The kinds of events found by `calendar`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum EventKind {
+    /// The apparent longitude of the Sun is a multiple of 90°
+    Season(Season),
+    /// An outer planet has the same apparent longitude as the Sun
+    Conjunction(Planet),
+    /// Mercury or Venus has the same apparent longitude as the Sun, and is between the Sun and the
+    /// Earth
+    InferiorConjunction(Planet),
+    /// Mercury or Venus has the same apparent longitude as the Sun, and is beyond the Sun
+    SuperiorConjunction(Planet),
+    /// An outer planet's apparent longitude differs from the Sun's by 180°
+    Opposition(Planet),
+    /// Mercury or Venus is the furthest from the Sun on the sky. The elongation is positive when
+    /// the planet is east of the Sun, i.e. visible in the evening.
+    GreatestElongation { planet: Planet, elongation: Angle },
+    /// A planet is the closest to the Sun
+    Perihelion(Planet),
+    /// A planet is the furthest from the Sun
+    Aphelion(Planet),
+    /// The Moon reaches one of its main phases
+    MoonPhase(Phase),
+    /// The Moon is the closest to or the furthest from the Earth
+    MoonApsis(Apsis),
+    /// The Moon crosses the ecliptic
+    MoonNode(Node),
+}
+
+/// An event and the moment it happens.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Event {
+    pub time: JD,
+    pub kind: EventKind,
+}
+
+impl Event {
+    /// Gets the calendar date and time of the event, rounded to the given precision. Like `time`,
+    /// this is in dynamical time.
+    pub fn date_time(&self, precision: Precision) -> DateTime {
+        DateTime::from_jd(&self.time, precision)
+    }
+}
+
+/// Selects which kinds of events `calendar` searches for. By default all of them are included.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct EventFilter {
+    /// Equinoxes and solstices
+    pub seasons: bool,
+    /// Conjunctions of the planets with the Sun, and oppositions
+    pub conjunctions: bool,
+    /// Greatest elongations of Mercury and Venus
+    pub elongations: bool,
+    /// Perihelia and aphelia of the planets, including the Earth
+    pub apsides: bool,
+    /// Phases of the Moon, its perigees and apogees, and its passages through the nodes
+    pub moon: bool,
+}
+
+impl Default for EventFilter {
+    fn default() -> Self {
+        EventFilter {
+            seasons: true,
+            conjunctions: true,
+            elongations: true,
+            apsides: true,
+            moon: true,
+        }
+    }
+}
+
+/// Lists the events selected by `filter` happening between `start` and `end`, sorted by time.
+///
+/// The geocentric events use apparent positions referred to the mean equinox of date, and the
+/// moments are found to within a few seconds of what those positions give. The events of the Moon
+/// have the accuracy of the functions in the `moon` module, which is about half an hour for the
+/// perigees and a few minutes or better for the others. Times are in dynamical time.
+pub fn calendar(start: &JD, end: &JD, filter: &EventFilter) -> Vec<Event> {
+    let mut events = Vec::new();
+
+    if filter.seasons {
+        let seasons = [
+            Season::MarchEquinox,
+            Season::JuneSolstice,
+            Season::SeptemberEquinox,
+            Season::DecemberSolstice,
+        ];
+        for (i, season) in seasons.iter().enumerate() {
+            let target = Angle::from_degrees(90.0 * i as f64);
+            let difference = |t: &JD| signed_degrees(sun::apparent_longitude(t) - target);
+            for time in find_crossings(difference, start, end, 10.0) {
+                events.push(Event {
+                    time,
+                    kind: EventKind::Season(*season),
+                });
+            }
+        }
+    }
+
+    if filter.conjunctions {
+        for planet in VISIBLE_PLANETS.iter() {
+            let inner = is_inner(*planet);
+            let elongation = |t: &JD| signed_degrees(longitude_from_sun(*planet, t));
+            for time in find_crossings(elongation, start, end, 2.0) {
+                let kind = if !inner {
+                    EventKind::Conjunction(*planet)
+                } else if planet
+                    .get_geocentric_position(&time, Aberration::None)
+                    .distance
+                    < sun::get_geocentric_position(&time, Aberration::None).distance
+                {
+                    EventKind::InferiorConjunction(*planet)
+                } else {
+                    EventKind::SuperiorConjunction(*planet)
+                };
+                events.push(Event { time, kind });
+            }
+
+            if !inner {
+                let opposite =
+                    |t: &JD| signed_degrees(longitude_from_sun(*planet, t) + Angle::STRAIGHT_ANGLE);
+                for time in find_crossings(opposite, start, end, 2.0) {
+                    events.push(Event {
+                        time,
+                        kind: EventKind::Opposition(*planet),
+                    });
+                }
+            }
+        }
+    }
+
+    if filter.elongations {
+        for planet in [Planet::Mercury, Planet::Venus].iter() {
+            let separation = |t: &JD| {
+                let planet = planet.get_geocentric_position(t, Aberration::Planetary);
+                let sun = sun::get_geocentric_position(t, Aberration::Planetary);
+                let (a, b) = (planet.ecliptical, sun.ecliptical);
+                (a.lat().angle().sin() * b.lat().angle().sin()
+                    + a.lat().angle().cos()
+                        * b.lat().angle().cos()
+                        * (a.lon().angle() - b.lon().angle()).cos())
+                .acos()
+            };
+            for (time, maximum) in find_extrema(separation, start, end, 2.0) {
+                if !maximum {
+                    continue;
+                }
+                let magnitude = Angle::from_radians(separation(&time));
+                let elongation = if signed_degrees(longitude_from_sun(*planet, &time)) > 0.0 {
+                    magnitude
+                } else {
+                    -magnitude
+                };
+                events.push(Event {
+                    time,
+                    kind: EventKind::GreatestElongation {
+                        planet: *planet,
+                        elongation,
+                    },
+                });
+            }
+        }
+    }
+
+    if filter.apsides {
+        for planet in VISIBLE_PLANETS.iter().chain([Planet::Earth].iter()) {
+            let radius = |t: &JD| planet.get_location(t).radius;
+            for (time, maximum) in find_extrema(radius, start, end, orbital_period(*planet) / 20.0)
+            {
+                let kind = if maximum {
+                    EventKind::Aphelion(*planet)
+                } else {
+                    EventKind::Perihelion(*planet)
+                };
+                events.push(Event { time, kind });
+            }
+        }
+    }
+
+    if filter.moon {
+        for (phase, time) in moon::phases_after(&(*start - 1e-9)).take_while(|(_, t)| t <= end) {
+            events.push(Event {
+                time,
+                kind: EventKind::MoonPhase(phase),
+            });
+        }
+        for apsis in [Apsis::Perigee, Apsis::Apogee].iter() {
+            let nearest = |t: &JD| moon::nearest_apsis(t, *apsis).instant;
+            for time in find_passages(nearest, start, end, ANOMALISTIC_MONTH) {
+                events.push(Event {
+                    time,
+                    kind: EventKind::MoonApsis(*apsis),
+                });
+            }
+        }
+        for node in [Node::Ascending, Node::Descending].iter() {
+            let nearest = |t: &JD| moon::nearest_node_passage(t, *node);
+            for time in find_passages(nearest, start, end, DRACONIC_MONTH) {
+                events.push(Event {
+                    time,
+                    kind: EventKind::MoonNode(*node),
+                });
+            }
+        }
+    }
+
+    events.sort_by_key(|event| event.time);
+    events
+}
+
+/// Finds the instants between `start` and `end` of a periodic event, given a function that finds
+/// the occurrence nearest to a time and the mean period
+fn find_passages<F: Fn(&JD) -> JD>(nearest: F, start: &JD, end: &JD, period: f64) -> Vec<JD> {
+    let mut passages: Vec<JD> = Vec::new();
+    let mut t = *start;
+    while *end - t > -period {
+        let passage = nearest(&t);
+        let new = passages
+            .last()
+            .is_none_or(|last| passage - *last > period / 2.0);
+        if new && passage >= *start && passage <= *end {
+            passages.push(passage);
+        }
+        t = t + period / 2.0;
+    }
+    passages
+}
+
+fn is_inner(planet: Planet) -> bool {
+    planet == Planet::Mercury || planet == Planet::Venus
+}
+
+/// The approximate sidereal period of a planet's orbit, in days
+fn orbital_period(planet: Planet) -> f64 {
+    match planet {
+        Planet::Mercury => 87.97,
+        Planet::Venus => 224.70,
+        Planet::Earth => 365.26,
+        Planet::Mars => 686.98,
+        Planet::Jupiter => 4_332.6,
+        Planet::Saturn => 10_759.2,
+        Planet::Uranus => 30_685.0,
+        Planet::Neptune => 60_190.0,
+    }
+}
+
+/// The difference between the apparent longitudes of a planet and the Sun
+fn longitude_from_sun(planet: Planet, t: &JD) -> Angle {
+    let planet = planet.get_geocentric_position(t, Aberration::Planetary);
+    let sun = sun::get_geocentric_position(t, Aberration::Planetary);
+    planet.ecliptical.lon().angle() - sun.ecliptical.lon().angle()
+}
+
+/// Brings an angle into the range [-180°, 180°], in degrees
+fn signed_degrees(angle: Angle) -> f64 {
+    angle.normalize_pm180().as_degrees()
+}
+
+/// Finds all the moments between `start` and `end` when `f` goes through zero, sampling it every
+/// `step` days. `f` is an angle in degrees wrapped to [-180°, 180°], so the jumps between the two
+/// ends of that range are not counted.
+fn find_crossings<F: Fn(&JD) -> f64>(f: F, start: &JD, end: &JD, step: f64) -> Vec<JD> {
+    let mut crossings = Vec::new();
+    let mut low = *start;
+    let mut low_value = f(&low);
+    while *end - low > 0.0 {
+        let high = if *end - low > step { low + step } else { *end };
+        let high_value = f(&high);
+        if low_value.signum() != high_value.signum() && (high_value - low_value).abs() < 180.0 {
+            let (mut a, mut a_value, mut b) = (low, low_value, high);
+            while b - a > 1e-6 {
+                let middle = a + (b - a) / 2.0;
+                let middle_value = f(&middle);
+                if middle_value.signum() == a_value.signum() {
+                    a = middle;
+                    a_value = middle_value;
+                } else {
+                    b = middle;
+                }
+            }
+            crossings.push(a + (b - a) / 2.0);
+        }
+        low = high;
+        low_value = high_value;
+    }
+    crossings
+}
+
+/// Finds the local extrema of `f` between `start` and `end`, sampling it every `step` days. Each
+/// extremum is returned along with whether it is a maximum.
+fn find_extrema<F: Fn(&JD) -> f64>(f: F, start: &JD, end: &JD, step: f64) -> Vec<(JD, bool)> {
+    let mut extrema = Vec::new();
+    // Sample one step beyond each end so extrema close to the ends are recognized
+    let count = ((*end - *start) / step).ceil() as usize + 3;
+    let times: Vec<JD> = (0..count)
+        .map(|i| *start + (i as f64 - 1.0) * step)
+        .collect();
+    let values: Vec<f64> = times.iter().map(&f).collect();
+
+    for i in 1..count - 1 {
+        let maximum = values[i] > values[i - 1] && values[i] >= values[i + 1];
+        let minimum = values[i] < values[i - 1] && values[i] <= values[i + 1];
+        if !maximum && !minimum {
+            continue;
+        }
+
+        // Golden section search for the extremum between the neighbouring samples
+        let sign = if maximum { -1.0 } else { 1.0 };
+        let ratio = (5f64.sqrt() - 1.0) / 2.0;
+        let (mut low, mut high) = (times[i - 1], times[i + 1]);
+        while high - low > 1e-6 {
+            let left = high - (high - low) * ratio;
+            let right = low + (high - low) * ratio;
+            if sign * f(&left) < sign * f(&right) {
+                high = right;
+            } else {
+                low = left;
+            }
+        }
+
+        let t = low + (high - low) / 2.0;
+        if t - *start >= 0.0 && *end - t >= 0.0 {
+            extrema.push((t, maximum));
+        }
+    }
+    extrema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn seasons() {
+        // The seasons of 2000, rounded to the minute. Without nutation the longitude of the Sun is
+        // off by up to 17″, which moves the seasons by up to seven minutes.
+        let filter = EventFilter {
+            seasons: true,
+            conjunctions: false,
+            elongations: false,
+            apsides: false,
+            moon: false,
+        };
+        let events = calendar(&JD::from(2_451_544.5), &JD::from(2_451_910.5), &filter);
+        let expected = [
+            (Season::MarchEquinox, 2_451_623.816_7),
+            (Season::JuneSolstice, 2_451_716.575_7),
+            (Season::SeptemberEquinox, 2_451_810.227_8),
+            (Season::DecemberSolstice, 2_451_900.068_1),
+        ];
+        assert_eq!(events.len(), expected.len());
+        for (event, (season, jde)) in events.iter().zip(expected.iter()) {
+            assert_eq!(event.kind, EventKind::Season(*season));
+            assert_approx_eq!(event.time.as_f64(), *jde, 0.005);
+        }
+    }
+
+    #[test]
+    fn mercury_events() {
+        // Example 36.a, page 252: inferior conjunction of Mercury on 1993 November 6
+        let filter = EventFilter {
+            seasons: false,
+            conjunctions: true,
+            elongations: true,
+            apsides: false,
+            moon: false,
+        };
+        let events = calendar(&JD::from(2_449_220.0), &JD::from(2_449_320.0), &filter);
+        let conjunction = events
+            .iter()
+            .find(|e| e.kind == EventKind::InferiorConjunction(Planet::Mercury))
+            .unwrap();
+        assert_approx_eq!(conjunction.time.as_f64(), 2_449_297.644_5, 0.01);
+
+        // The list is sorted
+        for pair in events.windows(2) {
+            assert!(pair[1].time - pair[0].time >= 0.0);
+        }
+    }
+
+    #[test]
+    fn earth_perihelion() {
+        // Example 38.a, page 270, gives the perihelion of the Earth on 1990 January 4 at 17h
+        let filter = EventFilter {
+            seasons: false,
+            conjunctions: false,
+            elongations: false,
+            apsides: true,
+            moon: false,
+        };
+        let events = calendar(&JD::from(2_447_892.5), &JD::from(2_447_900.5), &filter);
+        let perihelion = events
+            .iter()
+            .find(|e| e.kind == EventKind::Perihelion(Planet::Earth))
+            .unwrap();
+        assert_approx_eq!(perihelion.time.as_f64(), 2_447_896.2, 0.1);
+    }
+
+    #[test]
+    fn moon_events() {
+        // October 1988, with the apogee of Example 50.a on the 7th
+        let filter = EventFilter {
+            seasons: false,
+            conjunctions: false,
+            elongations: false,
+            apsides: false,
+            moon: true,
+        };
+        let (start, end) = (JD::from(2_447_435.5), JD::from(2_447_466.5));
+        let events = calendar(&start, &end, &filter);
+        let apogee = events
+            .iter()
+            .find(|e| e.kind == EventKind::MoonApsis(Apsis::Apogee))
+            .unwrap();
+        assert_approx_eq!(apogee.time.as_f64(), 2_447_442.354_3, 1e-4);
+
+        // Every phase, apsis and node passage of the month is there once, at the instant given by
+        // the moon module
+        let count = |kind: EventKind| events.iter().filter(|e| e.kind == kind).count();
+        for (phase, time) in moon::phases_after(&start).take_while(|(_, t)| *t <= end) {
+            assert_eq!(count(EventKind::MoonPhase(phase)), 1);
+            assert!(events.contains(&Event {
+                time,
+                kind: EventKind::MoonPhase(phase)
+            }));
+        }
+        assert_eq!(count(EventKind::MoonApsis(Apsis::Apogee)), 1);
+        assert_eq!(count(EventKind::MoonApsis(Apsis::Perigee)), 1);
+        assert_eq!(
+            count(EventKind::MoonNode(Node::Ascending))
+                + count(EventKind::MoonNode(Node::Descending)),
+            2
+        );
+        for event in events.iter() {
+            assert!(event.time >= start && event.time <= end);
+        }
+        for pair in events.windows(2) {
+            assert!(pair[1].time - pair[0].time >= 0.0);
+        }
+    }
+}
@@ -0,0 +1,227 @@
+//! Relationships between the reference frames used in this crate.
+//!
+//! The VSOP87 theory gives positions referred to the dynamical mean equator and equinox of J2000,
+//! while modern star catalogs are referred to the International Celestial Reference System
+//! (ICRS). The two frames are nearly identical, but the axes differ by a few tens of
+//! milliarcseconds. Precise work needs to account for that small "frame bias" rotation.
+//!
+//! Older catalogs are referred to the B1950 equinox of the FK4 system, which differs from the
+//! modern FK5 system by more than just precession. FK4 positions include the elliptic terms of
+//! aberration, and the FK4 equinox was slightly misplaced.
+//!
+//! The dynamical ecliptic and equinox of VSOP87 also differ very slightly from the ones of the
+//! FK5 system, by about a tenth of an arcsecond.
+use crate::angle::Angle;
+use crate::coords::{Ecliptical, Equatorial, Equinox, B1950, J2000};
+use crate::linalg::{Mat3, Vec3};
+use crate::time::JulianCenturies;
+
+/// Offset in right ascension of the J2000 mean equinox relative to the ICRS origin, in arcseconds
+const DELTA_ALPHA_0: f64 = -0.014_60;
+/// Offset of the J2000 mean pole relative to the ICRS pole along the x axis, in arcseconds
+const XI_0: f64 = -0.016_617_0;
+/// Offset of the J2000 mean pole relative to the ICRS pole along the y axis, in arcseconds
+const ETA_0: f64 = -0.006_819_2;
+
+/// Computes the frame bias matrix, which rotates rectangular coordinates in the ICRS into the
+/// dynamical mean equator and equinox of J2000.
+///
+/// The offsets are the ones adopted by the IERS Conventions (2003), and the matrix is accurate to
+/// second order in those offsets.
+pub fn frame_bias() -> Mat3 {
+    let arcsec = Angle::from_arcseconds(1.0).as_radians();
+    let da = DELTA_ALPHA_0 * arcsec;
+    let xi = XI_0 * arcsec;
+    let eta = ETA_0 * arcsec;
+
+    Mat3([
+        [1.0 - (da * da + xi * xi) / 2.0, da, -xi],
+        [-da - eta * xi, 1.0 - (da * da + eta * eta) / 2.0, -eta],
+        [
+            xi - eta * da,
+            eta + xi * da,
+            1.0 - (eta * eta + xi * xi) / 2.0,
+        ],
+    ])
+}
+
+/// Rotates a rectangular vector from the ICRS into the dynamical J2000 frame.
+pub fn icrs_to_j2000(v: &Vec3) -> Vec3 {
+    frame_bias() * *v
+}
+
+/// Rotates a rectangular vector from the dynamical J2000 frame into the ICRS.
+pub fn j2000_to_icrs(v: &Vec3) -> Vec3 {
+    frame_bias().transpose() * *v
+}
+
+/// Computes the matrix rotating rectangular coordinates referred to the ecliptic and equinox of
+/// J2000 into the equator and equinox of J2000. This is a rotation about the x axis, which points
+/// towards the equinox, by the obliquity of the ecliptic at J2000.
+fn ecliptic_rotation() -> Mat3 {
+    Mat3::rotation_x(Angle::from_radians(J2000::OBLIQUITY))
+}
+
+/// Rotates a rectangular vector from the ecliptic of J2000 to the equator of J2000, for example
+/// to combine VSOP87 positions with a star catalog in rectangular form.
+pub fn ecliptic_to_equatorial(v: &Vec3) -> Vec3 {
+    ecliptic_rotation() * *v
+}
+
+/// Rotates a rectangular vector from the equator of J2000 to the ecliptic of J2000.
+pub fn equatorial_to_ecliptic(v: &Vec3) -> Vec3 {
+    ecliptic_rotation().transpose() * *v
+}
+
+/// Converts ecliptical coordinates computed from VSOP87 into the FK5 system, using formula 32.3.
+///
+/// The correction is below a tenth of an arcsecond, and works for any equinox the coordinates
+/// have been precessed to.
+pub fn vsop87_to_fk5<E: Equinox>(position: &Ecliptical<E>) -> Ecliptical<E> {
+    let big_t = JulianCenturies::from(position.equinox().epoch()).0;
+    let lambda_prime =
+        position.lon().angle() - Angle::from_degrees(1.397 * big_t + 0.000_31 * big_t * big_t);
+    let (sin_l, cos_l) = lambda_prime.sin_cos();
+    let beta_tan = position.lat().angle().tan();
+
+    Ecliptical::from_angles_with_equinox(
+        position.lon().angle()
+            + Angle::from_arcseconds(-0.090_33 + 0.039_16 * (cos_l + sin_l) * beta_tan),
+        position.lat().angle() + Angle::from_arcseconds(0.039_16 * (cos_l - sin_l)),
+        position.equinox(),
+    )
+}
+
+/// The elliptic terms of aberration (E-terms) included in FK4 positions, as a rectangular vector
+const E_TERMS: Vec3 = Vec3 {
+    x: -1.625_57e-6,
+    y: -0.319_19e-6,
+    z: -0.138_43e-6,
+};
+
+/// Rotates FK4 positions at the B1950 equinox, with the E-terms removed, into FK5 at J2000. This
+/// includes the precession and the correction to the FK4 equinox.
+const FK4_TO_FK5: Mat3 = Mat3([
+    [0.999_925_678_2, -0.011_182_061_1, -0.004_857_947_7],
+    [0.011_182_061_0, 0.999_937_478_4, -0.000_027_176_5],
+    [0.004_857_947_9, -0.000_027_147_4, 0.999_988_199_7],
+]);
+
+/// Converts a position from the FK4 catalog system at the B1950 equinox to the FK5 system at the
+/// J2000 equinox.
+///
+/// The E-terms of aberration are removed before rotating into FK5. The position is assumed to be
+/// for the epoch B1950 and no proper motion is applied.
+pub fn fk4_to_fk5(position: &Equatorial<B1950>) -> Equatorial<J2000> {
+    let r = position.to_rectangular();
+    let without_e_terms = r - E_TERMS + r * r.dot(&E_TERMS);
+    Equatorial::from_vector(&(FK4_TO_FK5 * without_e_terms))
+}
+
+/// Converts a position from the FK5 system at the J2000 equinox to the FK4 catalog system at the
+/// B1950 equinox, adding back the E-terms of aberration.
+pub fn fk5_to_fk4(position: &Equatorial<J2000>) -> Equatorial<B1950> {
+    let r = (FK4_TO_FK5.inverse() * position.to_rectangular()).normalize();
+
+    // The E-terms depend on the final direction, which is found by iterating
+    let mut with_e_terms = r;
+    for _ in 0..3 {
+        with_e_terms = (r + E_TERMS - with_e_terms * with_e_terms.dot(&E_TERMS)).normalize();
+    }
+    Equatorial::from_vector(&with_e_terms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::angle::Angle;
+    use crate::coords::Ecliptical;
+    use crate::precession;
+    use crate::time::JD;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn icrs_origin() {
+        // The ICRS origin of right ascension lies 14.6 mas east of the J2000 equinox, and the
+        // poles are offset by about 17 mas
+        let v = icrs_to_j2000(&Vec3::new(1.0, 0.0, 0.0));
+        let ra = Angle::atan2(v.y, v.x);
+        let dec = Angle::asin(v.z);
+        assert_approx_eq!(ra.as_arcseconds(), 0.0146, 1e-6);
+        assert_approx_eq!(dec.as_arcseconds(), -0.016_617, 1e-6);
+    }
+
+    #[test]
+    fn vsop87_correction() {
+        // On the ecliptic of J2000 only the constant part of the correction in longitude remains
+        let position = Ecliptical::<J2000>::new(Angle::from_degrees(45.0), Angle::ZERO).unwrap();
+        let fk5 = vsop87_to_fk5(&position);
+        assert_approx_eq!(
+            (fk5.lon().angle() - position.lon().angle()).as_arcseconds(),
+            -0.090_33,
+            1e-9
+        );
+        assert_approx_eq!(fk5.lat().angle().as_arcseconds(), 0.0, 1e-9);
+
+        let position = Ecliptical::<J2000>::new(Angle::ZERO, Angle::ZERO).unwrap();
+        assert_approx_eq!(
+            vsop87_to_fk5(&position).lat().angle().as_arcseconds(),
+            0.039_16,
+            1e-9
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let v = Vec3::new(0.3, -0.5, 0.81);
+        let back = j2000_to_icrs(&icrs_to_j2000(&v));
+        assert_approx_eq!(back.x, v.x, 1e-15);
+        assert_approx_eq!(back.y, v.y, 1e-15);
+        assert_approx_eq!(back.z, v.z, 1e-15);
+    }
+
+    #[test]
+    fn ecliptic_equatorial_rotation() {
+        // Agrees with the spherical conversion
+        let ecliptical = Ecliptical::<J2000>::new(
+            Angle::from_degrees(113.215_63),
+            Angle::from_degrees(6.684_17),
+        )
+        .unwrap();
+        let latitude = ecliptical.lat().angle();
+        let longitude = ecliptical.lon().angle();
+        let v = Vec3::new(
+            2.0 * latitude.cos() * longitude.cos(),
+            2.0 * latitude.cos() * longitude.sin(),
+            2.0 * latitude.sin(),
+        );
+        let rotated = ecliptic_to_equatorial(&v);
+        assert_approx_eq!(rotated.norm(), 2.0, 1e-15);
+        let expected = ecliptical.to_equatorial().to_rectangular() * 2.0;
+        assert_approx_eq!(rotated.x, expected.x, 1e-15);
+        assert_approx_eq!(rotated.y, expected.y, 1e-15);
+        assert_approx_eq!(rotated.z, expected.z, 1e-15);
+
+        let back = equatorial_to_ecliptic(&rotated);
+        assert_approx_eq!(back.x, v.x, 1e-15);
+        assert_approx_eq!(back.y, v.y, 1e-15);
+        assert_approx_eq!(back.z, v.z, 1e-15);
+    }
+
+    #[test]
+    fn fk4_fk5_round_trip() {
+        let fk4 =
+            Equatorial::<B1950>::new(Angle::from_degrees(10.0), Angle::from_degrees(40.0)).unwrap();
+        let fk5 = fk4_to_fk5(&fk4);
+        let back = fk5_to_fk4(&fk5);
+        assert_approx_eq!(back.ra().angle().as_degrees(), 10.0, 1e-8);
+        assert_approx_eq!(back.dec().angle().as_degrees(), 40.0, 1e-8);
+
+        // The conversion is close to a bare precession, but the E-terms and the equinox correction
+        // move the position by a fraction of an arcsecond
+        let p = precession::matrix(&JD::from(2_433_282.423_5), &JD::J2000);
+        let precessed = Equatorial::<J2000>::from_rectangular(&(p * fk4.to_rectangular())).unwrap();
+        let difference = (fk5.ra().angle() - precessed.ra().angle()).as_arcseconds();
+        assert!(difference.abs() > 0.1 && difference.abs() < 1.0);
+    }
+}
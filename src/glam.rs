@@ -0,0 +1,69 @@
+//! Conversions between the rectangular types and `glam`, enabled by the `glam` feature.
+//!
+//! Vectors convert to and from `DVec3`, and matrices to and from `DMat3`. Positions keep their
+//! units, astronomical units for `HeliocentricRectangular`.
+use crate::coords::HeliocentricRectangular;
+use crate::linalg::{Mat3, Vec3};
+
+use ::glam::{DMat3, DVec3};
+
+impl From<Vec3> for DVec3 {
+    fn from(item: Vec3) -> Self {
+        DVec3::new(item.x, item.y, item.z)
+    }
+}
+
+impl From<DVec3> for Vec3 {
+    fn from(item: DVec3) -> Self {
+        Vec3::new(item.x, item.y, item.z)
+    }
+}
+
+impl From<HeliocentricRectangular> for DVec3 {
+    fn from(item: HeliocentricRectangular) -> Self {
+        DVec3::new(item.x, item.y, item.z)
+    }
+}
+
+impl From<DVec3> for HeliocentricRectangular {
+    fn from(item: DVec3) -> Self {
+        HeliocentricRectangular::new(item.x, item.y, item.z)
+    }
+}
+
+impl From<Mat3> for DMat3 {
+    /// `glam` stores its matrices by columns, while `Mat3` stores them by rows
+    fn from(item: Mat3) -> Self {
+        DMat3::from_cols_array_2d(&item.transpose().0)
+    }
+}
+
+impl From<DMat3> for Mat3 {
+    fn from(item: DMat3) -> Self {
+        Mat3(item.to_cols_array_2d()).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::angle::Angle;
+
+    #[test]
+    fn conversions() {
+        let m = Mat3::rotation_z(Angle::from_degrees(30.0))
+            * Mat3::rotation_x(Angle::from_degrees(-10.0));
+        let v = Vec3::new(0.25, -1.5, 3.0);
+
+        // Products agree on both sides, so the rows and columns are not swapped
+        let product: Vec3 = (DMat3::from(m) * DVec3::from(v)).into();
+        assert!((product - m * v).norm() < 1e-15);
+        assert_eq!(Mat3::from(DMat3::from(m)), m);
+        assert_eq!(Vec3::from(DVec3::from(v)), v);
+
+        let position = HeliocentricRectangular::new(0.5, 0.25, -0.125);
+        let vector = DVec3::from(position);
+        assert_eq!(vector, DVec3::new(0.5, 0.25, -0.125));
+        assert_eq!(HeliocentricRectangular::from(vector), position);
+    }
+}
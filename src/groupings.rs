@@ -0,0 +1,324 @@
+//! Geometric relationships between several bodies on the sky.
+//!
+//! These implement the tests described in chapters 19 and 20, which are useful for finding
+//! noteworthy configurations of planets and bright stars.
+use crate::angle::Angle;
+use crate::coords::{Equatorial, Equinox};
+use crate::time::JD;
+
+/// Evaluates formula 19.1 for three positions on the sky, which is zero when the three bodies lie
+/// on a great circle, i.e. appear to be in a straight line.
+///
+/// The sign of the result tells on which side of the great circle through the first and last
+/// positions the middle one lies, so a change of sign over time shows that the bodies passed
+/// through a straight line.
+pub fn collinearity<E: Equinox>(
+    first: &Equatorial<E>,
+    second: &Equatorial<E>,
+    third: &Equatorial<E>,
+) -> f64 {
+    let (a1, d1) = (first.ra().angle(), first.dec().angle());
+    let (a2, d2) = (second.ra().angle(), second.dec().angle());
+    let (a3, d3) = (third.ra().angle(), third.dec().angle());
+
+    d1.tan() * (a2 - a3).sin() + d2.tan() * (a3 - a1).sin() + d3.tan() * (a1 - a2).sin()
+}
+
+/// Computes the angular distance of the middle body from the great circle through the first and
+/// last ones, which tells how far three bodies are from appearing in a straight line.
+pub fn deviation_from_great_circle<E: Equinox>(
+    first: &Equatorial<E>,
+    middle: &Equatorial<E>,
+    last: &Equatorial<E>,
+) -> Angle {
+    let pole = first.to_rectangular().cross(&last.to_rectangular());
+    Angle::asin(pole.normalize().dot(&middle.to_rectangular()).abs())
+}
+
+/// Searches for the first moment between `start` and `end` when three bodies appear in a straight
+/// line.
+///
+/// `positions` gives the positions of the three bodies at a given time. The function is sampled
+/// every `step` days looking for a change in the sign of `collinearity`, and the moment of the
+/// alignment is then refined by bisection. The step should be short enough that the bodies can't
+/// pass through a straight line and back in between two samples.
+///
+/// Returns `None` if the bodies never appear in a straight line during the search interval.
+pub fn find_collinearity<E, F>(positions: F, start: &JD, end: &JD, step: f64) -> Option<JD>
+where
+    E: Equinox,
+    F: Fn(&JD) -> [Equatorial<E>; 3],
+{
+    assert!(step > 0.0, "Invalid search step: {}", step);
+    let value = |t: &JD| {
+        let [first, second, third] = positions(t);
+        collinearity(&first, &second, &third)
+    };
+
+    let mut low = *start;
+    let mut low_value = value(&low);
+    while *end - low > 0.0 {
+        let high = if *end - low > step { low + step } else { *end };
+        let high_value = value(&high);
+        if low_value == 0.0 {
+            return Some(low);
+        }
+        if low_value.signum() != high_value.signum() {
+            return Some(bisect(&value, low, low_value, high));
+        }
+        low = high;
+        low_value = high_value;
+    }
+
+    if low_value == 0.0 {
+        Some(low)
+    } else {
+        None
+    }
+}
+
+/// Narrows down a sign change of `f` between `low` and `high` to about a millisecond
+fn bisect<F: Fn(&JD) -> f64>(f: &F, mut low: JD, mut low_value: f64, mut high: JD) -> JD {
+    while high - low > 1e-8 {
+        let middle = low + (high - low) / 2.0;
+        let middle_value = f(&middle);
+        if middle_value.signum() == low_value.signum() {
+            low = middle;
+            low_value = middle_value;
+        } else {
+            high = middle;
+        }
+    }
+    low + (high - low) / 2.0
+}
+
+/// Computes the diameter of the smallest circle on the sky containing three bodies, following
+/// chapter 20.
+///
+/// The formula treats the sky as flat, which is fine for the small circles this is typically used
+/// for, but it becomes inaccurate when the bodies are more than a few tens of degrees apart.
+pub fn smallest_circle<E: Equinox>(
+    first: &Equatorial<E>,
+    second: &Equatorial<E>,
+    third: &Equatorial<E>,
+) -> Angle {
+    let mut sides = [
+        separation(second, third),
+        separation(first, third),
+        separation(first, second),
+    ];
+    sides.sort_by(|x, y| y.partial_cmp(x).unwrap());
+    let [a, b, c] = sides;
+
+    if a * a >= b * b + c * c {
+        // The triangle isn't acute, so the longest side is the diameter
+        Angle::from_radians(a)
+    } else {
+        Angle::from_radians(
+            2.0 * a * b * c / ((a + b + c) * (a + b - c) * (b + c - a) * (a + c - b)).sqrt(),
+        )
+    }
+}
+
+/// Searches for the moment between `start` and `end` when the smallest circle containing three
+/// moving bodies is the smallest, returning that moment and the diameter of the circle.
+///
+/// `positions` gives the positions of the three bodies at a given time. The diameter is sampled
+/// every `step` days and the smallest sample is then refined with a golden section search. The
+/// step should be short enough that the diameter only has one minimum between any two samples.
+pub fn find_smallest_circle<E, F>(positions: F, start: &JD, end: &JD, step: f64) -> (JD, Angle)
+where
+    E: Equinox,
+    F: Fn(&JD) -> [Equatorial<E>; 3],
+{
+    assert!(step > 0.0, "Invalid search step: {}", step);
+    let diameter = |t: &JD| {
+        let [first, second, third] = positions(t);
+        smallest_circle(&first, &second, &third).as_radians()
+    };
+
+    let mut best = *start;
+    let mut best_value = diameter(&best);
+    let mut t = *start;
+    while *end - t > 0.0 {
+        t = if *end - t > step { t + step } else { *end };
+        let value = diameter(&t);
+        if value < best_value {
+            best = t;
+            best_value = value;
+        }
+    }
+
+    // Refine within a step on each side of the best sample, without leaving the search interval
+    let mut low = if best - *start > step {
+        best - step
+    } else {
+        *start
+    };
+    let mut high = if *end - best > step {
+        best + step
+    } else {
+        *end
+    };
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    while high - low > 1e-8 {
+        let left = high - (high - low) * ratio;
+        let right = low + (high - low) * ratio;
+        if diameter(&left) < diameter(&right) {
+            high = right;
+        } else {
+            low = left;
+        }
+    }
+
+    let t = low + (high - low) / 2.0;
+    (t, Angle::from_radians(diameter(&t)))
+}
+
+/// The angular distance between two positions, in radians
+fn separation<E: Equinox>(first: &Equatorial<E>, second: &Equatorial<E>) -> f64 {
+    first.separation(second).as_radians()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::angle::{DegreesMinutesSeconds, HoursMinutesSeconds, Sign};
+    use crate::coords::J2000;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn straight_line() {
+        // Three points on the celestial equator are always in a straight line
+        let on_equator = |ra: f64| {
+            Equatorial::<J2000>::new(Angle::from_degrees(ra), Angle::from_degrees(0.0)).unwrap()
+        };
+        assert!(
+            collinearity(&on_equator(10.0), &on_equator(20.0), &on_equator(30.0)).abs() < 1e-15
+        );
+
+        // A body moving across the equator lines up with two fixed ones on it when it crosses
+        let start = JD::from(2_451_545.0);
+        let moving = |t: &JD| {
+            [
+                on_equator(10.0),
+                Equatorial::<J2000>::new(
+                    Angle::from_degrees(20.0),
+                    Angle::from_degrees(2.0 - 0.5 * (*t - start)),
+                )
+                .unwrap(),
+                on_equator(30.0),
+            ]
+        };
+        let found = find_collinearity(moving, &start, &(start + 10.0), 1.0).unwrap();
+        assert!((found - (start + 4.0)).abs() < 1e-6);
+
+        assert!(find_collinearity(moving, &start, &(start + 3.0), 1.0).is_none());
+    }
+
+    #[test]
+    fn deviation() {
+        // Example 19.b, the deviation of ε Orionis from the line from δ to ζ Orionis
+        let star = |h, m, s, d, dm, ds| {
+            Equatorial::<J2000>::new(
+                HoursMinutesSeconds {
+                    sign: Sign::Positive,
+                    hours: h,
+                    minutes: m,
+                    seconds: s,
+                }
+                .as_angle(),
+                DegreesMinutesSeconds {
+                    sign: Sign::Negative,
+                    degrees: d,
+                    minutes: dm,
+                    seconds: ds,
+                }
+                .as_angle(),
+            )
+            .unwrap()
+        };
+        let delta = star(5, 32, 0.40, 0, 17, 56.9);
+        let epsilon = star(5, 36, 12.81, 1, 12, 7.0);
+        let zeta = star(5, 40, 45.52, 1, 56, 33.3);
+        let omega = deviation_from_great_circle(&delta, &epsilon, &zeta);
+        assert_approx_eq!(omega.as_arcseconds(), 5.0 * 60.0 + 24.0, 1.0);
+    }
+
+    #[test]
+    fn smallest_circle_diameter() {
+        // Example 20.a, page 128
+        let mercury = Equatorial::<J2000>::new(
+            HoursMinutesSeconds {
+                sign: Sign::Positive,
+                hours: 12,
+                minutes: 41,
+                seconds: 8.63,
+            }
+            .as_angle(),
+            DegreesMinutesSeconds {
+                sign: Sign::Negative,
+                degrees: 5,
+                minutes: 37,
+                seconds: 54.2,
+            }
+            .as_angle(),
+        )
+        .unwrap();
+        let jupiter = Equatorial::<J2000>::new(
+            HoursMinutesSeconds {
+                sign: Sign::Positive,
+                hours: 12,
+                minutes: 52,
+                seconds: 5.21,
+            }
+            .as_angle(),
+            DegreesMinutesSeconds {
+                sign: Sign::Negative,
+                degrees: 4,
+                minutes: 22,
+                seconds: 26.2,
+            }
+            .as_angle(),
+        )
+        .unwrap();
+        let saturn = Equatorial::<J2000>::new(
+            HoursMinutesSeconds {
+                sign: Sign::Positive,
+                hours: 12,
+                minutes: 39,
+                seconds: 28.11,
+            }
+            .as_angle(),
+            DegreesMinutesSeconds {
+                sign: Sign::Negative,
+                degrees: 1,
+                minutes: 50,
+                seconds: 3.7,
+            }
+            .as_angle(),
+        )
+        .unwrap();
+        let diameter = smallest_circle(&mercury, &jupiter, &saturn);
+        assert_approx_eq!(diameter.as_degrees(), 4.2636, 1e-4); // 4°15′49″
+    }
+
+    #[test]
+    fn smallest_circle_search() {
+        // Two bodies moving away from a fixed one in perpendicular directions, closest together at
+        // the start plus three days
+        let start = JD::from(2_451_545.0);
+        let positions = |t: &JD| {
+            let d = 1.0 + (*t - start - 3.0).powi(2);
+            [
+                Equatorial::<J2000>::new(Angle::from_degrees(0.0), Angle::from_degrees(0.0))
+                    .unwrap(),
+                Equatorial::<J2000>::new(Angle::from_degrees(d), Angle::from_degrees(0.0)).unwrap(),
+                Equatorial::<J2000>::new(Angle::from_degrees(0.0), Angle::from_degrees(d)).unwrap(),
+            ]
+        };
+        let (t, diameter) = find_smallest_circle(positions, &start, &(start + 10.0), 1.0);
+        assert_approx_eq!(t - start, 3.0, 1e-4);
+        assert_approx_eq!(diameter.as_degrees(), 2f64.sqrt(), 1e-4);
+    }
+}
@@ -0,0 +1,136 @@
+//! Interpolation of tabulated values.
+//!
+//! Astronomical quantities are often tabulated at equally spaced intervals, and intermediate values
+//! are found by interpolation. These implement the methods described in chapter 3, where `n` is the
+//! interpolating factor measured in units of the tabular interval from the central value.
+use crate::coords::{Equatorial, Equinox};
+use crate::time::JD;
+
+/// Interpolates from three equally spaced values, using formula 3.3.
+///
+/// `n` is measured from the central value, so `n == 0.0` gives back `y[1]`. The interpolation is
+/// most accurate for `-0.5 <= n <= 0.5`.
+pub fn interpolate3(y: &[f64; 3], n: f64) -> f64 {
+    let a = y[1] - y[0];
+    let b = y[2] - y[1];
+    let c = b - a;
+
+    y[1] + n / 2.0 * (a + b + n * c)
+}
+
+/// Interpolates from five equally spaced values, using formula 3.8.
+///
+/// `n` is measured from the central value, so `n == 0.0` gives back `y[2]`. The interpolation is
+/// most accurate for `-0.5 <= n <= 0.5`.
+pub fn interpolate5(y: &[f64; 5], n: f64) -> f64 {
+    let a = y[1] - y[0];
+    let b = y[2] - y[1];
+    let c = y[3] - y[2];
+    let d = y[4] - y[3];
+    let e = b - a;
+    let f = c - b;
+    let g = d - c;
+    let h = f - e;
+    let j = g - f;
+    let k = j - h;
+
+    let n2 = n * n;
+    y[2] + n / 2.0 * (b + c)
+        + n2 / 2.0 * f
+        + n * (n2 - 1.0) / 12.0 * (h + j)
+        + n2 * (n2 - 1.0) / 24.0 * k
+}
+
+/// Interpolates a direction from positions tabulated at increasing times, such as a coarse
+/// ephemeris of a comet or of the Moon. Between two consecutive samples the direction moves along
+/// the great circle joining them.
+///
+/// Returns `None` if `t` is outside the range of the samples, or if the times of the samples
+/// aren't strictly increasing.
+pub fn interpolate_great_circle<E: Equinox>(
+    samples: &[(JD, Equatorial<E>)],
+    t: &JD,
+) -> Option<Equatorial<E>> {
+    let (first, last) = (samples.first()?, samples.last()?);
+    if *t < first.0 || *t > last.0 || samples.windows(2).any(|pair| pair[0].0 >= pair[1].0) {
+        return None;
+    }
+
+    if samples.len() == 1 {
+        return Some(first.1);
+    }
+
+    // The first sample after `t`, or the last one when `t` is exactly on it
+    let index = samples
+        .partition_point(|(time, _)| time <= t)
+        .clamp(1, samples.len() - 1);
+    let (start_time, start) = &samples[index - 1];
+    let (end_time, end) = &samples[index];
+    Some(start.slerp(end, (*t - *start_time) / (*end_time - *start_time)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::angle::Angle;
+    use crate::coords::J2000;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn three_values() {
+        // Example 3.a, page 25
+        let y = [0.884_226, 0.877_366, 0.870_531];
+        assert_approx_eq!(interpolate3(&y, 0.18125), 0.876_125, 1e-6);
+        assert_eq!(interpolate3(&y, 0.0), y[1]);
+    }
+
+    #[test]
+    fn five_values() {
+        // Five point interpolation is exact for polynomials up to the fourth degree
+        let p = |x: f64| 3.0 * x.powi(4) - 2.0 * x.powi(3) + x * x - 7.0 * x + 0.5;
+        let y = [p(-2.0), p(-1.0), p(0.0), p(1.0), p(2.0)];
+        for &n in &[-0.5, -0.25, 0.0, 0.3, 0.5, 1.7] {
+            assert_approx_eq!(interpolate5(&y, n), p(n), 1e-12);
+        }
+    }
+
+    #[test]
+    fn great_circle() {
+        let sample = |day: f64, ra: f64, dec: f64| {
+            (
+                JD::from(2_451_545.0 + day),
+                Equatorial::<J2000>::new(Angle::from_degrees(ra), Angle::from_degrees(dec))
+                    .unwrap(),
+            )
+        };
+        let samples = [
+            sample(0.0, 10.0, 0.0),
+            sample(1.0, 12.0, 0.0),
+            sample(2.0, 12.0, 3.0),
+        ];
+
+        let t = JD::from(2_451_545.25);
+        let position = interpolate_great_circle(&samples, &t).unwrap();
+        assert_approx_eq!(position.ra().angle().as_degrees(), 10.5, 1e-12);
+
+        let t = JD::from(2_451_546.5);
+        let position = interpolate_great_circle(&samples, &t).unwrap();
+        assert_approx_eq!(position.ra().angle().as_degrees(), 12.0, 1e-12);
+        assert_approx_eq!(position.dec().angle().as_degrees(), 1.5, 1e-12);
+
+        let last = interpolate_great_circle(&samples, &JD::from(2_451_547.0)).unwrap();
+        assert_approx_eq!(last.dec().angle().as_degrees(), 3.0, 1e-12);
+        assert!(interpolate_great_circle(&samples, &JD::from(2_451_547.5)).is_none());
+        assert!(interpolate_great_circle::<J2000>(&[], &JD::from(2_451_545.0)).is_none());
+        assert_eq!(
+            interpolate_great_circle(&samples[..1], &JD::from(2_451_545.0)),
+            Some(samples[0].1)
+        );
+
+        // The samples must be in order, without two at the same time
+        let unsorted = [samples[1], samples[0], samples[2]];
+        assert!(interpolate_great_circle(&unsorted, &t).is_none());
+        let repeated = [samples[0], samples[1], samples[1], samples[2]];
+        assert!(interpolate_great_circle(&repeated, &t).is_none());
+    }
+}
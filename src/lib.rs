@@ -5,7 +5,38 @@
 //! utilities dealing with andles and time. The time representation is somewhat different than is
 //! typical.
 
+pub mod aberration;
 pub mod angle;
+#[cfg(feature = "approx")]
+mod approx;
+pub mod bodies;
 pub mod coords;
+pub mod ephemeris;
+pub mod events;
+pub mod frames;
+#[cfg(feature = "glam")]
+mod glam;
+pub mod groupings;
+pub mod interpolation;
+pub mod linalg;
+pub mod moon;
+#[cfg(feature = "nalgebra")]
+mod nalgebra;
+pub mod nutation;
+pub mod observation;
+pub mod observer;
+pub mod parallax;
 pub mod planets;
+pub mod precession;
+pub mod refraction;
+pub mod seasons;
+#[cfg(feature = "serde")]
+pub mod serde;
+pub mod stars;
+pub mod sun;
+pub mod sundial;
 pub mod time;
+pub mod transit;
+
+/// The length of the astronomical unit, in meters
+pub const METERS_PER_AU: f64 = 149_597_870_700.0;
@@ -0,0 +1,238 @@
+//! Small vector and matrix types for working with rectangular coordinates.
+//!
+//! Many of the reductions in astronomy (precession, nutation, frame changes) are most naturally
+//! expressed as rotations of rectangular coordinates. These types keep that math readable without
+//! pulling in a general purpose linear algebra crate.
+use crate::angle::Angle;
+
+/// A three element column vector.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Vec3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Vec3 {
+    pub fn new(x: f64, y: f64, z: f64) -> Vec3 {
+        Vec3 { x, y, z }
+    }
+
+    /// Computes the dot product of two vectors
+    pub fn dot(&self, rhs: &Vec3) -> f64 {
+        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+    }
+
+    /// Computes the cross product of two vectors
+    pub fn cross(&self, rhs: &Vec3) -> Vec3 {
+        Vec3::new(
+            self.y * rhs.z - self.z * rhs.y,
+            self.z * rhs.x - self.x * rhs.z,
+            self.x * rhs.y - self.y * rhs.x,
+        )
+    }
+
+    /// Computes the length of the vector
+    pub fn norm(&self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    /// Scales the vector to a length of one
+    pub fn normalize(&self) -> Vec3 {
+        *self * (1.0 / self.norm())
+    }
+}
+
+/// A 3x3 matrix, stored as an array of rows.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Mat3(pub [[f64; 3]; 3]);
+
+impl Mat3 {
+    /// The identity matrix
+    pub fn identity() -> Mat3 {
+        Mat3([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]])
+    }
+
+    /// Builds the matrix rotating vectors by `angle` about the x axis, counterclockwise when
+    /// looking from the positive end of the axis towards the origin.
+    ///
+    /// Rotating the vectors one way is the same as rotating the frame they are expressed in the
+    /// other way, so changing to a frame rotated by `angle` uses the rotation by `-angle`.
+    pub fn rotation_x(angle: Angle) -> Mat3 {
+        let (sin, cos) = angle.sin_cos();
+        Mat3([[1.0, 0.0, 0.0], [0.0, cos, -sin], [0.0, sin, cos]])
+    }
+
+    /// Builds the matrix rotating vectors by `angle` about the y axis. See `rotation_x`.
+    pub fn rotation_y(angle: Angle) -> Mat3 {
+        let (sin, cos) = angle.sin_cos();
+        Mat3([[cos, 0.0, sin], [0.0, 1.0, 0.0], [-sin, 0.0, cos]])
+    }
+
+    /// Builds the matrix rotating vectors by `angle` about the z axis. See `rotation_x`.
+    pub fn rotation_z(angle: Angle) -> Mat3 {
+        let (sin, cos) = angle.sin_cos();
+        Mat3([[cos, -sin, 0.0], [sin, cos, 0.0], [0.0, 0.0, 1.0]])
+    }
+
+    /// Computes the determinant of the matrix
+    pub fn determinant(&self) -> f64 {
+        let m = &self.0;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    /// Computes the inverse of the matrix. For rotation matrices `transpose` gives the same result
+    /// more cheaply.
+    ///
+    /// The result is not finite if the matrix is singular.
+    pub fn inverse(&self) -> Mat3 {
+        let m = &self.0;
+        let det = self.determinant();
+        let cofactor = |r1: usize, r2: usize, c1: usize, c2: usize| {
+            (m[r1][c1] * m[r2][c2] - m[r1][c2] * m[r2][c1]) / det
+        };
+        Mat3([
+            [
+                cofactor(1, 2, 1, 2),
+                -cofactor(0, 2, 1, 2),
+                cofactor(0, 1, 1, 2),
+            ],
+            [
+                -cofactor(1, 2, 0, 2),
+                cofactor(0, 2, 0, 2),
+                -cofactor(0, 1, 0, 2),
+            ],
+            [
+                cofactor(1, 2, 0, 1),
+                -cofactor(0, 2, 0, 1),
+                cofactor(0, 1, 0, 1),
+            ],
+        ])
+    }
+
+    /// Swaps the rows and columns of the matrix. For a rotation matrix this is also its inverse.
+    pub fn transpose(&self) -> Mat3 {
+        let m = &self.0;
+        Mat3([
+            [m[0][0], m[1][0], m[2][0]],
+            [m[0][1], m[1][1], m[2][1]],
+            [m[0][2], m[1][2], m[2][2]],
+        ])
+    }
+}
+
+impl std::ops::Add for Vec3 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Vec3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl std::ops::Sub for Vec3 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Vec3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl std::ops::Mul<f64> for Vec3 {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self {
+        Vec3::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl std::ops::Mul<Vec3> for Mat3 {
+    type Output = Vec3;
+
+    fn mul(self, rhs: Vec3) -> Vec3 {
+        let m = &self.0;
+        Vec3 {
+            x: m[0][0] * rhs.x + m[0][1] * rhs.y + m[0][2] * rhs.z,
+            y: m[1][0] * rhs.x + m[1][1] * rhs.y + m[1][2] * rhs.z,
+            z: m[2][0] * rhs.x + m[2][1] * rhs.y + m[2][2] * rhs.z,
+        }
+    }
+}
+
+impl std::ops::Mul for Mat3 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let mut out = [[0.0; 3]; 3];
+        for (i, row) in out.iter_mut().enumerate() {
+            for (j, item) in row.iter_mut().enumerate() {
+                *item = (0..3).map(|k| self.0[i][k] * rhs.0[k][j]).sum();
+            }
+        }
+        Mat3(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matrix_products() {
+        let m = Mat3([[0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]]);
+        assert_eq!(m * Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        assert_eq!(m * m.transpose(), Mat3::identity());
+        assert_eq!(
+            m * m,
+            Mat3([[-1.0, 0.0, 0.0], [0.0, -1.0, 0.0], [0.0, 0.0, 1.0]])
+        );
+    }
+
+    #[test]
+    fn matrix_inverse() {
+        let m = Mat3([[2.0, 0.0, 1.0], [1.0, 3.0, 0.0], [0.0, 1.0, 4.0]]);
+        assert_eq!(m.determinant(), 25.0);
+        let product = m * m.inverse();
+        for (i, row) in product.0.iter().enumerate() {
+            for (j, item) in row.iter().enumerate() {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((item - expected).abs() < 1e-15);
+            }
+        }
+    }
+
+    #[test]
+    fn rotations() {
+        let close = |a: Vec3, b: Vec3| assert!((a - b).norm() < 1e-15, "{:?} != {:?}", a, b);
+        let x = Vec3::new(1.0, 0.0, 0.0);
+        let y = Vec3::new(0.0, 1.0, 0.0);
+        let z = Vec3::new(0.0, 0.0, 1.0);
+
+        // Each axis is carried towards the next one, in a right handed cycle
+        close(Mat3::rotation_x(Angle::RIGHT_ANGLE) * y, z);
+        close(Mat3::rotation_y(Angle::RIGHT_ANGLE) * z, x);
+        close(Mat3::rotation_z(Angle::RIGHT_ANGLE) * x, y);
+
+        let angle = Angle::from_degrees(23.4);
+        let m = Mat3::rotation_y(angle) * Mat3::rotation_z(-angle);
+        assert!((m.determinant() - 1.0).abs() < 1e-15);
+        close(Mat3::rotation_x(-angle) * (Mat3::rotation_x(angle) * y), y);
+        close(m.transpose() * (m * z), z);
+    }
+
+    #[test]
+    fn vector_products() {
+        let v = Vec3::new(3.0, 4.0, 12.0);
+        assert_eq!(v.norm(), 13.0);
+        assert_eq!(v.dot(&Vec3::new(1.0, -1.0, 0.0)), -1.0);
+        assert_eq!(v - v, Vec3::new(0.0, 0.0, 0.0));
+        assert_eq!(v + v, Vec3::new(6.0, 8.0, 24.0));
+        assert_eq!(v * 2.0, Vec3::new(6.0, 8.0, 24.0));
+        assert_eq!(
+            Vec3::new(1.0, 0.0, 0.0).cross(&Vec3::new(0.0, 1.0, 0.0)),
+            Vec3::new(0.0, 0.0, 1.0)
+        );
+        assert!((v.normalize().norm() - 1.0).abs() < 1e-15);
+    }
+}
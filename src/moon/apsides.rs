@@ -0,0 +1,339 @@
+//! The passages of the Moon through the perigee and the apogee of its orbit, following chapter 50.
+use crate::angle::Angle;
+use crate::observer::EQUATORIAL_RADIUS;
+use crate::time::JD;
+
+/// The mean length of an anomalistic month, from one perigee to the next, in days
+const ANOMALISTIC_MONTH: f64 = 27.554_549_89;
+
+/// The mean perigee from which the apsides are counted, near 1999 December 22
+const EPOCH: f64 = 2_451_534.669_8;
+
+/// The periodic terms of the instant of perigee. Each term holds the multiples of D, M and F, then
+/// the coefficient in days and its rate of change per Julian century.
+#[rustfmt::skip]
+const PERIGEE_TERMS: [([i8; 3], f64, f64); 60] = [
+    ([ 2,  0,  0], -1.676_9,  0.0),
+    ([ 4,  0,  0],  0.458_9,  0.0),
+    ([ 6,  0,  0], -0.185_6,  0.0),
+    ([ 8,  0,  0],  0.088_3,  0.0),
+    ([ 2, -1,  0], -0.077_3,  0.000_19),
+    ([ 0,  1,  0],  0.050_2, -0.000_13),
+    ([10,  0,  0], -0.046_0,  0.0),
+    ([ 4, -1,  0],  0.042_2, -0.000_11),
+    ([ 6, -1,  0], -0.025_6,  0.0),
+    ([12,  0,  0],  0.025_3,  0.0),
+    ([ 1,  0,  0],  0.023_7,  0.0),
+    ([ 8, -1,  0],  0.016_2,  0.0),
+    ([14,  0,  0], -0.014_5,  0.0),
+    ([ 0,  0,  2],  0.012_9,  0.0),
+    ([ 3,  0,  0], -0.011_2,  0.0),
+    ([10, -1,  0], -0.010_4,  0.0),
+    ([16,  0,  0],  0.008_6,  0.0),
+    ([12, -1,  0],  0.006_9,  0.0),
+    ([ 5,  0,  0],  0.006_6,  0.0),
+    ([ 2,  0,  2], -0.005_3,  0.0),
+    ([18,  0,  0], -0.005_2,  0.0),
+    ([14, -1,  0], -0.004_6,  0.0),
+    ([ 7,  0,  0], -0.004_1,  0.0),
+    ([ 2,  1,  0],  0.004_0,  0.0),
+    ([20,  0,  0],  0.003_2,  0.0),
+    ([ 1,  1,  0], -0.003_2,  0.0),
+    ([16, -1,  0],  0.003_1,  0.0),
+    ([ 4,  1,  0], -0.002_9,  0.0),
+    ([ 9,  0,  0],  0.002_7,  0.0),
+    ([ 4,  0,  2],  0.002_7,  0.0),
+    ([ 2, -2,  0], -0.002_7,  0.0),
+    ([ 4, -2,  0],  0.002_4,  0.0),
+    ([ 6, -2,  0], -0.002_1,  0.0),
+    ([22,  0,  0], -0.002_1,  0.0),
+    ([18, -1,  0], -0.002_1,  0.0),
+    ([ 6,  1,  0],  0.001_9,  0.0),
+    ([11,  0,  0], -0.001_8,  0.0),
+    ([ 8,  1,  0], -0.001_4,  0.0),
+    ([ 4,  0, -2], -0.001_4,  0.0),
+    ([ 6,  0,  2], -0.001_4,  0.0),
+    ([ 3,  1,  0],  0.001_4,  0.0),
+    ([ 5,  1,  0], -0.001_4,  0.0),
+    ([13,  0,  0],  0.001_3,  0.0),
+    ([20, -1,  0],  0.001_3,  0.0),
+    ([ 3,  2,  0],  0.001_1,  0.0),
+    ([ 4, -2,  2], -0.001_1,  0.0),
+    ([ 1,  2,  0], -0.001_0,  0.0),
+    ([22, -1,  0], -0.000_9,  0.0),
+    ([ 0,  0,  4], -0.000_8,  0.0),
+    ([ 6,  0, -2],  0.000_8,  0.0),
+    ([ 2,  1, -2],  0.000_8,  0.0),
+    ([ 0,  2,  0],  0.000_7,  0.0),
+    ([ 0, -1,  2],  0.000_7,  0.0),
+    ([ 2,  0,  4],  0.000_7,  0.0),
+    ([ 0, -2,  2], -0.000_6,  0.0),
+    ([ 2,  2, -2], -0.000_6,  0.0),
+    ([24,  0,  0],  0.000_6,  0.0),
+    ([ 4,  0, -4],  0.000_5,  0.0),
+    ([ 2,  2,  0],  0.000_5,  0.0),
+    ([ 1, -1,  0], -0.000_4,  0.0),
+];
+
+/// The periodic terms of the instant of apogee, in the same form as `PERIGEE_TERMS`
+#[rustfmt::skip]
+const APOGEE_TERMS: [([i8; 3], f64, f64); 32] = [
+    ([ 2,  0,  0],  0.439_2,  0.0),
+    ([ 4,  0,  0],  0.068_4,  0.0),
+    ([ 0,  1,  0],  0.045_6, -0.000_11),
+    ([ 2, -1,  0],  0.042_6, -0.000_11),
+    ([ 0,  0,  2],  0.021_2,  0.0),
+    ([ 1,  0,  0], -0.018_9,  0.0),
+    ([ 6,  0,  0],  0.014_4,  0.0),
+    ([ 4, -1,  0],  0.011_3,  0.0),
+    ([ 2,  0,  2],  0.004_7,  0.0),
+    ([ 1,  1,  0],  0.003_6,  0.0),
+    ([ 8,  0,  0],  0.003_5,  0.0),
+    ([ 6, -1,  0],  0.003_4,  0.0),
+    ([ 2,  0, -2], -0.003_4,  0.0),
+    ([ 2, -2,  0],  0.002_2,  0.0),
+    ([ 3,  0,  0], -0.001_7,  0.0),
+    ([ 4,  0,  2],  0.001_3,  0.0),
+    ([ 8, -1,  0],  0.001_1,  0.0),
+    ([ 4, -2,  0],  0.001_0,  0.0),
+    ([10,  0,  0],  0.000_9,  0.0),
+    ([ 3,  1,  0],  0.000_7,  0.0),
+    ([ 0,  2,  0],  0.000_6,  0.0),
+    ([ 2,  1,  0],  0.000_5,  0.0),
+    ([ 2,  2,  0],  0.000_5,  0.0),
+    ([ 6,  0,  2],  0.000_4,  0.0),
+    ([ 6, -2,  0],  0.000_4,  0.0),
+    ([10, -1,  0],  0.000_4,  0.0),
+    ([ 5,  0,  0], -0.000_4,  0.0),
+    ([ 4,  0, -2], -0.000_4,  0.0),
+    ([ 0,  1,  2],  0.000_3,  0.0),
+    ([12,  0,  0],  0.000_3,  0.0),
+    ([ 2, -1,  2],  0.000_3,  0.0),
+    ([ 1, -1,  0], -0.000_3,  0.0),
+];
+
+/// The periodic terms of the equatorial horizontal parallax at perigee. Each term holds the
+/// multiples of D, M and F, then the coefficient in arcseconds and its rate of change per Julian
+/// century.
+#[rustfmt::skip]
+const PERIGEE_PARALLAX_TERMS: [([i8; 3], f64, f64); 46] = [
+    ([ 2,  0,  0], 63.224,  0.0),
+    ([ 4,  0,  0], -6.990,  0.0),
+    ([ 2, -1,  0],  2.834, -0.007_1),
+    ([ 6,  0,  0],  1.927,  0.0),
+    ([ 1,  0,  0], -1.263,  0.0),
+    ([ 8,  0,  0], -0.702,  0.0),
+    ([ 0,  1,  0],  0.696, -0.001_7),
+    ([ 0,  0,  2], -0.690,  0.0),
+    ([ 4, -1,  0], -0.629,  0.001_6),
+    ([ 2,  0, -2], -0.392,  0.0),
+    ([10,  0,  0],  0.297,  0.0),
+    ([ 6, -1,  0],  0.260,  0.0),
+    ([ 3,  0,  0],  0.201,  0.0),
+    ([ 2,  1,  0], -0.161,  0.0),
+    ([ 1,  1,  0],  0.157,  0.0),
+    ([12,  0,  0], -0.138,  0.0),
+    ([ 8, -1,  0], -0.127,  0.0),
+    ([ 2,  0,  2],  0.104,  0.0),
+    ([ 2, -2,  0],  0.104,  0.0),
+    ([ 5,  0,  0], -0.079,  0.0),
+    ([14,  0,  0],  0.068,  0.0),
+    ([10, -1,  0],  0.067,  0.0),
+    ([ 4,  1,  0],  0.054,  0.0),
+    ([12, -1,  0], -0.038,  0.0),
+    ([ 4, -2,  0], -0.038,  0.0),
+    ([ 7,  0,  0],  0.037,  0.0),
+    ([ 4,  0,  2], -0.037,  0.0),
+    ([16,  0,  0], -0.035,  0.0),
+    ([ 3,  1,  0], -0.030,  0.0),
+    ([ 1, -1,  0],  0.029,  0.0),
+    ([ 6,  1,  0], -0.025,  0.0),
+    ([ 0,  2,  0],  0.023,  0.0),
+    ([14, -1,  0],  0.023,  0.0),
+    ([ 2,  2,  0], -0.023,  0.0),
+    ([ 6, -2,  0],  0.022,  0.0),
+    ([ 2, -1, -2], -0.021,  0.0),
+    ([ 9,  0,  0], -0.020,  0.0),
+    ([18,  0,  0],  0.019,  0.0),
+    ([ 6,  0,  2],  0.017,  0.0),
+    ([ 0, -1,  2],  0.014,  0.0),
+    ([16, -1,  0], -0.014,  0.0),
+    ([ 4,  0, -2],  0.013,  0.0),
+    ([ 8,  1,  0],  0.012,  0.0),
+    ([11,  0,  0],  0.011,  0.0),
+    ([ 5,  1,  0],  0.010,  0.0),
+    ([20,  0,  0], -0.010,  0.0),
+];
+
+/// The periodic terms of the equatorial horizontal parallax at apogee, in the same form as
+/// `PERIGEE_PARALLAX_TERMS`
+#[rustfmt::skip]
+const APOGEE_PARALLAX_TERMS: [([i8; 3], f64, f64); 17] = [
+    ([ 2,  0,  0], -9.147,  0.0),
+    ([ 1,  0,  0], -0.841,  0.0),
+    ([ 0,  0,  2],  0.697,  0.0),
+    ([ 0,  1,  0], -0.656,  0.001_6),
+    ([ 4,  0,  0],  0.355,  0.0),
+    ([ 2, -1,  0],  0.159,  0.0),
+    ([ 1,  1,  0],  0.127,  0.0),
+    ([ 4, -1,  0],  0.065,  0.0),
+    ([ 6,  0,  0],  0.052,  0.0),
+    ([ 2,  1,  0],  0.043,  0.0),
+    ([ 2,  0,  2],  0.031,  0.0),
+    ([ 2,  0, -2], -0.023,  0.0),
+    ([ 2, -2,  0],  0.022,  0.0),
+    ([ 2,  2,  0],  0.019,  0.0),
+    ([ 0,  2,  0], -0.016,  0.0),
+    ([ 6, -1,  0],  0.014,  0.0),
+    ([ 8,  0,  0],  0.010,  0.0),
+];
+
+/// The two points of the Moon's orbit where it is closest to and farthest from the Earth
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Apsis {
+    Perigee,
+    Apogee,
+}
+
+/// A passage of the Moon through one of its apsides.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ApsisPassage {
+    pub apsis: Apsis,
+    /// The instant of the passage, as a Julian Ephemeris Day
+    pub instant: JD,
+    /// The equatorial horizontal parallax of the Moon at the passage
+    pub parallax: Angle,
+}
+
+impl ApsisPassage {
+    /// The distance between the centers of the Earth and the Moon at the passage, in kilometers
+    pub fn distance_km(&self) -> f64 {
+        EQUATORIAL_RADIUS / 1000.0 / self.parallax.sin()
+    }
+}
+
+/// Finds the passage of the Moon through the given apsis that is nearest to the Julian Ephemeris
+/// Day `t`.
+///
+/// The instants of perigee are accurate to about half an hour and those of apogee to a few
+/// minutes. The parallaxes are accurate to about 0.1".
+pub fn nearest_apsis(t: &JD, apsis: Apsis) -> ApsisPassage {
+    let offset = match apsis {
+        Apsis::Perigee => 0.0,
+        Apsis::Apogee => 0.5,
+    };
+    let k = ((*t - JD::from(EPOCH)) / ANOMALISTIC_MONTH - offset).round() + offset;
+
+    // The true apsides can be days away from the mean ones, so the neighbours may be nearer
+    [k - 1.0, k, k + 1.0]
+        .iter()
+        .map(|&k| passage(k))
+        .min_by(|a, b| {
+            let (a, b) = ((a.instant - *t).abs(), (b.instant - *t).abs());
+            a.partial_cmp(&b).unwrap()
+        })
+        .unwrap()
+}
+
+/// Computes the passage with the index `k`, counted from the perigee of 1999 December 22. Integer
+/// values give perigees, and adding 0.5 gives the apogee that follows.
+fn passage(k: f64) -> ApsisPassage {
+    let big_t = k / 1_325.55;
+    let (t2, t3, t4) = (
+        big_t * big_t,
+        big_t * big_t * big_t,
+        big_t * big_t * big_t * big_t,
+    );
+
+    let mean = EPOCH + ANOMALISTIC_MONTH * k - 0.000_669_1 * t2 - 0.000_001_098 * t3
+        + 0.000_000_005_2 * t4;
+    let arguments = [
+        171.917_9 + 335.910_604_6 * k - 0.010_038_3 * t2 - 0.000_011_56 * t3 + 0.000_000_055 * t4,
+        347.347_7 + 27.157_772_1 * k - 0.000_813_0 * t2 - 0.000_001_0 * t3,
+        316.610_9 + 364.528_791_1 * k - 0.012_505_3 * t2 - 0.000_014_8 * t3,
+    ];
+    let sum = |terms: &[([i8; 3], f64, f64)], f: fn(&Angle) -> f64| {
+        terms
+            .iter()
+            .map(|(multiples, coefficient, rate)| {
+                let argument = multiples
+                    .iter()
+                    .zip(arguments.iter())
+                    .map(|(&m, a)| f64::from(m) * a)
+                    .sum::<f64>();
+                (coefficient + rate * big_t) * f(&Angle::from_degrees(argument))
+            })
+            .sum::<f64>()
+    };
+
+    let (apsis, correction, parallax) = if k - k.floor() < 0.25 {
+        (
+            Apsis::Perigee,
+            sum(&PERIGEE_TERMS, Angle::sin),
+            3_629.215 + sum(&PERIGEE_PARALLAX_TERMS, Angle::cos),
+        )
+    } else {
+        (
+            Apsis::Apogee,
+            sum(&APOGEE_TERMS, Angle::sin),
+            3_245.251 + sum(&APOGEE_PARALLAX_TERMS, Angle::cos),
+        )
+    };
+
+    ApsisPassage {
+        apsis,
+        instant: JD::from(mean) + correction,
+        parallax: Angle::from_arcseconds(parallax),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moon::distance_km;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn apogee_of_1988() {
+        // Example 50.a, page 357: the apogee of 1988 October 7 at 20h30m TD
+        let apogee = nearest_apsis(&JD::from(2_447_440.0), Apsis::Apogee);
+        assert_eq!(apogee.apsis, Apsis::Apogee);
+        assert_approx_eq!(apogee.instant.as_f64(), 2_447_442.354_3, 1e-4);
+        assert_approx_eq!(apogee.parallax.as_arcseconds(), 3_240.679, 1e-3);
+    }
+
+    #[test]
+    fn perigee_of_2016() {
+        // The close perigee of 2016 November 14, published at 11:23 UT and 356 509 km. ΔT was
+        // 68.6 seconds.
+        let perigee = nearest_apsis(&JD::from(2_457_700.5), Apsis::Perigee);
+        assert_eq!(perigee.apsis, Apsis::Perigee);
+        let published = 2_457_706.5 + (11.0 + 23.0 / 60.0) / 24.0 + 68.6 / 86_400.0;
+        assert_approx_eq!(perigee.instant.as_f64(), published, 2e-3);
+        assert_approx_eq!(perigee.distance_km(), 356_509.0, 3.0);
+    }
+
+    #[test]
+    fn agrees_with_the_position() {
+        let mut t = JD::from(2_460_000.5);
+        for _ in 0..12 {
+            for &apsis in [Apsis::Perigee, Apsis::Apogee].iter() {
+                let passage = nearest_apsis(&t, apsis);
+                assert_eq!(passage.apsis, apsis);
+
+                // The distance is at its extreme within the stated accuracy of the instant
+                let distance = distance_km(&passage.instant);
+                let (before, after) = (
+                    distance_km(&(passage.instant - 0.03)),
+                    distance_km(&(passage.instant + 0.03)),
+                );
+                match apsis {
+                    Apsis::Perigee => assert!(distance < before.max(after)),
+                    Apsis::Apogee => assert!(distance > before.min(after)),
+                }
+                assert_approx_eq!(passage.distance_km(), distance, 20.0);
+            }
+            t = t + ANOMALISTIC_MONTH;
+        }
+    }
+}
@@ -0,0 +1,44 @@
+//! The illuminated part of the Moon's disk, following chapter 48.
+use super::position::apparent_position;
+use crate::angle::Angle;
+use crate::sun;
+use crate::time::JD;
+
+/// Computes the phase angle of the Moon at the Julian Ephemeris Day `t`, the angle between the
+/// Earth and the Sun as seen from the Moon. It is 0° at full Moon and 180° at new Moon.
+pub fn phase_angle(t: &JD) -> Angle {
+    let moon = apparent_position(t).to_equatorial();
+    moon.phase_angle(&sun::apparent_position(t).to_equatorial())
+}
+
+/// Computes the fraction of the Moon's disk that is illuminated at the Julian Ephemeris Day `t`,
+/// using formula 48.1
+pub fn illuminated_fraction(t: &JD) -> f64 {
+    (1.0 + phase_angle(t).cos()) / 2.0
+}
+
+/// Computes the position angle of the midpoint of the illuminated limb of the Moon at the Julian
+/// Ephemeris Day `t`, measured eastwards from the north point of the disk.
+///
+/// The limb always faces the Sun, so this is the position angle of the Sun from the center of the
+/// Moon. It is near 270° for a waxing Moon and near 90° for a waning one.
+pub fn bright_limb_position_angle(t: &JD) -> Angle {
+    let moon = apparent_position(t).to_equatorial().direction;
+    let sun = sun::apparent_position(t).to_equatorial().direction;
+    moon.position_angle(&sun).normalize_0_360()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn illumination_1992() {
+        // Example 48.a, page 347: 1992 April 12 at 0h TD
+        let t = JD::from(2_448_724.5);
+        assert_approx_eq!(phase_angle(&t).as_degrees(), 69.0756, 1e-3);
+        assert_approx_eq!(illuminated_fraction(&t), 0.6786, 1e-4);
+        assert_approx_eq!(bright_limb_position_angle(&t).as_degrees(), 285.0, 0.1);
+    }
+}
@@ -0,0 +1,179 @@
+//! The Moon.
+//!
+//! This covers the position of the Moon, its illumination, its perigees and apogees, the passages
+//! through its nodes, the cycle of the lunar phases and the sunrise and sunset on its features.
+//! The lunations are numbered with Brown's lunation number, which counts the new Moons since the
+//! one of 1923 January 17, lunation 1.
+use crate::time::JD;
+
+mod apsides;
+mod illumination;
+mod nodes;
+mod phases;
+mod position;
+mod selenographic;
+
+pub use self::apsides::{nearest_apsis, Apsis, ApsisPassage};
+pub use self::illumination::{bright_limb_position_angle, illuminated_fraction, phase_angle};
+pub use self::nodes::{nearest_node_passage, Node};
+pub use self::position::{
+    apparent_position, distance_km, geocentric_position, horizontal_parallax, semidiameter,
+    topocentric_semidiameter,
+};
+pub use self::selenographic::{colongitude, subsolar_point, sunrise, sunset, Selenographic};
+
+/// The Brown lunation number of the new Moon of 2000 January 6, from which chapter 49 counts
+const BROWN_OFFSET: i64 = 953;
+
+/// Gets the Brown lunation number of the lunation in progress at `t`, the one which started at
+/// the last new Moon at or before `t`.
+pub fn lunation_number(t: &JD) -> i64 {
+    let estimate = ((*t - JD::from(2_451_550.097_66)) / phases::SYNODIC_MONTH).floor() as i64;
+    let mut lunation = estimate + BROWN_OFFSET;
+
+    // The true new Moons can be more than half a day away from the mean ones
+    while new_moon(lunation) > *t {
+        lunation -= 1;
+    }
+    while new_moon(lunation + 1) <= *t {
+        lunation += 1;
+    }
+    lunation
+}
+
+/// Computes the instant, in dynamical time, of the new Moon starting the lunation with the given
+/// Brown lunation number.
+pub fn new_moon(lunation: i64) -> JD {
+    phases::new_moon((lunation - BROWN_OFFSET) as f64)
+}
+
+/// The four main phases of the Moon, when the excess of its apparent longitude over the Sun's is
+/// 0°, 90°, 180° and 270°.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Phase {
+    New,
+    FirstQuarter,
+    Full,
+    LastQuarter,
+}
+
+/// Finds the first instant, in dynamical time, of the given phase after `t`.
+///
+/// The instants are accurate to a few seconds over several centuries around the present.
+pub fn next_phase(t: &JD, phase: Phase) -> JD {
+    phases_after(t)
+        .find(|(p, _)| *p == phase)
+        .map(|(_, instant)| instant)
+        .unwrap()
+}
+
+/// Iterates over the main phases of the Moon following `t`, in order.
+///
+/// ```
+/// use astro_algos::moon::{self, Phase};
+/// use astro_algos::time::JD;
+///
+/// let mut phases = moon::phases_after(&JD::from(2_451_545.0));
+/// assert_eq!(phases.next().unwrap().0, Phase::New);
+/// assert_eq!(phases.next().unwrap().0, Phase::FirstQuarter);
+/// ```
+pub fn phases_after(t: &JD) -> Phases {
+    // Start from a quarter before the mean phase preceding `t`, since the true phases can be
+    // more than half a day away from the mean ones
+    let mean = (*t - JD::from(2_451_550.097_66)) / phases::SYNODIC_MONTH;
+    let mut k = (mean * 4.0).floor() / 4.0 - 0.25;
+    while phases::instant(k) <= *t {
+        k += 0.25;
+    }
+    Phases { k }
+}
+
+/// An iterator over the main phases of the Moon, created by `phases_after`
+#[derive(Debug, Clone)]
+pub struct Phases {
+    k: f64,
+}
+
+impl Iterator for Phases {
+    type Item = (Phase, JD);
+
+    fn next(&mut self) -> Option<(Phase, JD)> {
+        let k = self.k;
+        self.k += 0.25;
+        Some((phases::phase_of(k), phases::instant(k)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::angle::Angle;
+    use crate::sun;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn lunation_numbers() {
+        // Lunation 1 started on 1923 January 17 at about 2h40m
+        let first = new_moon(1);
+        assert!((first - JD::from(2_423_436.611)).abs() < 0.01);
+        assert_eq!(lunation_number(&first), 1);
+        assert_eq!(lunation_number(&(first - 1e-6)), 0);
+        assert_eq!(lunation_number(&(first + 29.0)), 1);
+
+        // Example 49.a, the new Moon of 1977 February 18
+        let t = JD::from(2_443_192.651_18);
+        assert_eq!(lunation_number(&(t + 0.001)), 670);
+        assert_eq!(lunation_number(&(t - 0.001)), 669);
+
+        // The lunation numbers are consecutive
+        for lunation in 1200..1240 {
+            let start = new_moon(lunation);
+            assert_eq!(lunation_number(&(start + 1.0)), lunation);
+            let length = new_moon(lunation + 1) - start;
+            assert!(length > 29.2 && length < 29.9);
+        }
+    }
+
+    #[test]
+    fn phases_in_order() {
+        // Example 49.b, the last quarter of 2044 January
+        let t = JD::from(2_467_630.0);
+        assert_approx_eq!(
+            next_phase(&t, Phase::LastQuarter).as_f64(),
+            2_467_636.491_86,
+            1e-5
+        );
+        assert_approx_eq!(
+            next_phase(&JD::from(2_443_180.0), Phase::New).as_f64(),
+            2_443_192.651_18,
+            1e-5
+        );
+
+        let expected = [
+            Phase::New,
+            Phase::FirstQuarter,
+            Phase::Full,
+            Phase::LastQuarter,
+        ];
+        let mut previous = t;
+        for (i, (phase, instant)) in phases_after(&t).take(50).enumerate() {
+            assert!(instant > previous);
+            assert!(instant - previous < 8.5);
+            previous = instant;
+            assert_eq!(phase, expected[(i + 3) % 4]);
+
+            // The Moon is ahead of the Sun by a multiple of 90°, to the accuracy of the positions
+            let elongation = (apparent_position(&instant).direction.lon().angle()
+                - sun::apparent_position(&instant).direction.lon().angle())
+            .normalize_0_360();
+            let target = 90.0 * ((i + 3) % 4) as f64;
+            assert_approx_eq!(
+                (elongation - Angle::from_degrees(target))
+                    .normalize_pm180()
+                    .as_degrees(),
+                0.0,
+                0.01
+            );
+        }
+    }
+}
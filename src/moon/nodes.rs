@@ -0,0 +1,135 @@
+//! The passages of the Moon through the nodes of its orbit, following chapter 51.
+use crate::angle::Angle;
+use crate::time::JD;
+
+/// The mean length of a draconic month, from one ascending node to the next, in days
+const DRACONIC_MONTH: f64 = 27.212_220_817;
+
+/// The mean passage through the ascending node from which the nodes are counted, near 2000
+/// January 6
+const EPOCH: f64 = 2_451_565.161_9;
+
+/// The coefficients of the periodic terms, the power of E they are multiplied by, and the
+/// multiples of D, M and M′ in their arguments
+const NODE_TERMS: [(f64, i32, i32, i32, i32); 19] = [
+    (-0.472_1, 0, 0, 0, 1),
+    (-0.164_9, 0, 2, 0, 0),
+    (-0.086_8, 0, 2, 0, -1),
+    (0.008_4, 0, 2, 0, 1),
+    (-0.008_3, 1, 2, -1, 0),
+    (-0.003_9, 1, 2, -1, -1),
+    (0.003_4, 0, 0, 0, 2),
+    (-0.003_1, 0, 2, 0, -2),
+    (0.003_0, 1, 2, 1, 0),
+    (0.002_8, 1, 0, 1, -1),
+    (0.002_6, 1, 0, 1, 0),
+    (0.002_5, 0, 4, 0, 0),
+    (0.002_4, 0, 1, 0, 0),
+    (0.002_2, 1, 0, 1, 1),
+    (0.001_4, 0, 4, 0, -1),
+    (0.000_5, 1, 2, 1, -1),
+    (0.000_4, 1, 2, -1, 1),
+    (-0.000_3, 1, 2, -2, 0),
+    (0.000_3, 1, 4, -1, 0),
+];
+
+/// The two points where the orbit of the Moon crosses the ecliptic
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Node {
+    /// Where the Moon moves north of the ecliptic
+    Ascending,
+    /// Where the Moon moves south of the ecliptic
+    Descending,
+}
+
+/// Finds the instant, in dynamical time, of the passage of the Moon through the given node that
+/// is nearest to the Julian Ephemeris Day `t`.
+///
+/// The instants are accurate to a few minutes.
+pub fn nearest_node_passage(t: &JD, node: Node) -> JD {
+    let offset = match node {
+        Node::Ascending => 0.0,
+        Node::Descending => 0.5,
+    };
+    let k = ((*t - JD::from(EPOCH)) / DRACONIC_MONTH - offset).round() + offset;
+
+    // The true passages can be several hours away from the mean ones
+    [k - 1.0, k, k + 1.0]
+        .iter()
+        .map(|&k| passage(k))
+        .min_by(|a, b| {
+            let (a, b) = ((*a - *t).abs(), (*b - *t).abs());
+            a.partial_cmp(&b).unwrap()
+        })
+        .unwrap()
+}
+
+/// Computes the instant, in dynamical time, of the passage with the index `k`, counted from the
+/// ascending node of 2000 January 6. Integer values give ascending nodes, and adding 0.5 gives the
+/// descending node that follows.
+fn passage(k: f64) -> JD {
+    let t = k / 1342.23;
+    let (t2, t3, t4) = (t * t, t * t * t, t * t * t * t);
+
+    let mean =
+        EPOCH + DRACONIC_MONTH * k + 0.000_276_2 * t2 + 0.000_000_021 * t3 - 0.000_000_000_088 * t4;
+
+    let e = 1.0 - 0.002_516 * t - 0.000_007_4 * t2;
+    let d =
+        183.6380 + 331.737_356_82 * k + 0.001_485_2 * t2 + 0.000_002_09 * t3 - 0.000_000_010 * t4;
+    let m = 17.4006 + 26.820_372_50 * k + 0.000_118_6 * t2 + 0.000_000_06 * t3;
+    let m_prime =
+        38.3776 + 355.527_473_13 * k + 0.012_349_9 * t2 + 0.000_014_627 * t3 - 0.000_000_069 * t4;
+    let omega =
+        123.9767 - 1.440_989_56 * k + 0.002_060_8 * t2 + 0.000_002_14 * t3 - 0.000_000_016 * t4;
+
+    let periodic: f64 = NODE_TERMS
+        .iter()
+        .map(|&(coefficient, e_power, d_n, m_n, m_prime_n)| {
+            let argument = d_n as f64 * d + m_n as f64 * m + m_prime_n as f64 * m_prime;
+            coefficient * e.powi(e_power) * Angle::from_degrees(argument).sin()
+        })
+        .sum();
+
+    // The terms in Ω, V and P don't fit the form of the table
+    let v = 299.75 + 132.85 * t - 0.009_173 * t2;
+    let p = omega + 272.75 - 2.3 * t;
+    let additional = 0.001_7 * Angle::from_degrees(omega).sin()
+        + 0.000_3 * Angle::from_degrees(v).sin()
+        + 0.000_3 * Angle::from_degrees(p).sin();
+
+    JD::from(mean) + periodic + additional
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moon::geocentric_position;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn ascending_node_of_1987() {
+        // Example 51.a, page 365: the passage through the ascending node of 1987 May 23 at 6h25.6m
+        // TD
+        let passage = nearest_node_passage(&JD::from(2_446_940.0), Node::Ascending);
+        assert_approx_eq!(passage.as_f64(), 2_446_938.768_03, 1e-5);
+    }
+
+    #[test]
+    fn agrees_with_the_position() {
+        let mut t = JD::from(2_460_000.5);
+        for _ in 0..12 {
+            for &node in [Node::Ascending, Node::Descending].iter() {
+                let passage = nearest_node_passage(&t, node);
+                assert!((passage - t).abs() < DRACONIC_MONTH / 2.0 + 1.0);
+
+                // The latitude of the Moon is zero, and is crossing the ecliptic in the right sense
+                let latitude = |t: JD| geocentric_position(&t).direction.lat().angle().as_degrees();
+                assert_approx_eq!(latitude(passage), 0.0, 0.01);
+                let rising = latitude(passage + 0.1) > latitude(passage - 0.1);
+                assert_eq!(rising, node == Node::Ascending);
+            }
+            t = t + DRACONIC_MONTH;
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! The instants of the phases of the Moon, following chapter 49.
+use super::Phase;
+use crate::angle::Angle;
+use crate::time::JD;
+
+/// The mean length of a synodic month, in days
+pub(crate) const SYNODIC_MONTH: f64 = 29.530_588_861;
+
+/// The coefficients of the periodic terms for a new Moon, the power of E they are multiplied by,
+/// and the multiples of M, M′, F and Ω in their arguments
+const NEW_MOON_TERMS: [(f64, i32, i32, i32, i32, i32); 25] = [
+    (-0.407_20, 0, 0, 1, 0, 0),
+    (0.172_41, 1, 1, 0, 0, 0),
+    (0.016_08, 0, 0, 2, 0, 0),
+    (0.010_39, 0, 0, 0, 2, 0),
+    (0.007_39, 1, -1, 1, 0, 0),
+    (-0.005_14, 1, 1, 1, 0, 0),
+    (0.002_08, 2, 2, 0, 0, 0),
+    (-0.001_11, 0, 0, 1, -2, 0),
+    (-0.000_57, 0, 0, 1, 2, 0),
+    (0.000_56, 1, 1, 2, 0, 0),
+    (-0.000_42, 0, 0, 3, 0, 0),
+    (0.000_42, 1, 1, 0, 2, 0),
+    (0.000_38, 1, 1, 0, -2, 0),
+    (-0.000_24, 1, -1, 2, 0, 0),
+    (-0.000_17, 0, 0, 0, 0, 1),
+    (-0.000_07, 0, 2, 1, 0, 0),
+    (0.000_04, 0, 0, 2, -2, 0),
+    (0.000_04, 0, 3, 0, 0, 0),
+    (0.000_03, 0, 1, 1, -2, 0),
+    (0.000_03, 0, 0, 2, 2, 0),
+    (-0.000_03, 0, 1, 1, 2, 0),
+    (0.000_03, 0, -1, 1, 2, 0),
+    (-0.000_02, 0, -1, 1, -2, 0),
+    (-0.000_02, 0, 1, 3, 0, 0),
+    (0.000_02, 0, 0, 4, 0, 0),
+];
+
+/// The periodic terms for a full Moon, in the same form as `NEW_MOON_TERMS`
+const FULL_MOON_TERMS: [(f64, i32, i32, i32, i32, i32); 25] = [
+    (-0.406_14, 0, 0, 1, 0, 0),
+    (0.173_02, 1, 1, 0, 0, 0),
+    (0.016_14, 0, 0, 2, 0, 0),
+    (0.010_43, 0, 0, 0, 2, 0),
+    (0.007_34, 1, -1, 1, 0, 0),
+    (-0.005_15, 1, 1, 1, 0, 0),
+    (0.002_09, 2, 2, 0, 0, 0),
+    (-0.001_11, 0, 0, 1, -2, 0),
+    (-0.000_57, 0, 0, 1, 2, 0),
+    (0.000_56, 1, 1, 2, 0, 0),
+    (-0.000_42, 0, 0, 3, 0, 0),
+    (0.000_42, 1, 1, 0, 2, 0),
+    (0.000_38, 1, 1, 0, -2, 0),
+    (-0.000_24, 1, -1, 2, 0, 0),
+    (-0.000_17, 0, 0, 0, 0, 1),
+    (-0.000_07, 0, 2, 1, 0, 0),
+    (0.000_04, 0, 0, 2, -2, 0),
+    (0.000_04, 0, 3, 0, 0, 0),
+    (0.000_03, 0, 1, 1, -2, 0),
+    (0.000_03, 0, 0, 2, 2, 0),
+    (-0.000_03, 0, 1, 1, 2, 0),
+    (0.000_03, 0, -1, 1, 2, 0),
+    (-0.000_02, 0, -1, 1, -2, 0),
+    (-0.000_02, 0, 1, 3, 0, 0),
+    (0.000_02, 0, 0, 4, 0, 0),
+];
+
+/// The periodic terms for the first and last quarters, in the same form as `NEW_MOON_TERMS`
+const QUARTER_TERMS: [(f64, i32, i32, i32, i32, i32); 25] = [
+    (-0.628_01, 0, 0, 1, 0, 0),
+    (0.171_72, 1, 1, 0, 0, 0),
+    (-0.011_83, 1, 1, 1, 0, 0),
+    (0.008_62, 0, 0, 2, 0, 0),
+    (0.008_04, 0, 0, 0, 2, 0),
+    (0.004_54, 1, -1, 1, 0, 0),
+    (0.002_04, 2, 2, 0, 0, 0),
+    (-0.001_80, 0, 0, 1, -2, 0),
+    (-0.000_70, 0, 0, 1, 2, 0),
+    (-0.000_40, 0, 0, 3, 0, 0),
+    (-0.000_34, 1, -1, 2, 0, 0),
+    (0.000_32, 1, 1, 0, 2, 0),
+    (0.000_32, 1, 1, 0, -2, 0),
+    (-0.000_28, 2, 2, 1, 0, 0),
+    (0.000_27, 1, 1, 2, 0, 0),
+    (-0.000_17, 0, 0, 0, 0, 1),
+    (-0.000_05, 0, -1, 1, -2, 0),
+    (0.000_04, 0, 0, 2, 2, 0),
+    (-0.000_04, 0, 1, 1, 2, 0),
+    (0.000_04, 0, -2, 1, 0, 0),
+    (0.000_03, 0, 1, 1, -2, 0),
+    (0.000_03, 0, 3, 0, 0, 0),
+    (0.000_02, 0, 0, 2, -2, 0),
+    (0.000_02, 0, -1, 1, 2, 0),
+    (-0.000_02, 0, 1, 3, 0, 0),
+];
+
+/// The additional corrections common to all the phases, as the coefficient and the argument
+/// A + Bk in degrees. The first argument also has a term in T², added separately.
+const PLANETARY_TERMS: [(f64, f64, f64); 14] = [
+    (0.000_325, 299.77, 0.107_408),
+    (0.000_165, 251.88, 0.016_321),
+    (0.000_164, 251.83, 26.651_886),
+    (0.000_126, 349.42, 36.412_478),
+    (0.000_110, 84.66, 18.206_239),
+    (0.000_062, 141.74, 53.303_771),
+    (0.000_060, 207.14, 2.453_732),
+    (0.000_056, 154.84, 7.306_860),
+    (0.000_047, 34.52, 27.261_239),
+    (0.000_042, 207.19, 0.121_824),
+    (0.000_040, 291.34, 1.844_379),
+    (0.000_037, 161.72, 24.198_154),
+    (0.000_035, 239.56, 25.513_099),
+    (0.000_023, 331.55, 3.592_518),
+];
+
+/// Gets the phase the index `k` stands for, from its fractional part
+pub(crate) fn phase_of(k: f64) -> Phase {
+    match ((k - k.floor()) * 4.0).round() as i32 {
+        1 => Phase::FirstQuarter,
+        2 => Phase::Full,
+        3 => Phase::LastQuarter,
+        _ => Phase::New,
+    }
+}
+
+/// Computes the instant, in dynamical time, of the new Moon with the integer index `k`, counted
+/// from the new Moon of 2000 January 6.
+pub(crate) fn new_moon(k: f64) -> JD {
+    instant(k)
+}
+
+/// Computes the instant, in dynamical time, of the phase with the index `k`, counted from the new
+/// Moon of 2000 January 6. Integer values of `k` give new Moons, and adding 0.25, 0.5 or 0.75 gives
+/// the first quarter, full Moon or last quarter that follow.
+pub(crate) fn instant(k: f64) -> JD {
+    let t = k / 1236.85;
+    let (t2, t3, t4) = (t * t, t * t * t, t * t * t * t);
+
+    let mean = 2_451_550.097_66 + SYNODIC_MONTH * k + 0.000_154_37 * t2 - 0.000_000_150 * t3
+        + 0.000_000_000_73 * t4;
+
+    let e = 1.0 - 0.002_516 * t - 0.000_007_4 * t2;
+    let m = 2.5534 + 29.105_356_70 * k - 0.000_001_4 * t2 - 0.000_000_11 * t3;
+    let m_prime =
+        201.5643 + 385.816_935_28 * k + 0.010_758_2 * t2 + 0.000_012_38 * t3 - 0.000_000_058 * t4;
+    let f =
+        160.7108 + 390.670_502_84 * k - 0.001_611_8 * t2 - 0.000_002_27 * t3 + 0.000_000_011 * t4;
+    let omega = 124.7746 - 1.563_755_88 * k + 0.002_067_2 * t2 + 0.000_002_15 * t3;
+
+    let phase = phase_of(k);
+    let terms = match phase {
+        Phase::New => &NEW_MOON_TERMS,
+        Phase::Full => &FULL_MOON_TERMS,
+        Phase::FirstQuarter | Phase::LastQuarter => &QUARTER_TERMS,
+    };
+    let periodic: f64 = terms
+        .iter()
+        .map(|&(coefficient, e_power, m_n, m_prime_n, f_n, omega_n)| {
+            let argument = m_n as f64 * m
+                + m_prime_n as f64 * m_prime
+                + f_n as f64 * f
+                + omega_n as f64 * omega;
+            coefficient * e.powi(e_power) * Angle::from_degrees(argument).sin()
+        })
+        .sum();
+
+    let planetary: f64 = PLANETARY_TERMS
+        .iter()
+        .enumerate()
+        .map(|(i, &(coefficient, a, b))| {
+            let argument = if i == 0 {
+                a + b * k - 0.009_173 * t2
+            } else {
+                a + b * k
+            };
+            coefficient * Angle::from_degrees(argument).sin()
+        })
+        .sum();
+
+    // The quarters have an additional correction, with opposite signs
+    let (m_rad, m_prime_rad) = (Angle::from_degrees(m), Angle::from_degrees(m_prime));
+    let w = 0.003_06 - 0.000_38 * e * m_rad.cos() + 0.000_26 * m_prime_rad.cos()
+        - 0.000_02 * (m_prime_rad - m_rad).cos()
+        + 0.000_02 * (m_prime_rad + m_rad).cos()
+        + 0.000_02 * Angle::from_degrees(2.0 * f).cos();
+    let quarter = match phase {
+        Phase::FirstQuarter => w,
+        Phase::LastQuarter => -w,
+        Phase::New | Phase::Full => 0.0,
+    };
+
+    JD::from(mean) + periodic + planetary + quarter
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn new_moon_of_1977() {
+        // Example 49.a, the new Moon of 1977 February
+        assert_approx_eq!(new_moon(-283.0).as_f64(), 2_443_192.651_18, 1e-5);
+    }
+
+    #[test]
+    fn last_quarter_of_2044() {
+        // Example 49.b, the last quarter of 2044 January
+        assert_eq!(phase_of(544.75), Phase::LastQuarter);
+        assert_approx_eq!(instant(544.75).as_f64(), 2_467_636.491_86, 1e-5);
+    }
+}
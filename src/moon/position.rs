@@ -0,0 +1,389 @@
+//! The geocentric position of the Moon, following chapter 47.
+//!
+//! This uses the main periodic terms of the ELP-2000/82 theory by Chapront-Touzé and Chapront. The
+//! longitude is accurate to about 10" and the latitude to about 4".
+use crate::angle::Angle;
+use crate::coords::{Ecliptical, EclipticalPosition, MeanOfDate, TrueOfDate};
+use crate::nutation;
+use crate::observer::{Observer, EQUATORIAL_RADIUS};
+use crate::time::{JulianCenturies, JD, UT};
+use crate::METERS_PER_AU;
+
+/// The ratio of the radius of the Moon to the equatorial radius of the Earth
+const RADIUS_RATIO: f64 = 0.272_481;
+
+/// The periodic terms for the longitude and the distance, from table 47.A.
+///
+/// Each term holds the multiples of D, M, M′ and F, then the coefficients of the sine for the
+/// longitude in 0.000001° and of the cosine for the distance in meters.
+#[rustfmt::skip]
+const LONGITUDE_DISTANCE_TERMS: [([i8; 4], f64, f64); 60] = [
+    ([0,  0,  1,  0], 6_288_774.0, -20_905_355.0),
+    ([2,  0, -1,  0], 1_274_027.0,  -3_699_111.0),
+    ([2,  0,  0,  0],   658_314.0,  -2_955_968.0),
+    ([0,  0,  2,  0],   213_618.0,    -569_925.0),
+    ([0,  1,  0,  0],  -185_116.0,      48_888.0),
+    ([0,  0,  0,  2],  -114_332.0,      -3_149.0),
+    ([2,  0, -2,  0],    58_793.0,     246_158.0),
+    ([2, -1, -1,  0],    57_066.0,    -152_138.0),
+    ([2,  0,  1,  0],    53_322.0,    -170_733.0),
+    ([2, -1,  0,  0],    45_758.0,    -204_586.0),
+    ([0,  1, -1,  0],   -40_923.0,    -129_620.0),
+    ([1,  0,  0,  0],   -34_720.0,     108_743.0),
+    ([0,  1,  1,  0],   -30_383.0,     104_755.0),
+    ([2,  0,  0, -2],    15_327.0,      10_321.0),
+    ([0,  0,  1,  2],   -12_528.0,           0.0),
+    ([0,  0,  1, -2],    10_980.0,      79_661.0),
+    ([4,  0, -1,  0],    10_675.0,     -34_782.0),
+    ([0,  0,  3,  0],    10_034.0,     -23_210.0),
+    ([4,  0, -2,  0],     8_548.0,     -21_636.0),
+    ([2,  1, -1,  0],    -7_888.0,      24_208.0),
+    ([2,  1,  0,  0],    -6_766.0,      30_824.0),
+    ([1,  0, -1,  0],    -5_163.0,      -8_379.0),
+    ([1,  1,  0,  0],     4_987.0,     -16_675.0),
+    ([2, -1,  1,  0],     4_036.0,     -12_831.0),
+    ([2,  0,  2,  0],     3_994.0,     -10_445.0),
+    ([4,  0,  0,  0],     3_861.0,     -11_650.0),
+    ([2,  0, -3,  0],     3_665.0,      14_403.0),
+    ([0,  1, -2,  0],    -2_689.0,      -7_003.0),
+    ([2,  0, -1,  2],    -2_602.0,           0.0),
+    ([2, -1, -2,  0],     2_390.0,      10_056.0),
+    ([1,  0,  1,  0],    -2_348.0,       6_322.0),
+    ([2, -2,  0,  0],     2_236.0,      -9_884.0),
+    ([0,  1,  2,  0],    -2_120.0,       5_751.0),
+    ([0,  2,  0,  0],    -2_069.0,           0.0),
+    ([2, -2, -1,  0],     2_048.0,      -4_950.0),
+    ([2,  0,  1, -2],    -1_773.0,       4_130.0),
+    ([2,  0,  0,  2],    -1_595.0,           0.0),
+    ([4, -1, -1,  0],     1_215.0,      -3_958.0),
+    ([0,  0,  2,  2],    -1_110.0,           0.0),
+    ([3,  0, -1,  0],      -892.0,       3_258.0),
+    ([2,  1,  1,  0],      -810.0,       2_616.0),
+    ([4, -1, -2,  0],       759.0,      -1_897.0),
+    ([0,  2, -1,  0],      -713.0,      -2_117.0),
+    ([2,  2, -1,  0],      -700.0,       2_354.0),
+    ([2,  1, -2,  0],       691.0,           0.0),
+    ([2, -1,  0, -2],       596.0,           0.0),
+    ([4,  0,  1,  0],       549.0,      -1_423.0),
+    ([0,  0,  4,  0],       537.0,      -1_117.0),
+    ([4, -1,  0,  0],       520.0,      -1_571.0),
+    ([1,  0, -2,  0],      -487.0,      -1_739.0),
+    ([2,  1,  0, -2],      -399.0,           0.0),
+    ([0,  0,  2, -2],      -381.0,      -4_421.0),
+    ([1,  1,  1,  0],       351.0,           0.0),
+    ([3,  0, -2,  0],      -340.0,           0.0),
+    ([4,  0, -3,  0],       330.0,           0.0),
+    ([2, -1,  2,  0],       327.0,           0.0),
+    ([0,  2,  1,  0],      -323.0,       1_165.0),
+    ([1,  1, -1,  0],       299.0,           0.0),
+    ([2,  0,  3,  0],       294.0,           0.0),
+    ([2,  0, -1, -2],         0.0,       8_752.0),
+];
+
+/// The periodic terms for the latitude, from table 47.B.
+///
+/// Each term holds the multiples of D, M, M′ and F, then the coefficient of the sine in
+/// 0.000001°.
+#[rustfmt::skip]
+const LATITUDE_TERMS: [([i8; 4], f64); 60] = [
+    ([0,  0,  0,  1], 5_128_122.0),
+    ([0,  0,  1,  1],   280_602.0),
+    ([0,  0,  1, -1],   277_693.0),
+    ([2,  0,  0, -1],   173_237.0),
+    ([2,  0, -1,  1],    55_413.0),
+    ([2,  0, -1, -1],    46_271.0),
+    ([2,  0,  0,  1],    32_573.0),
+    ([0,  0,  2,  1],    17_198.0),
+    ([2,  0,  1, -1],     9_266.0),
+    ([0,  0,  2, -1],     8_822.0),
+    ([2, -1,  0, -1],     8_216.0),
+    ([2,  0, -2, -1],     4_324.0),
+    ([2,  0,  1,  1],     4_200.0),
+    ([2,  1,  0, -1],    -3_359.0),
+    ([2, -1, -1,  1],     2_463.0),
+    ([2, -1,  0,  1],     2_211.0),
+    ([2, -1, -1, -1],     2_065.0),
+    ([0,  1, -1, -1],    -1_870.0),
+    ([4,  0, -1, -1],     1_828.0),
+    ([0,  1,  0,  1],    -1_794.0),
+    ([0,  0,  0,  3],    -1_749.0),
+    ([0,  1, -1,  1],    -1_565.0),
+    ([1,  0,  0,  1],    -1_491.0),
+    ([0,  1,  1,  1],    -1_475.0),
+    ([0,  1,  1, -1],    -1_410.0),
+    ([0,  1,  0, -1],    -1_344.0),
+    ([1,  0,  0, -1],    -1_335.0),
+    ([0,  0,  3,  1],     1_107.0),
+    ([4,  0,  0, -1],     1_021.0),
+    ([4,  0, -1,  1],       833.0),
+    ([0,  0,  1, -3],       777.0),
+    ([4,  0, -2,  1],       671.0),
+    ([2,  0,  0, -3],       607.0),
+    ([2,  0,  2, -1],       596.0),
+    ([2, -1,  1, -1],       491.0),
+    ([2,  0, -2,  1],      -451.0),
+    ([0,  0,  3, -1],       439.0),
+    ([2,  0,  2,  1],       422.0),
+    ([2,  0, -3, -1],       421.0),
+    ([2,  1, -1,  1],      -366.0),
+    ([2,  1,  0,  1],      -351.0),
+    ([4,  0,  0,  1],       331.0),
+    ([2, -1,  1,  1],       315.0),
+    ([2, -2,  0, -1],       302.0),
+    ([0,  0,  1,  3],      -283.0),
+    ([2,  1,  1, -1],      -229.0),
+    ([1,  1,  0, -1],       223.0),
+    ([1,  1,  0,  1],       223.0),
+    ([0,  1, -2, -1],      -220.0),
+    ([2,  1, -1, -1],      -220.0),
+    ([1,  0,  1,  1],      -185.0),
+    ([2, -1, -2, -1],       181.0),
+    ([0,  1,  2,  1],      -177.0),
+    ([4,  0, -2, -1],       176.0),
+    ([4, -1, -1, -1],       166.0),
+    ([1,  0,  1, -1],      -164.0),
+    ([4,  0,  1, -1],       132.0),
+    ([1,  0, -1, -1],      -119.0),
+    ([4, -1,  0, -1],       115.0),
+    ([2, -2,  0,  1],       107.0),
+];
+
+/// The coefficients of the argument of latitude of the Moon, its mean distance from the ascending
+/// node, in formula 47.5
+const ARGUMENT_OF_LATITUDE: [f64; 5] = [
+    93.272_095_0,
+    483_202.017_523_3,
+    -0.003_653_9,
+    -1.0 / 3_526_000.0,
+    1.0 / 863_310_000.0,
+];
+
+/// The coefficients of the longitude of the mean ascending node of the Moon, in formula 47.7
+const ASCENDING_NODE: [f64; 5] = [
+    125.044_547_9,
+    -1_934.136_289_1,
+    0.002_075_4,
+    1.0 / 467_441.0,
+    -1.0 / 60_616_000.0,
+];
+
+/// Evaluates a polynomial in Julian centuries with coefficients in degrees
+fn degrees_polynomial(big_t: f64, c: [f64; 5]) -> Angle {
+    Angle::from_degrees(c[0] + big_t * (c[1] + big_t * (c[2] + big_t * (c[3] + big_t * c[4]))))
+}
+
+/// Computes the argument of latitude of the Moon at the Julian Ephemeris Day `t`
+pub(super) fn argument_of_latitude(t: &JD) -> Angle {
+    degrees_polynomial(JulianCenturies::from(*t).0, ARGUMENT_OF_LATITUDE)
+}
+
+/// Computes the longitude of the mean ascending node of the Moon at the Julian Ephemeris Day `t`,
+/// referred to the mean equinox of date
+pub(super) fn mean_ascending_node(t: &JD) -> Angle {
+    degrees_polynomial(JulianCenturies::from(*t).0, ASCENDING_NODE)
+}
+
+/// Computes the geometric position of the Moon seen from the center of the Earth at the Julian
+/// Ephemeris Day `t`, referred to the ecliptic and mean equinox of date.
+pub fn geocentric_position(t: &JD) -> EclipticalPosition<MeanOfDate> {
+    let big_t = JulianCenturies::from(*t).0;
+    let polynomial = |c: [f64; 5]| degrees_polynomial(big_t, c);
+
+    // The mean longitude of the Moon, the mean elongation of the Moon, the mean anomalies of the
+    // Sun and of the Moon, and the argument of latitude of the Moon
+    let l_prime = polynomial([
+        218.316_447_7,
+        481_267.881_234_21,
+        -0.001_578_6,
+        1.0 / 538_841.0,
+        -1.0 / 65_194_000.0,
+    ]);
+    let arguments = [
+        polynomial([
+            297.850_192_1,
+            445_267.111_403_4,
+            -0.001_881_9,
+            1.0 / 545_868.0,
+            -1.0 / 113_065_000.0,
+        ]),
+        polynomial([
+            357.529_109_2,
+            35_999.050_290_9,
+            -0.000_153_6,
+            1.0 / 24_490_000.0,
+            0.0,
+        ]),
+        polynomial([
+            134.963_396_4,
+            477_198.867_505_5,
+            0.008_741_4,
+            1.0 / 69_699.0,
+            -1.0 / 14_712_000.0,
+        ]),
+        polynomial(ARGUMENT_OF_LATITUDE),
+    ];
+    let f = arguments[3];
+
+    // The action of Venus, of Jupiter, and of the flattening of the Earth
+    let a1 = Angle::from_degrees(119.75 + 131.849 * big_t);
+    let a2 = Angle::from_degrees(53.09 + 479_264.290 * big_t);
+    let a3 = Angle::from_degrees(313.45 + 481_266.484 * big_t);
+
+    // The eccentricity of the Earth's orbit decreases, which scales the terms involving M
+    let e = 1.0 - 0.002_516 * big_t - 0.000_007_4 * big_t * big_t;
+    let argument = |multiples: &[i8; 4]| {
+        let angle = multiples
+            .iter()
+            .zip(arguments.iter())
+            .fold(Angle::ZERO, |sum, (&m, a)| sum + *a * f64::from(m));
+        (angle, e.powi(i32::from(multiples[1].abs())))
+    };
+
+    let (mut sigma_l, mut sigma_r) =
+        LONGITUDE_DISTANCE_TERMS
+            .iter()
+            .fold((0.0, 0.0), |(l, r), (multiples, sin, cos)| {
+                let (angle, factor) = argument(multiples);
+                (
+                    l + sin * factor * angle.sin(),
+                    r + cos * factor * angle.cos(),
+                )
+            });
+    let mut sigma_b = LATITUDE_TERMS
+        .iter()
+        .map(|(multiples, sin)| {
+            let (angle, factor) = argument(multiples);
+            sin * factor * angle.sin()
+        })
+        .sum::<f64>();
+
+    sigma_l += 3958.0 * a1.sin() + 1962.0 * (l_prime - f).sin() + 318.0 * a2.sin();
+    sigma_b += -2235.0 * l_prime.sin()
+        + 382.0 * a3.sin()
+        + 175.0 * (a1 - f).sin()
+        + 175.0 * (a1 + f).sin()
+        + 127.0 * (l_prime - arguments[2]).sin()
+        - 115.0 * (l_prime + arguments[2]).sin();
+    sigma_r /= 1000.0;
+
+    EclipticalPosition::new(
+        Ecliptical::from_angles_with_equinox(
+            l_prime + Angle::from_degrees(sigma_l / 1_000_000.0),
+            Angle::from_degrees(sigma_b / 1_000_000.0),
+            MeanOfDate(*t),
+        ),
+        (385_000.56 + sigma_r) * 1000.0 / METERS_PER_AU,
+    )
+}
+
+/// Computes the apparent position of the Moon seen from the center of the Earth at the Julian
+/// Ephemeris Day `t`, referred to the ecliptic and true equinox of date.
+///
+/// Only the nutation in longitude is added. The aberration of the Moon is below 0.7", well within
+/// the accuracy of the series.
+pub fn apparent_position(t: &JD) -> EclipticalPosition<TrueOfDate> {
+    let position = geocentric_position(t);
+    EclipticalPosition::new(
+        Ecliptical::from_angles_with_equinox(
+            (position.direction.lon().angle() + nutation::nutation(t).delta_psi).normalize_0_360(),
+            position.direction.lat().angle(),
+            TrueOfDate(*t),
+        ),
+        position.distance,
+    )
+}
+
+/// Computes the distance between the centers of the Earth and the Moon at the Julian Ephemeris
+/// Day `t`, in kilometers
+pub fn distance_km(t: &JD) -> f64 {
+    geocentric_position(t).distance * METERS_PER_AU / 1000.0
+}
+
+/// Computes the equatorial horizontal parallax of the Moon at the Julian Ephemeris Day `t`, the
+/// angle the equatorial radius of the Earth subtends from the Moon
+pub fn horizontal_parallax(t: &JD) -> Angle {
+    Angle::asin(EQUATORIAL_RADIUS / 1000.0 / distance_km(t))
+}
+
+/// Computes the apparent semidiameter of the Moon seen from the center of the Earth at the Julian
+/// Ephemeris Day `t`
+pub fn semidiameter(t: &JD) -> Angle {
+    Angle::asin(RADIUS_RATIO * horizontal_parallax(t).sin())
+}
+
+/// Computes the apparent semidiameter of the Moon seen by `observer` at the instant `t`.
+///
+/// The observer is closer to the Moon than the center of the Earth when it is above the horizon,
+/// so the Moon looks larger, by up to 1.8% at the zenith. See chapter 55.
+pub fn topocentric_semidiameter(observer: &Observer, t: &JD<UT>) -> Angle {
+    let position = apparent_position(&t.to_scale())
+        .to_equatorial()
+        .to_topocentric(observer, t);
+    Angle::asin(RADIUS_RATIO * EQUATORIAL_RADIUS / (position.distance * METERS_PER_AU))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn position_1992() {
+        // Example 47.a, page 342: 1992 April 12 at 0h TD
+        let t = JD::from(2_448_724.5);
+        let moon = geocentric_position(&t);
+        assert_approx_eq!(moon.direction.lon().angle().as_degrees(), 133.162_655, 1e-6);
+        assert_approx_eq!(moon.direction.lat().angle().as_degrees(), -3.229_126, 1e-6);
+        assert_approx_eq!(moon.distance * METERS_PER_AU / 1000.0, 368_409.7, 0.1);
+
+        let apparent = apparent_position(&t);
+        assert_approx_eq!(
+            apparent.direction.lon().angle().as_degrees(),
+            133.167_265,
+            1e-6
+        );
+
+        // 8h58m45.2s and +13°46'06"
+        let equatorial = apparent.to_equatorial().direction;
+        assert_approx_eq!(
+            equatorial.ra().angle().as_hours(),
+            8.0 + 58.0 / 60.0 + 45.2 / 3600.0,
+            0.1 / 3600.0
+        );
+        assert_approx_eq!(
+            equatorial.dec().angle().as_degrees(),
+            13.0 + 46.0 / 60.0 + 6.0 / 3600.0,
+            1.0 / 3600.0
+        );
+    }
+
+    #[test]
+    fn parallax_and_semidiameter() {
+        // Example 47.a: π = 0.991990°
+        let t = JD::from(2_448_724.5);
+        assert_approx_eq!(distance_km(&t), 368_409.7, 0.1);
+        assert_approx_eq!(horizontal_parallax(&t).as_degrees(), 0.991_990, 1e-6);
+
+        // Chapter 55 gives s = 358473400" / Δ
+        assert_approx_eq!(
+            semidiameter(&t).as_arcseconds(),
+            358_473_400.0 / 368_409.7,
+            0.01
+        );
+
+        // Seen from the surface the semidiameter grows with the altitude, by s sin π sin h
+        let observer = Observer::new(Angle::from_degrees(40.0), Angle::from_degrees(-100.0), 0.0);
+        let ut = JD::<UT>::from(2_448_724.5);
+        let altitude = apparent_position(&t)
+            .to_equatorial()
+            .direction
+            .to_horizontal(&observer, &ut)
+            .altitude;
+        let s = semidiameter(&t);
+        assert_approx_eq!(
+            topocentric_semidiameter(&observer, &ut).as_arcseconds(),
+            s.as_arcseconds() * (1.0 + altitude.sin() * horizontal_parallax(&t).sin()),
+            0.2
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! The illumination of the surface of the Moon by the Sun, following chapter 53.
+//!
+//! Features on the Moon are located by their selenographic latitude and longitude, with the
+//! longitude counted positive towards Mare Crisium, the east in the IAU sense. The Sun rises on a
+//! feature when the morning terminator crosses it, which happens when the selenographic
+//! colongitude of the Sun is close to 360° less the longitude of the feature.
+use super::position::{apparent_position, argument_of_latitude, mean_ascending_node};
+use crate::angle::Angle;
+use crate::nutation;
+use crate::sun;
+use crate::time::JD;
+
+/// The inclination of the mean lunar equator to the ecliptic
+const INCLINATION: Angle = Angle::from_degrees(1.542_42);
+
+/// The mean rate at which the colongitude of the Sun increases, in degrees per day
+const COLONGITUDE_RATE: f64 = 360.0 / 29.530_588_861;
+
+/// A position on the surface of the Moon, in selenographic coordinates.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Selenographic {
+    pub latitude: Angle,
+    pub longitude: Angle,
+}
+
+/// Computes the selenographic position of the point of the Moon that has the Sun at its zenith,
+/// at the Julian Ephemeris Day `t`.
+///
+/// This is the optical libration of the Moon as seen from the Sun. The physical libration, which
+/// never exceeds a few hundredths of a degree, is left out.
+pub fn subsolar_point(t: &JD) -> Selenographic {
+    let moon = apparent_position(t);
+    let sun = sun::apparent_position(t);
+    let (lambda, beta) = (moon.direction.lon().angle(), moon.direction.lat().angle());
+    let lambda_0 = sun.direction.lon().angle();
+
+    // The heliocentric position of the Moon
+    let ratio = moon.distance / sun.distance;
+    let lambda_h = lambda_0
+        + Angle::from_degrees(180.0)
+        + Angle::from_radians(ratio * beta.cos() * (lambda_0 - lambda).sin());
+    let beta_h = beta * ratio;
+
+    let w = lambda_h - nutation::nutation(t).delta_psi - mean_ascending_node(t);
+    let a = Angle::atan2(
+        w.sin() * beta_h.cos() * INCLINATION.cos() - beta_h.sin() * INCLINATION.sin(),
+        w.cos() * beta_h.cos(),
+    );
+
+    Selenographic {
+        latitude: Angle::asin(
+            -w.sin() * beta_h.cos() * INCLINATION.sin() - beta_h.sin() * INCLINATION.cos(),
+        ),
+        longitude: (a - argument_of_latitude(t)).normalize_pm180(),
+    }
+}
+
+/// Computes the selenographic colongitude of the Sun at the Julian Ephemeris Day `t`, in the
+/// range [0°, 360°).
+///
+/// The colongitude is about 0° at first quarter, 90° at full Moon, 180° at last quarter and 270°
+/// at new Moon.
+pub fn colongitude(t: &JD) -> Angle {
+    (Angle::from_degrees(90.0) - subsolar_point(t).longitude).normalize_0_360()
+}
+
+/// Finds the first sunrise on the lunar `feature` after the Julian Ephemeris Day `t`, when the
+/// center of the Sun is on the feature's horizon with its altitude increasing.
+///
+/// Returns `None` for features so close to a pole that the Sun doesn't rise or set there.
+pub fn sunrise(feature: &Selenographic, t: &JD) -> Option<JD> {
+    next_crossing(feature, t, |rising| rising)
+}
+
+/// Finds the first sunset on the lunar `feature` after the Julian Ephemeris Day `t`, when the
+/// center of the Sun is on the feature's horizon with its altitude decreasing.
+///
+/// Returns `None` for features so close to a pole that the Sun doesn't rise or set there.
+pub fn sunset(feature: &Selenographic, t: &JD) -> Option<JD> {
+    next_crossing(feature, t, |rising| Angle::from_degrees(180.0) - rising)
+}
+
+/// Finds the first instant after `t` when the Sun crosses the horizon of `feature`. The altitude
+/// of the Sun is zero when the colongitude plus the longitude of the feature is the angle given by
+/// `crossing` from the one of sunrise.
+fn next_crossing(feature: &Selenographic, t: &JD, crossing: fn(Angle) -> Angle) -> Option<JD> {
+    // How far the colongitude still has to go to bring the Sun to the horizon of the feature
+    let remaining = |t: &JD| {
+        let sun = subsolar_point(t);
+        let sin_rising = -sun.latitude.tan() * feature.latitude.tan();
+        if sin_rising.is_nan() || sin_rising.abs() > 1.0 {
+            return None;
+        }
+        let target = crossing(Angle::asin(sin_rising)) - feature.longitude;
+        Some(target - colongitude(t))
+    };
+
+    let mut instant = *t + remaining(t)?.normalize_0_360().as_degrees() / COLONGITUDE_RATE;
+    for _ in 0..10 {
+        let step = remaining(&instant)?.normalize_pm180().as_degrees() / COLONGITUDE_RATE;
+        instant = instant + step;
+        if step.abs() < 1e-6 {
+            break;
+        }
+    }
+    Some(instant)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    /// The altitude of the Sun above the horizon of `feature` at `t`
+    fn altitude(feature: &Selenographic, t: &JD) -> Angle {
+        let sun = subsolar_point(t);
+        Angle::asin(
+            sun.latitude.sin() * feature.latitude.sin()
+                + sun.latitude.cos()
+                    * feature.latitude.cos()
+                    * (colongitude(t) + feature.longitude).sin(),
+        )
+    }
+
+    #[test]
+    fn sun_on_the_moon_1992() {
+        // Example 53.a, page 376: 1992 April 12 at 0h TD. The book includes the physical
+        // libration, which moves the Sun by -0.025° in longitude at that instant.
+        let t = JD::from(2_448_724.5);
+        let sun = subsolar_point(&t);
+        assert_approx_eq!(sun.longitude.as_degrees(), 67.89, 0.04);
+        assert_approx_eq!(colongitude(&t).as_degrees(), 22.11, 0.04);
+
+        // The Sun is never further from the lunar equator than its inclination to the ecliptic,
+        // and the latitude of the Moon seen from the Sun adds less than 0.02°
+        assert!(sun.latitude.abs() < INCLINATION + Angle::from_degrees(0.02));
+    }
+
+    #[test]
+    fn sunrise_and_sunset() {
+        let copernicus = Selenographic {
+            latitude: Angle::from_degrees(9.62),
+            longitude: Angle::from_degrees(-20.08),
+        };
+        let t = JD::from(2_460_000.5);
+        let rise = sunrise(&copernicus, &t).unwrap();
+        let set = sunset(&copernicus, &rise).unwrap();
+        assert!(rise > t && rise - t < 30.0);
+
+        // The Sun is on the horizon, and shines for about half a synodic month
+        assert_approx_eq!(altitude(&copernicus, &rise).as_degrees(), 0.0, 1e-5);
+        assert_approx_eq!(altitude(&copernicus, &set).as_degrees(), 0.0, 1e-5);
+        assert!(altitude(&copernicus, &(rise + 0.1)) > Angle::ZERO);
+        assert!(altitude(&copernicus, &(set + 0.1)) < Angle::ZERO);
+        assert_approx_eq!(set - rise, 14.77, 0.5);
+
+        // The colongitude at sunrise is 360° less the longitude of the crater, give or take the
+        // latitude of the Sun
+        assert_approx_eq!(colongitude(&rise).as_degrees(), 20.08, 0.5);
+
+        // The next sunrise is one synodic month later, give or take the eccentricity of the orbit
+        let next = sunrise(&copernicus, &set).unwrap();
+        assert_approx_eq!(next - rise, 29.53, 0.5);
+    }
+
+    #[test]
+    fn near_the_poles() {
+        // Within a degree and a half of a pole the Sun stays above or below the horizon for as
+        // long as its latitude has the right sign
+        let t = JD::from(2_448_724.5);
+        let pole = Selenographic {
+            latitude: Angle::from_degrees(89.5),
+            longitude: Angle::ZERO,
+        };
+        assert!(subsolar_point(&t).latitude.as_degrees() > 1.0);
+        assert_eq!(sunrise(&pole, &t), None);
+        assert_eq!(sunset(&pole, &t), None);
+    }
+}
@@ -0,0 +1,75 @@
+//! Conversions between the rectangular types and `nalgebra`, enabled by the `nalgebra` feature.
+//!
+//! Vectors convert to and from `Vector3<f64>`, and matrices to and from `Matrix3<f64>`. Positions
+//! keep their units, astronomical units for `HeliocentricRectangular`.
+use crate::coords::HeliocentricRectangular;
+use crate::linalg::{Mat3, Vec3};
+
+use ::nalgebra::{Matrix3, Vector3};
+
+impl From<Vec3> for Vector3<f64> {
+    fn from(item: Vec3) -> Self {
+        Vector3::new(item.x, item.y, item.z)
+    }
+}
+
+impl From<Vector3<f64>> for Vec3 {
+    fn from(item: Vector3<f64>) -> Self {
+        Vec3::new(item.x, item.y, item.z)
+    }
+}
+
+impl From<HeliocentricRectangular> for Vector3<f64> {
+    fn from(item: HeliocentricRectangular) -> Self {
+        Vector3::new(item.x, item.y, item.z)
+    }
+}
+
+impl From<Vector3<f64>> for HeliocentricRectangular {
+    fn from(item: Vector3<f64>) -> Self {
+        HeliocentricRectangular::new(item.x, item.y, item.z)
+    }
+}
+
+impl From<Mat3> for Matrix3<f64> {
+    fn from(item: Mat3) -> Self {
+        let m = &item.0;
+        Matrix3::new(
+            m[0][0], m[0][1], m[0][2], m[1][0], m[1][1], m[1][2], m[2][0], m[2][1], m[2][2],
+        )
+    }
+}
+
+impl From<Matrix3<f64>> for Mat3 {
+    fn from(item: Matrix3<f64>) -> Self {
+        Mat3([
+            [item[(0, 0)], item[(0, 1)], item[(0, 2)]],
+            [item[(1, 0)], item[(1, 1)], item[(1, 2)]],
+            [item[(2, 0)], item[(2, 1)], item[(2, 2)]],
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::angle::Angle;
+
+    #[test]
+    fn conversions() {
+        let m = Mat3::rotation_z(Angle::from_degrees(30.0))
+            * Mat3::rotation_x(Angle::from_degrees(-10.0));
+        let v = Vec3::new(0.25, -1.5, 3.0);
+
+        // Products agree on both sides, so the rows and columns are not swapped
+        let product: Vec3 = (Matrix3::from(m) * Vector3::from(v)).into();
+        assert!((product - m * v).norm() < 1e-15);
+        assert_eq!(Mat3::from(Matrix3::from(m)), m);
+        assert_eq!(Vec3::from(Vector3::from(v)), v);
+
+        let position = HeliocentricRectangular::new(0.5, 0.25, -0.125);
+        let vector = Vector3::from(position);
+        assert_eq!(vector, Vector3::new(0.5, 0.25, -0.125));
+        assert_eq!(HeliocentricRectangular::from(vector), position);
+    }
+}
@@ -0,0 +1,220 @@
+//! Nutation of the Earth's axis.
+//!
+//! On top of the slow precession, the Earth's axis wobbles with a period of 18.6 years and
+//! smaller periodic terms, mainly because of the Moon. The nutation is split into a component
+//! along the ecliptic, the nutation in longitude Δψ, and a component perpendicular to it, the
+//! nutation in obliquity Δε. This implements the IAU 1980 theory given in chapter 22.
+use crate::angle::Angle;
+use crate::linalg::Mat3;
+use crate::precession;
+use crate::time::{JulianCenturies, JD};
+
+/// The nutation in longitude and in obliquity at an instant.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Nutation {
+    /// The nutation in longitude Δψ, which moves the equinox along the ecliptic
+    pub delta_psi: Angle,
+    /// The nutation in obliquity Δε, which changes the angle between the equator and the ecliptic
+    pub delta_epsilon: Angle,
+}
+
+/// The periodic terms of the nutation, from table 22.A.
+///
+/// Each term holds the multiples of the arguments D, M, M', F and Ω, then the coefficients of the
+/// sine for Δψ and of the cosine for Δε, each with its rate of change per Julian century. The
+/// coefficients are in units of 0.0001".
+#[rustfmt::skip]
+const TERMS: [([i8; 5], f64, f64, f64, f64); 63] = [
+    ([ 0,  0,  0,  0,  1], -171_996.0, -174.2, 92_025.0,  8.9),
+    ([-2,  0,  0,  2,  2],  -13_187.0,   -1.6,  5_736.0, -3.1),
+    ([ 0,  0,  0,  2,  2],   -2_274.0,   -0.2,    977.0, -0.5),
+    ([ 0,  0,  0,  0,  2],    2_062.0,    0.2,   -895.0,  0.5),
+    ([ 0,  1,  0,  0,  0],    1_426.0,   -3.4,     54.0, -0.1),
+    ([ 0,  0,  1,  0,  0],      712.0,    0.1,     -7.0,  0.0),
+    ([-2,  1,  0,  2,  2],     -517.0,    1.2,    224.0, -0.6),
+    ([ 0,  0,  0,  2,  1],     -386.0,   -0.4,    200.0,  0.0),
+    ([ 0,  0,  1,  2,  2],     -301.0,    0.0,    129.0, -0.1),
+    ([-2, -1,  0,  2,  2],      217.0,   -0.5,    -95.0,  0.3),
+    ([-2,  0,  1,  0,  0],     -158.0,    0.0,      0.0,  0.0),
+    ([-2,  0,  0,  2,  1],      129.0,    0.1,    -70.0,  0.0),
+    ([ 0,  0, -1,  2,  2],      123.0,    0.0,    -53.0,  0.0),
+    ([ 2,  0,  0,  0,  0],       63.0,    0.0,      0.0,  0.0),
+    ([ 0,  0,  1,  0,  1],       63.0,    0.1,    -33.0,  0.0),
+    ([ 2,  0, -1,  2,  2],      -59.0,    0.0,     26.0,  0.0),
+    ([ 0,  0, -1,  0,  1],      -58.0,   -0.1,     32.0,  0.0),
+    ([ 0,  0,  1,  2,  1],      -51.0,    0.0,     27.0,  0.0),
+    ([-2,  0,  2,  0,  0],       48.0,    0.0,      0.0,  0.0),
+    ([ 0,  0, -2,  2,  1],       46.0,    0.0,    -24.0,  0.0),
+    ([ 2,  0,  0,  2,  2],      -38.0,    0.0,     16.0,  0.0),
+    ([ 0,  0,  2,  2,  2],      -31.0,    0.0,     13.0,  0.0),
+    ([ 0,  0,  2,  0,  0],       29.0,    0.0,      0.0,  0.0),
+    ([-2,  0,  1,  2,  2],       29.0,    0.0,    -12.0,  0.0),
+    ([ 0,  0,  0,  2,  0],       26.0,    0.0,      0.0,  0.0),
+    ([-2,  0,  0,  2,  0],      -22.0,    0.0,      0.0,  0.0),
+    ([ 0,  0, -1,  2,  1],       21.0,    0.0,    -10.0,  0.0),
+    ([ 0,  2,  0,  0,  0],       17.0,   -0.1,      0.0,  0.0),
+    ([ 2,  0, -1,  0,  1],       16.0,    0.0,     -8.0,  0.0),
+    ([-2,  2,  0,  2,  2],      -16.0,    0.1,      7.0,  0.0),
+    ([ 0,  1,  0,  0,  1],      -15.0,    0.0,      9.0,  0.0),
+    ([-2,  0,  1,  0,  1],      -13.0,    0.0,      7.0,  0.0),
+    ([ 0, -1,  0,  0,  1],      -12.0,    0.0,      6.0,  0.0),
+    ([ 0,  0,  2, -2,  0],       11.0,    0.0,      0.0,  0.0),
+    ([ 2,  0, -1,  2,  1],      -10.0,    0.0,      5.0,  0.0),
+    ([ 2,  0,  1,  2,  2],       -8.0,    0.0,      3.0,  0.0),
+    ([ 0,  1,  0,  2,  2],        7.0,    0.0,     -3.0,  0.0),
+    ([-2,  1,  1,  0,  0],       -7.0,    0.0,      0.0,  0.0),
+    ([ 0, -1,  0,  2,  2],       -7.0,    0.0,      3.0,  0.0),
+    ([ 2,  0,  0,  2,  1],       -7.0,    0.0,      3.0,  0.0),
+    ([ 2,  0,  1,  0,  0],        6.0,    0.0,      0.0,  0.0),
+    ([-2,  0,  2,  2,  2],        6.0,    0.0,     -3.0,  0.0),
+    ([-2,  0,  1,  2,  1],        6.0,    0.0,     -3.0,  0.0),
+    ([ 2,  0, -2,  0,  1],       -6.0,    0.0,      3.0,  0.0),
+    ([ 2,  0,  0,  0,  1],       -6.0,    0.0,      3.0,  0.0),
+    ([ 0, -1,  1,  0,  0],        5.0,    0.0,      0.0,  0.0),
+    ([-2, -1,  0,  2,  1],       -5.0,    0.0,      3.0,  0.0),
+    ([-2,  0,  0,  0,  1],       -5.0,    0.0,      3.0,  0.0),
+    ([ 0,  0,  2,  2,  1],       -5.0,    0.0,      3.0,  0.0),
+    ([-2,  0,  2,  0,  1],        4.0,    0.0,      0.0,  0.0),
+    ([-2,  1,  0,  2,  1],        4.0,    0.0,      0.0,  0.0),
+    ([ 0,  0,  1, -2,  0],        4.0,    0.0,      0.0,  0.0),
+    ([-1,  0,  1,  0,  0],       -4.0,    0.0,      0.0,  0.0),
+    ([-2,  1,  0,  0,  0],       -4.0,    0.0,      0.0,  0.0),
+    ([ 1,  0,  0,  0,  0],       -4.0,    0.0,      0.0,  0.0),
+    ([ 0,  0,  1,  2,  0],        3.0,    0.0,      0.0,  0.0),
+    ([ 0,  0, -2,  2,  2],       -3.0,    0.0,      0.0,  0.0),
+    ([-1, -1,  1,  0,  0],       -3.0,    0.0,      0.0,  0.0),
+    ([ 0,  1,  1,  0,  0],       -3.0,    0.0,      0.0,  0.0),
+    ([ 0, -1,  1,  2,  2],       -3.0,    0.0,      0.0,  0.0),
+    ([ 2, -1, -1,  2,  2],       -3.0,    0.0,      0.0,  0.0),
+    ([ 0,  0,  3,  2,  2],       -3.0,    0.0,      0.0,  0.0),
+    ([ 2, -1,  0,  2,  2],       -3.0,    0.0,      0.0,  0.0),
+];
+
+/// The coefficients of the polynomial in Julian centuries giving the longitude of the ascending
+/// node of the Moon's mean orbit, in degrees
+const ASCENDING_NODE: [f64; 4] = [125.044_52, -1_934.136_261, 0.002_070_8, 1.0 / 450_000.0];
+
+/// Computes the nutation in longitude and in obliquity at the Julian Ephemeris Day `t`.
+///
+/// All 63 terms of the IAU 1980 theory are used, which is accurate to about 0.0003" in Δψ and
+/// 0.0001" in Δε.
+pub fn nutation(t: &JD) -> Nutation {
+    let big_t = JulianCenturies::from(*t).0;
+    let polynomial = |c: [f64; 4]| c[0] + big_t * (c[1] + big_t * (c[2] + big_t * c[3]));
+
+    // The mean elongation of the Moon from the Sun, the mean anomalies of the Sun and of the Moon,
+    // the argument of latitude of the Moon and the longitude of the ascending node of the Moon
+    let arguments = [
+        polynomial([297.850_36, 445_267.111_480, -0.001_914_2, 1.0 / 189_474.0]),
+        polynomial([357.527_72, 35_999.050_340, -0.000_160_3, -1.0 / 300_000.0]),
+        polynomial([134.962_98, 477_198.867_398, 0.008_697_2, 1.0 / 56_250.0]),
+        polynomial([93.271_91, 483_202.017_538, -0.003_682_5, 1.0 / 327_270.0]),
+        polynomial(ASCENDING_NODE),
+    ];
+
+    let (delta_psi, delta_epsilon) = TERMS.iter().fold(
+        (0.0, 0.0),
+        |(psi, epsilon), (multiples, sin, sin_rate, cos, cos_rate)| {
+            let argument = multiples
+                .iter()
+                .zip(arguments.iter())
+                .map(|(&m, a)| f64::from(m) * a)
+                .sum::<f64>();
+            let (sin_arg, cos_arg) = Angle::from_degrees(argument).sin_cos();
+            (
+                psi + (sin + sin_rate * big_t) * sin_arg,
+                epsilon + (cos + cos_rate * big_t) * cos_arg,
+            )
+        },
+    );
+
+    Nutation {
+        delta_psi: Angle::from_arcseconds(delta_psi / 10_000.0),
+        delta_epsilon: Angle::from_arcseconds(delta_epsilon / 10_000.0),
+    }
+}
+
+/// Computes the true obliquity of the ecliptic ε = ε0 + Δε at the Julian Ephemeris Day `t`, the
+/// angle between the ecliptic and the true equator of date.
+pub fn true_obliquity(t: &JD) -> Angle {
+    precession::mean_obliquity(t) + nutation(t).delta_epsilon
+}
+
+/// Computes the equation of the equinoxes Δψ cos ε at the Julian Ephemeris Day `t`, the
+/// difference between the apparent and the mean sidereal time.
+///
+/// The result never exceeds about 1.2 seconds of time. See `equation_of_the_equinoxes_iau1994` for
+/// the version including the complementary terms.
+pub fn equation_of_the_equinoxes(t: &JD) -> Angle {
+    let n = nutation(t);
+    let obliquity = precession::mean_obliquity(t) + n.delta_epsilon;
+    n.delta_psi * obliquity.cos()
+}
+
+/// Computes the equation of the equinoxes at the Julian Ephemeris Day `t`, including the
+/// complementary terms adopted by the IAU in 1994.
+///
+/// The terms depend on the longitude of the ascending node of the Moon, and add up to at most
+/// 0.0027".
+pub fn equation_of_the_equinoxes_iau1994(t: &JD) -> Angle {
+    let big_t = JulianCenturies::from(*t).0;
+    let node = Angle::from_degrees(
+        ASCENDING_NODE[0]
+            + big_t * (ASCENDING_NODE[1] + big_t * (ASCENDING_NODE[2] + big_t * ASCENDING_NODE[3])),
+    );
+    equation_of_the_equinoxes(t)
+        + Angle::from_arcseconds(0.002_64 * node.sin() + 0.000_063 * (node * 2.0).sin())
+}
+
+/// Computes the rotation matrix carrying rectangular equatorial coordinates referred to the mean
+/// equator and equinox of the Julian Ephemeris Day `t` into the true equator and equinox of `t`.
+///
+/// The coordinates are rotated onto the mean ecliptic, moved along it by Δψ, and rotated back
+/// onto the true equator with the true obliquity.
+pub fn matrix(t: &JD) -> Mat3 {
+    let mean_obliquity = precession::mean_obliquity(t);
+    let n = nutation(t);
+    Mat3::rotation_x(mean_obliquity + n.delta_epsilon)
+        * Mat3::rotation_z(n.delta_psi)
+        * Mat3::rotation_x(-mean_obliquity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn nutation_in_longitude_and_obliquity() {
+        // Example 22.a, page 148: 1987 April 10 at 0h TD
+        let t = JD::from(2_446_895.5);
+        let n = nutation(&t);
+        assert_approx_eq!(n.delta_psi.as_arcseconds(), -3.788, 1e-3);
+        assert_approx_eq!(n.delta_epsilon.as_arcseconds(), 9.443, 1e-3);
+
+        // ε = 23°26'36.850"
+        assert_approx_eq!(
+            true_obliquity(&t).as_degrees(),
+            23.0 + 26.0 / 60.0 + 36.850 / 3600.0,
+            1e-3 / 3600.0
+        );
+    }
+
+    #[test]
+    fn equinoxes() {
+        // Example 12.a, page 88: -0.2317 seconds of time on 1987 April 10
+        let t = JD::from(2_446_895.5);
+        let equation = equation_of_the_equinoxes(&t);
+        assert_approx_eq!(equation.as_hours() * 3600.0, -0.2317, 1e-4);
+
+        // The test value of the SOFA library's iauEqeq94 for MJD 41234 TT is 5.357758254609257e-5
+        // radians. The complementary terms make up -0.0021" of it, which the truncated series of
+        // the nutation reproduces to within 0.001".
+        let t = JD::from(2_400_000.5 + 41_234.0);
+        let expected = Angle::from_radians(5.357_758_254_609_257e-5);
+        let complete = equation_of_the_equinoxes_iau1994(&t);
+        assert_approx_eq!((complete - expected).as_arcseconds(), 0.0, 1e-3);
+        let without = equation_of_the_equinoxes(&t);
+        assert!((without - expected).as_arcseconds().abs() > 1e-3);
+    }
+}
@@ -0,0 +1,344 @@
+//! Where a body appears in the sky of an observer, in a single call.
+//!
+//! Getting from a theory of motion to the place a body is seen at takes a chain of reductions, and
+//! applying them in the wrong order or on the wrong time scale gives errors of several seconds of
+//! arc. An `Observation` takes care of the whole chain:
+//!
+//! 1. The instant is converted to Terrestrial Time, applying ΔT when given in UT or UTC.
+//! 2. The geocentric position is computed from VSOP87, or the series of chapter 47 for the Moon,
+//!    corrected for light-time and aberration with the planetary aberration method.
+//! 3. The position is precessed and nutated to the true equator and equinox of date.
+//! 4. The parallax moves it to the observer's place on the surface of the Earth.
+//! 5. It is converted to horizontal coordinates with the apparent sidereal time, and the
+//!    refraction of the atmosphere is applied.
+//!
+//! `apparent_place` runs the whole chain for an instant in UTC with the standard atmosphere, and
+//! returns both the topocentric right ascension and declination and the refracted azimuth and
+//! altitude.
+//!
+//! ```
+//! use astro_algos::angle::Angle;
+//! use astro_algos::observation::Observation;
+//! use astro_algos::observer::Observer;
+//! use astro_algos::planets::Planet;
+//! use astro_algos::time::{JD, UTC};
+//!
+//! let palomar = Observer::new(Angle::from_degrees(33.356), Angle::from_degrees(-116.863), 1706.0);
+//! let mars = Observation::new(palomar)
+//!     .at(JD::<UTC>::from(2_460_000.5))
+//!     .target(Planet::Mars)
+//!     .apparent_alt_az();
+//! assert!(mars.altitude > Angle::ZERO);
+//!
+//! let place = astro_algos::observation::apparent_place(
+//!     Planet::Mars,
+//!     JD::<UTC>::from(2_460_000.5),
+//!     palomar,
+//! );
+//! assert_eq!(place.horizontal, mars);
+//! ```
+use crate::bodies::Body;
+use crate::coords::{EquatorialPosition, Horizontal, TrueOfDate};
+use crate::observer::Observer;
+use crate::planets::Aberration;
+use crate::refraction::Atmosphere;
+use crate::time::{TimeScale, JD, TT, UT, UTC};
+
+/// Computes the place of `body` in the sky of `observer` at the instant `t`, refracted by the
+/// standard atmosphere of `Atmosphere::default`.
+pub fn apparent_place<B: Into<Body>>(body: B, t: JD<UTC>, observer: Observer) -> ApparentPlace {
+    Observation::new(observer)
+        .at(t)
+        .target(body)
+        .apparent_place()
+}
+
+/// Where a body appears in the sky of an observer, see `apparent_place`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ApparentPlace {
+    /// The topocentric position, referred to the true equator and equinox of date
+    pub equatorial: EquatorialPosition<TrueOfDate>,
+    /// The azimuth and the altitude, including the refraction
+    pub horizontal: Horizontal,
+}
+
+/// A builder for the place of a body as seen by an observer at an instant.
+///
+/// The instant and the body are set with `at` and `target`, in either order, and the type keeps
+/// track of which have been set: the positions can only be computed once both are. The default
+/// type parameters are those of a complete observation. The atmosphere defaults to the standard
+/// conditions of `Atmosphere::default`.
+///
+/// ```compile_fail
+/// use astro_algos::angle::Angle;
+/// use astro_algos::observation::Observation;
+/// use astro_algos::observer::Observer;
+/// use astro_algos::planets::Planet;
+///
+/// let observer = Observer::new(Angle::ZERO, Angle::ZERO, 0.0);
+/// // No instant was given
+/// Observation::new(observer).target(Planet::Mars).apparent_alt_az();
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Observation<T = JD<TT>, B = Body> {
+    observer: Observer,
+    t: T,
+    body: B,
+    atmosphere: Option<Atmosphere>,
+}
+
+impl Observation<(), ()> {
+    pub fn new(observer: Observer) -> Observation<(), ()> {
+        Observation {
+            observer,
+            t: (),
+            body: (),
+            atmosphere: Some(Atmosphere::default()),
+        }
+    }
+}
+
+impl<T, B> Observation<T, B> {
+    /// Sets the instant of the observation, in any time scale
+    pub fn at<S: TimeScale>(self, t: JD<S>) -> Observation<JD<TT>, B> {
+        Observation {
+            observer: self.observer,
+            t: t.to_scale(),
+            body: self.body,
+            atmosphere: self.atmosphere,
+        }
+    }
+
+    /// Sets the body being observed
+    pub fn target<X: Into<Body>>(self, body: X) -> Observation<T, Body> {
+        Observation {
+            observer: self.observer,
+            t: self.t,
+            body: body.into(),
+            atmosphere: self.atmosphere,
+        }
+    }
+
+    /// Sets the conditions of the atmosphere used for the refraction
+    pub fn atmosphere(self, atmosphere: Atmosphere) -> Observation<T, B> {
+        Observation {
+            atmosphere: Some(atmosphere),
+            ..self
+        }
+    }
+
+    /// Leaves out the refraction, giving the true altitude instead of the one the body appears at
+    pub fn without_refraction(self) -> Observation<T, B> {
+        Observation {
+            atmosphere: None,
+            ..self
+        }
+    }
+}
+
+impl Observation {
+    /// Computes the apparent position of the body seen from the center of the Earth, referred to
+    /// the true equator and equinox of date.
+    pub fn geocentric(&self) -> EquatorialPosition<TrueOfDate> {
+        let position = self
+            .body
+            .get_geocentric_position(&self.t, Aberration::Planetary)
+            .position()
+            .to_equatorial();
+        let direction = position.direction.to_equinox(TrueOfDate(self.t));
+        EquatorialPosition::new(direction, position.distance)
+    }
+
+    /// Computes the apparent position of the body seen by the observer, referred to the true
+    /// equator and equinox of date.
+    pub fn topocentric(&self) -> EquatorialPosition<TrueOfDate> {
+        self.geocentric()
+            .to_topocentric(&self.observer, &self.t.to_scale::<UT>())
+    }
+
+    /// Computes the place of the body in the observer's sky, including the refraction unless it
+    /// has been left out.
+    ///
+    /// Bodies more than a degree below the horizon aren't refracted.
+    pub fn apparent_alt_az(&self) -> Horizontal {
+        self.apparent_place().horizontal
+    }
+
+    /// Computes both the topocentric position of the body and its place in the observer's sky.
+    pub fn apparent_place(&self) -> ApparentPlace {
+        let equatorial = self.topocentric();
+        let horizontal = equatorial
+            .direction
+            .to_horizontal(&self.observer, &self.t.to_scale::<UT>());
+        let horizontal = match self
+            .atmosphere
+            .and_then(|atmosphere| atmosphere.true_to_apparent(horizontal.altitude))
+        {
+            Some(altitude) => Horizontal::new(horizontal.azimuth, altitude),
+            None => horizontal,
+        };
+
+        ApparentPlace {
+            equatorial,
+            horizontal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::angle::{Angle, DegreesMinutesSeconds, HoursMinutesSeconds, Sign};
+    use crate::parallax;
+    use crate::planets::Planet;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn apparent_place() {
+        // Example 33.a, page 225: Venus on 1992 December 20 at 0h TD has the apparent coordinates
+        // 21h04m41.454s and -18°53'16.84". The book uses the stellar aberration and the FK5
+        // correction, which account for a few tenths of an arcsecond.
+        let observer = Observer::new(Angle::ZERO, Angle::ZERO, 0.0);
+        let venus = Observation::new(observer)
+            .at(JD::<TT>::from(2_448_976.5))
+            .target(Planet::Venus)
+            .geocentric();
+        let expected_ra = HoursMinutesSeconds::new(Sign::Positive, 21, 4, 41.454)
+            .unwrap()
+            .as_angle();
+        let expected_dec = DegreesMinutesSeconds::new(Sign::Negative, 18, 53, 16.84)
+            .unwrap()
+            .as_angle();
+        assert_approx_eq!(
+            (venus.direction.ra().angle() - expected_ra)
+                .normalize_pm180()
+                .as_arcseconds(),
+            0.0,
+            0.5
+        );
+        assert_approx_eq!(
+            (venus.direction.dec().angle() - expected_dec)
+                .normalize_pm180()
+                .as_arcseconds(),
+            0.0,
+            0.5
+        );
+        // The planetary aberration measures the distance from where the Earth was when the light
+        // left Venus, which is 0.000064 AU less than the book's 0.910947 AU
+        assert_approx_eq!(venus.distance, 0.910_883, 1e-6);
+    }
+
+    #[test]
+    fn horizontal() {
+        // Example 13.b, page 95: Venus seen from the US Naval Observatory on 1987 April 10 at
+        // 19h21m UT, at an altitude of 15.1249° before parallax and refraction. The parallax
+        // lowers it by a few arcseconds.
+        let washington = Observer::new(
+            DegreesMinutesSeconds::new(Sign::Positive, 38, 55, 17.0)
+                .unwrap()
+                .as_angle(),
+            DegreesMinutesSeconds::new(Sign::Negative, 77, 3, 56.0)
+                .unwrap()
+                .as_angle(),
+            0.0,
+        );
+        let observation = Observation::new(washington)
+            .at(JD::<UT>::from_parts(2_446_895.5, 19.35 / 24.0))
+            .target(Planet::Venus);
+        let geometric = observation.without_refraction().apparent_alt_az();
+        let distance = observation.geocentric().distance;
+        let parallax = parallax::horizontal_parallax(distance) * geometric.altitude.cos();
+        assert_approx_eq!((geometric.altitude + parallax).as_degrees(), 15.1249, 2e-4);
+        assert_approx_eq!(geometric.azimuth.as_degrees(), 68.0337 + 180.0, 1e-3);
+
+        // The refraction lifts the body by about 3.5'
+        let refracted = observation.apparent_alt_az();
+        assert_approx_eq!(
+            refracted.azimuth.as_degrees(),
+            geometric.azimuth.as_degrees()
+        );
+        assert_approx_eq!(
+            refracted.altitude.as_degrees(),
+            Atmosphere::default()
+                .true_to_apparent(geometric.altitude)
+                .unwrap()
+                .as_degrees()
+        );
+        assert!((refracted.altitude - geometric.altitude).as_degrees() * 60.0 > 3.0);
+    }
+
+    #[test]
+    fn one_call() {
+        // Example 13.b again, with the instant given in UTC. UT1 differed from UTC by less than a
+        // second in 1987, which moves Venus by a few arcseconds at most.
+        let washington = Observer::new(
+            DegreesMinutesSeconds::new(Sign::Positive, 38, 55, 17.0)
+                .unwrap()
+                .as_angle(),
+            DegreesMinutesSeconds::new(Sign::Negative, 77, 3, 56.0)
+                .unwrap()
+                .as_angle(),
+            0.0,
+        );
+        let t = JD::<UTC>::from_parts(2_446_895.5, 19.35 / 24.0);
+        let place = super::apparent_place(Planet::Venus, t, washington);
+        assert_approx_eq!(place.horizontal.azimuth.as_degrees(), 68.0337 + 180.0, 2e-3);
+
+        // The parallax lowers Venus and the refraction lifts it again
+        let geocentric = Observation::new(washington)
+            .at(t)
+            .target(Planet::Venus)
+            .geocentric();
+        let parallax =
+            parallax::horizontal_parallax(geocentric.distance) * place.horizontal.altitude.cos();
+        let refraction = Atmosphere::default()
+            .apparent_to_true(place.horizontal.altitude)
+            .unwrap()
+            - place.horizontal.altitude;
+        assert_approx_eq!(
+            (place.horizontal.altitude + refraction + parallax).as_degrees(),
+            15.1249,
+            2e-3
+        );
+
+        // The topocentric position is the geocentric one moved by the parallax
+        let separation = geocentric.direction.separation(&place.equatorial.direction);
+        assert!(separation.as_arcseconds() > 1.0);
+        assert!(separation < parallax::horizontal_parallax(geocentric.distance));
+    }
+
+    #[test]
+    fn moon() {
+        // Example 47.a, page 342: the Moon on 1992 April 12 at 0h TD has the apparent coordinates
+        // 134.688470° and 13.768368°. The book leaves out the light-time, which moves the Moon by
+        // about 0.7".
+        let observer = Observer::new(Angle::from_degrees(45.0), Angle::ZERO, 0.0);
+        let t = JD::<TT>::from(2_448_724.5);
+        let observation = Observation::new(observer).at(t).target(Body::Moon);
+        let geocentric = observation.geocentric();
+        assert_approx_eq!(
+            geocentric.direction.ra().angle().as_degrees(),
+            134.688_470,
+            1.0 / 3600.0
+        );
+        assert_approx_eq!(
+            geocentric.direction.dec().angle().as_degrees(),
+            13.768_368,
+            0.5 / 3600.0
+        );
+        assert_approx_eq!(
+            geocentric.distance * crate::METERS_PER_AU,
+            368_409_700.0,
+            100.0
+        );
+
+        // Seen from the surface of the Earth the Moon is displaced by up to its horizontal
+        // parallax of nearly a degree, less the closer it is to the zenith
+        let place = super::apparent_place(Body::Moon, t.to_scale::<UTC>(), observer);
+        let separation = geocentric.direction.separation(&place.equatorial.direction);
+        let parallax = crate::moon::horizontal_parallax(&t);
+        let zenith_distance = Angle::from_degrees(90.0) - place.horizontal.altitude;
+        assert!(separation < parallax);
+        assert!(separation > parallax * zenith_distance.sin() * 0.9);
+    }
+}
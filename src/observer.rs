@@ -0,0 +1,129 @@
+//! The place on the Earth a body is observed from.
+use crate::angle::Angle;
+use crate::time::sidereal::{apparent_sidereal_time, mean_sidereal_time};
+use crate::time::{JD, UT};
+
+/// The equatorial radius of the Earth, in meters
+pub const EQUATORIAL_RADIUS: f64 = 6_378_140.0;
+
+/// The ratio of the polar radius of the Earth to the equatorial one
+const POLAR_TO_EQUATORIAL: f64 = 0.996_647_19;
+
+/// An observer on the surface of the Earth.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Observer {
+    /// The geographic latitude, positive north
+    pub latitude: Angle,
+    /// The longitude, positive east of Greenwich
+    pub longitude: Angle,
+    /// The height above sea level, in meters
+    pub elevation: f64,
+}
+
+impl Observer {
+    pub fn new(latitude: Angle, longitude: Angle, elevation: f64) -> Observer {
+        Observer {
+            latitude,
+            longitude,
+            elevation,
+        }
+    }
+
+    /// Computes the local mean sidereal time at the given instant, in the range [0°, 360°)
+    pub fn local_sidereal_time(&self, t: &JD<UT>) -> Angle {
+        (mean_sidereal_time(t) + self.longitude).normalize_0_360()
+    }
+
+    /// Computes the local apparent sidereal time at the given instant, in the range [0°, 360°)
+    pub fn local_apparent_sidereal_time(&self, t: &JD<UT>) -> Angle {
+        (apparent_sidereal_time(t) + self.longitude).normalize_0_360()
+    }
+
+    /// Computes the local hour angle of a body with the given right ascension, positive west of
+    /// the meridian, in the range [-180°, 180°)
+    pub fn hour_angle(&self, t: &JD<UT>, right_ascension: Angle) -> Angle {
+        (self.local_sidereal_time(t) - right_ascension).normalize_pm180()
+    }
+
+    /// Computes ρ sin φ′, where ρ is the distance of the observer from the center of the Earth in
+    /// units of the equatorial radius, and φ′ the geocentric latitude. See chapter 11.
+    pub fn rho_sin_phi_prime(&self) -> f64 {
+        let u = self.reduced_latitude();
+        POLAR_TO_EQUATORIAL * u.sin() + self.elevation / EQUATORIAL_RADIUS * self.latitude.sin()
+    }
+
+    /// Computes ρ cos φ′, where ρ is the distance of the observer from the center of the Earth in
+    /// units of the equatorial radius, and φ′ the geocentric latitude. See chapter 11.
+    pub fn rho_cos_phi_prime(&self) -> f64 {
+        let u = self.reduced_latitude();
+        u.cos() + self.elevation / EQUATORIAL_RADIUS * self.latitude.cos()
+    }
+
+    /// Computes the geocentric latitude φ′, the angle between the equator and the direction from
+    /// the center of the Earth to the observer
+    pub fn geocentric_latitude(&self) -> Angle {
+        Angle::atan2(self.rho_sin_phi_prime(), self.rho_cos_phi_prime())
+    }
+
+    /// Computes the distance ρ of the observer from the center of the Earth, in units of the
+    /// equatorial radius
+    pub fn geocentric_distance(&self) -> f64 {
+        self.rho_sin_phi_prime().hypot(self.rho_cos_phi_prime())
+    }
+
+    /// The latitude u of the point of the ellipsoid under the observer, seen from the center of a
+    /// sphere with the equatorial radius
+    fn reduced_latitude(&self) -> Angle {
+        Angle::atan(POLAR_TO_EQUATORIAL * self.latitude.tan())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::angle::{DegreesMinutesSeconds, HoursMinutesSeconds, Sign};
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn hour_angle() {
+        // Example 13.b, Venus seen from the US Naval Observatory. The example uses the apparent
+        // sidereal time, which differs from the mean one by less than a second of time.
+        let observer = Observer::new(
+            DegreesMinutesSeconds::new(Sign::Positive, 38, 55, 17.0)
+                .unwrap()
+                .as_angle(),
+            DegreesMinutesSeconds::new(Sign::Negative, 77, 3, 56.0)
+                .unwrap()
+                .as_angle(),
+            0.0,
+        );
+        let t = JD::from_parts(2_446_895.5, 19.35 / 24.0);
+        let ra = HoursMinutesSeconds::new(Sign::Positive, 23, 9, 16.641)
+            .unwrap()
+            .as_angle();
+        assert_approx_eq!(observer.hour_angle(&t, ra).as_degrees(), 64.352_133, 1e-3);
+    }
+
+    #[test]
+    fn geocentric_position() {
+        // Example 11.a, page 82: the Palomar Observatory
+        let palomar = Observer::new(
+            DegreesMinutesSeconds::new(Sign::Positive, 33, 21, 22.0)
+                .unwrap()
+                .as_angle(),
+            Angle::from_degrees(-116.8625),
+            1706.0,
+        );
+        assert_approx_eq!(palomar.rho_sin_phi_prime(), 0.546_861, 1e-6);
+        assert_approx_eq!(palomar.rho_cos_phi_prime(), 0.836_339, 1e-6);
+
+        // At sea level, φ - φ′ is 692.73" sin 2φ - 1.16" sin 4φ, and ρ shrinks from 1 at the
+        // equator towards the pole
+        let observer = Observer::new(Angle::from_degrees(45.0), Angle::ZERO, 0.0);
+        let difference = observer.latitude - observer.geocentric_latitude();
+        assert_approx_eq!(difference.as_arcseconds(), 692.73, 0.01);
+        let equator = Observer::new(Angle::ZERO, Angle::ZERO, 0.0);
+        assert_approx_eq!(equator.geocentric_distance(), 1.0, 1e-12);
+        assert!(observer.geocentric_distance() < 1.0);
+    }
+}
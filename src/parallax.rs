@@ -0,0 +1,161 @@
+//! Parallax due to the position of the observer on the Earth.
+//!
+//! Positions are usually computed for the center of the Earth, but a body close to the Earth is
+//! seen in a noticeably different direction from its surface. The Moon can be displaced by up to a
+//! degree. These implement the corrections of chapter 40.
+use crate::angle::Angle;
+use crate::coords::{Equatorial, Equinox};
+use crate::observer::Observer;
+use crate::time::{JD, UT};
+
+/// The equatorial horizontal parallax of a body one astronomical unit away, in arcseconds
+const SOLAR_PARALLAX: f64 = 8.794;
+
+/// Computes the equatorial horizontal parallax of a body at `distance` astronomical units from the
+/// center of the Earth, the angle the equatorial radius of the Earth subtends from the body.
+pub fn horizontal_parallax(distance: f64) -> Angle {
+    Angle::asin(Angle::from_arcseconds(SOLAR_PARALLAX).sin() / distance)
+}
+
+/// Converts the geocentric equatorial coordinates of a body at `distance` astronomical units into
+/// the topocentric coordinates seen by `observer` at the instant `t`, using the rigorous formulas
+/// 40.2 and 40.3.
+///
+/// The coordinates should be referred to the equator and equinox of date.
+pub fn topocentric_equatorial<E: Equinox>(
+    position: &Equatorial<E>,
+    distance: f64,
+    observer: &Observer,
+    t: &JD<UT>,
+) -> Equatorial<E> {
+    let rho_sin_phi = observer.rho_sin_phi_prime();
+    let rho_cos_phi = observer.rho_cos_phi_prime();
+    let sin_pi = horizontal_parallax(distance).sin();
+    let (sin_h, cos_h) = position.hour_angle(observer, t).sin_cos();
+    let (sin_d, cos_d) = position.dec().angle().sin_cos();
+
+    let denominator = cos_d - rho_cos_phi * sin_pi * cos_h;
+    let delta_ra = Angle::atan2(-rho_cos_phi * sin_pi * sin_h, denominator);
+    Equatorial::from_angles_with_equinox(
+        position.ra().angle() + delta_ra,
+        Angle::atan2((sin_d - rho_sin_phi * sin_pi) * delta_ra.cos(), denominator),
+        position.equinox(),
+    )
+}
+
+/// Converts the geocentric equatorial coordinates of a body at `distance` astronomical units into
+/// the topocentric coordinates seen by `observer` at the instant `t`, using the approximate
+/// formulas 40.4.
+///
+/// These treat the parallax as a small angle, which is good enough for the Sun and the planets but
+/// not for the Moon. The coordinates should be referred to the equator and equinox of date.
+pub fn topocentric_equatorial_approx<E: Equinox>(
+    position: &Equatorial<E>,
+    distance: f64,
+    observer: &Observer,
+    t: &JD<UT>,
+) -> Equatorial<E> {
+    let rho_sin_phi = observer.rho_sin_phi_prime();
+    let rho_cos_phi = observer.rho_cos_phi_prime();
+    let parallax = horizontal_parallax(distance);
+    let (sin_h, cos_h) = position.hour_angle(observer, t).sin_cos();
+    let (sin_d, cos_d) = position.dec().angle().sin_cos();
+
+    Equatorial::from_angles_with_equinox(
+        position.ra().angle() - parallax * (rho_cos_phi * sin_h / cos_d),
+        position.dec().angle() - parallax * (rho_sin_phi * cos_d - rho_cos_phi * cos_h * sin_d),
+        position.equinox(),
+    )
+}
+
+/// Corrects the geocentric altitude of a body at `distance` astronomical units into the altitude
+/// seen from the surface of the Earth.
+///
+/// The Earth is taken as a sphere, which is the usual way to correct the altitudes of the Moon in
+/// the horizontal coordinates of an observer. The error is below 12" for the Moon.
+pub fn topocentric_altitude(altitude: Angle, distance: f64) -> Angle {
+    let sin_pi = horizontal_parallax(distance).sin();
+    let (sin_h, cos_h) = altitude.sin_cos();
+    Angle::atan2(sin_h - sin_pi, cos_h)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::angle::{DegreesMinutesSeconds, HoursMinutesSeconds, Sign};
+    use crate::coords::TrueOfDate;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn mars_from_palomar() {
+        // Example 40.a, page 280: Mars on 2003 August 28 at 3h17m UT, seen from Palomar
+        let palomar = Observer::new(
+            DegreesMinutesSeconds::new(Sign::Positive, 33, 21, 22.0)
+                .unwrap()
+                .as_angle(),
+            HoursMinutesSeconds::new(Sign::Negative, 7, 47, 27.0)
+                .unwrap()
+                .as_angle(),
+            1706.0,
+        );
+        let t = JD::from_parts(2_452_879.5, (3.0 + 17.0 / 60.0) / 24.0);
+        let mars = Equatorial::with_equinox(
+            Angle::from_degrees(339.530_208),
+            Angle::from_degrees(-15.771_083),
+            TrueOfDate(t.to_scale()),
+        )
+        .unwrap();
+
+        assert_approx_eq!(horizontal_parallax(0.37276).as_arcseconds(), 23.592, 1e-3);
+
+        let topocentric = topocentric_equatorial(&mars, 0.37276, &palomar, &t);
+        let expected_ra = HoursMinutesSeconds::new(Sign::Positive, 22, 38, 8.54)
+            .unwrap()
+            .as_angle();
+        let expected_dec = DegreesMinutesSeconds::new(Sign::Negative, 15, 46, 30.0)
+            .unwrap()
+            .as_angle();
+        assert_approx_eq!(
+            topocentric.ra().angle().as_degrees(),
+            expected_ra.as_degrees(),
+            0.01 / 240.0
+        );
+        assert_approx_eq!(
+            topocentric.dec().angle().as_degrees(),
+            expected_dec.as_degrees(),
+            0.1 / 3600.0
+        );
+
+        // The approximate formulas are nearly as good for a planet
+        let approx = topocentric_equatorial_approx(&mars, 0.37276, &palomar, &t);
+        assert_approx_eq!(
+            approx.ra().angle().as_degrees(),
+            expected_ra.as_degrees(),
+            0.01 / 240.0
+        );
+        assert_approx_eq!(
+            approx.dec().angle().as_degrees(),
+            expected_dec.as_degrees(),
+            0.1 / 3600.0
+        );
+    }
+
+    #[test]
+    fn lowered_altitude() {
+        // The Moon at its mean distance is on the horizon when it is its full parallax above the
+        // horizon of the center of the Earth, and isn't lowered at all at the zenith
+        let distance = 384_400_000.0 / crate::METERS_PER_AU;
+        let parallax = horizontal_parallax(distance);
+        assert_approx_eq!(parallax.as_degrees(), 0.9508, 1e-3);
+        assert_approx_eq!(
+            topocentric_altitude(parallax, distance).as_degrees(),
+            0.0,
+            1e-12
+        );
+        assert_approx_eq!(
+            topocentric_altitude(Angle::RIGHT_ANGLE, distance).as_degrees(),
+            90.0,
+            1e-9
+        );
+    }
+}
@@ -8,11 +8,22 @@ mod saturn;
 mod uranus;
 mod venus;
 
+use crate::aberration;
 use crate::angle::Angle;
-use crate::coords::HeliocentricSpherical;
-use crate::time::JD;
+use crate::coords::{
+    Ecliptical, EclipticalPosition, Equatorial, HeliocentricRectangular, HeliocentricSpherical,
+    J2000,
+};
+use crate::frames;
+use crate::linalg::Vec3;
+use crate::precession;
+use crate::time::{JulianMillennia, JD};
+
+/// The time it takes light to travel one astronomical unit, in days
+pub(crate) const LIGHT_TIME_PER_AU: f64 = 0.005_775_518_3;
 
 /// Representation of the planets in our solar system.
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Planet {
     Mercury,
     Venus,
@@ -33,7 +44,7 @@ impl Planet {
     /// the accuracy of the positions for the inner four planets degrade. Finally past +/- 6000 years
     /// from the year 2000 the accuracy of Uranus and Neptune's positions start to degrade.
     pub fn get_location(&self, t: &JD) -> HeliocentricSpherical {
-        let tau = (t.as_f64() - 2451_545.0) / 365_250.0;
+        let tau = JulianMillennia::from(*t).0;
         let (l_terms, b_terms, r_terms) = match self {
             Planet::Mercury => (mercury::LTERMS, mercury::BTERMS, mercury::RTERMS),
             Planet::Venus => (venus::LTERMS, venus::BTERMS, venus::RTERMS),
@@ -50,13 +61,226 @@ impl Planet {
         let r = sum_terms(&r_terms, tau);
 
         HeliocentricSpherical {
-            longitude: Angle::from_radians(l)
-                .wrap(&Angle::from_degrees(0.0), &Angle::from_degrees(360.0)),
-            latitude: Angle::from_radians(b)
-                .wrap(&Angle::from_degrees(-90.0), &Angle::from_degrees(90.0)),
+            longitude: Angle::from_radians(l).normalize_0_360(),
+            latitude: Angle::from_radians(b),
             radius: r,
         }
     }
+
+    /// Computes the position of the planet as seen from the center of the Earth, for the J2000.0
+    /// equinox, following the method of chapter 33.
+    ///
+    /// The `aberration` parameter selects how the finite speed of light is accounted for, see
+    /// `Aberration` for the details of each option. No correction for nutation is made, so the
+    /// results are referred to the mean equinox.
+    ///
+    /// # Note
+    /// Asking for the geocentric position of `Planet::Earth` is meaningless, the result will have a
+    /// distance of zero and an undefined direction.
+    pub fn get_geocentric_position(&self, t: &JD, aberration: Aberration) -> GeocentricPosition {
+        geocentric_position(|t| to_rectangular(&self.get_location(t)), t, aberration)
+    }
+
+    /// Computes the position of the planet in one of the frames of `Frame`.
+    ///
+    /// Every frame is computed from the VSOP87B series, see `Frame`.
+    pub fn get_position(&self, t: &JD, frame: Frame) -> Position {
+        let heliocentric = || to_rectangular(&self.get_location(t));
+        match frame {
+            Frame::RectangularJ2000 => Position::Rectangular(heliocentric()),
+            Frame::SphericalJ2000 => Position::Spherical(self.get_location(t)),
+            Frame::RectangularOfDate => {
+                Position::Rectangular(to_ecliptic_of_date(&heliocentric(), t))
+            }
+            Frame::SphericalOfDate => {
+                Position::Spherical(to_spherical(&to_ecliptic_of_date(&heliocentric(), t)))
+            }
+            Frame::BarycentricJ2000 => {
+                Position::Rectangular(heliocentric() - solar_barycentric_offset(t))
+            }
+        }
+    }
+}
+
+/// The coordinates a planet's position can be given in, all derived from the VSOP87B series.
+///
+/// The other VSOP87 series (A, C, D and E) aren't included in this crate. The frames of date are
+/// precessed with the formulas of chapter 21, and the barycenter only accounts for the Sun and the
+/// eight planets, so positions in those frames differ slightly from what the corresponding VSOP87
+/// series would give.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Frame {
+    /// Heliocentric rectangular coordinates, referred to the ecliptic and equinox of J2000.0
+    RectangularJ2000,
+    /// Heliocentric spherical coordinates, referred to the ecliptic and equinox of J2000.0, as
+    /// given by VSOP87B
+    SphericalJ2000,
+    /// Heliocentric rectangular coordinates, referred to the ecliptic and mean equinox of date
+    RectangularOfDate,
+    /// Heliocentric spherical coordinates, referred to the ecliptic and mean equinox of date
+    SphericalOfDate,
+    /// Rectangular coordinates centered on the barycenter of the Sun and the eight planets,
+    /// referred to the ecliptic and equinox of J2000.0
+    BarycentricJ2000,
+}
+
+/// A position of a planet in one of the frames of `Frame`, in astronomical units.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Position {
+    Rectangular(Vec3),
+    Spherical(HeliocentricSpherical),
+}
+
+impl Position {
+    /// Gets the position as rectangular coordinates, converting if needed
+    pub fn to_rectangular(&self) -> Vec3 {
+        match self {
+            Position::Rectangular(v) => *v,
+            Position::Spherical(position) => to_rectangular(position),
+        }
+    }
+
+    /// Gets the position as spherical coordinates, converting if needed
+    pub fn to_spherical(&self) -> HeliocentricSpherical {
+        match self {
+            Position::Rectangular(v) => to_spherical(v),
+            Position::Spherical(position) => *position,
+        }
+    }
+}
+
+/// The ratio of the mass of the Sun to the mass of each planet, including its satellites
+const MASS_RATIOS: [(Planet, f64); 8] = [
+    (Planet::Mercury, 6_023_600.0),
+    (Planet::Venus, 408_523.71),
+    (Planet::Earth, 328_900.56),
+    (Planet::Mars, 3_098_708.0),
+    (Planet::Jupiter, 1_047.348_6),
+    (Planet::Saturn, 3_497.898),
+    (Planet::Uranus, 22_902.98),
+    (Planet::Neptune, 19_412.24),
+];
+
+/// The heliocentric position of the barycenter of the Sun and the planets, referred to the
+/// ecliptic and equinox of J2000.0
+fn solar_barycentric_offset(t: &JD) -> Vec3 {
+    let mut weighted = Vec3::new(0.0, 0.0, 0.0);
+    let mut total = 1.0;
+    for (planet, ratio) in MASS_RATIOS.iter() {
+        weighted = weighted + to_rectangular(&planet.get_location(t)) * (1.0 / ratio);
+        total += 1.0 / ratio;
+    }
+    weighted * (1.0 / total)
+}
+
+/// Rotates rectangular coordinates from the ecliptic and equinox of J2000.0 to the ecliptic and
+/// mean equinox of `t`
+fn to_ecliptic_of_date(v: &Vec3, t: &JD) -> Vec3 {
+    let equatorial = precession::matrix(&JD::J2000, t) * frames::ecliptic_to_equatorial(v);
+    let (sin_e, cos_e) = precession::mean_obliquity(t).sin_cos();
+    Vec3::new(
+        equatorial.x,
+        equatorial.y * cos_e + equatorial.z * sin_e,
+        -equatorial.y * sin_e + equatorial.z * cos_e,
+    )
+}
+
+/// Computes the geocentric position of a body following chapter 33, given a function returning the
+/// body's heliocentric rectangular coordinates (ecliptic and equinox of J2000.0, in astronomical
+/// units) at a given time.
+pub(crate) fn geocentric_position<F: Fn(&JD) -> Vec3>(
+    heliocentric: F,
+    t: &JD,
+    aberration: Aberration,
+) -> GeocentricPosition {
+    let earth = to_rectangular(&Planet::Earth.get_location(t));
+    let mut light_time = 0.0;
+    let mut geocentric = heliocentric(t) - earth;
+
+    if aberration != Aberration::None {
+        // Iterate on the light-time until it stops changing
+        for _ in 0..10 {
+            let previous = light_time;
+            light_time = LIGHT_TIME_PER_AU * geocentric.norm();
+            let emitted = *t - light_time;
+            let observer = if aberration == Aberration::Planetary {
+                to_rectangular(&Planet::Earth.get_location(&emitted))
+            } else {
+                earth
+            };
+            geocentric = heliocentric(&emitted) - observer;
+            if (light_time - previous).abs() < 1e-10 {
+                break;
+            }
+        }
+    }
+
+    let mut ecliptical = Ecliptical::<J2000>::from_angles(
+        Angle::atan2(geocentric.y, geocentric.x),
+        Angle::atan2(
+            geocentric.z,
+            (geocentric.x * geocentric.x + geocentric.y * geocentric.y).sqrt(),
+        ),
+    );
+
+    if aberration == Aberration::Stellar {
+        let sun_longitude = Angle::atan2(-earth.y, -earth.x);
+        ecliptical = aberration::annual_ecliptical(&ecliptical, sun_longitude, t);
+    }
+
+    GeocentricPosition {
+        ecliptical,
+        distance: geocentric.norm(),
+        light_time,
+    }
+}
+
+/// The ways the finite speed of light can be accounted for when computing the geocentric position
+/// of a planet.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Aberration {
+    /// No correction at all, giving the geometric position of the planet at the given instant.
+    None,
+    /// The planet's position is corrected for light-time, and the annual aberration due to the
+    /// Earth's motion is then applied as it would be for a star. This is the approach taken in
+    /// example 33.a.
+    Stellar,
+    /// The planetary aberration, which combines the light-time and the Earth's motion by using the
+    /// positions of both the planet and the Earth at the moment the light left the planet.
+    Planetary,
+}
+
+/// The position of a body as seen from the center of the Earth.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GeocentricPosition {
+    /// The direction to the body
+    pub ecliptical: Ecliptical<J2000>,
+    /// The distance to the body, in astronomical units
+    pub distance: f64,
+    /// The time taken by the light to reach the Earth, in days. This is zero if no correction for
+    /// light-time was requested.
+    pub light_time: f64,
+}
+
+impl GeocentricPosition {
+    /// The direction to the body in equatorial coordinates, referred to the equator and equinox of
+    /// J2000.0
+    pub fn equatorial(&self) -> Equatorial<J2000> {
+        self.ecliptical.to_equatorial()
+    }
+
+    /// The direction to the body together with its distance
+    pub fn position(&self) -> EclipticalPosition<J2000> {
+        EclipticalPosition::new(self.ecliptical, self.distance)
+    }
+}
+
+fn to_rectangular(position: &HeliocentricSpherical) -> Vec3 {
+    HeliocentricRectangular::from(*position).into()
+}
+
+fn to_spherical(v: &Vec3) -> HeliocentricSpherical {
+    HeliocentricRectangular::from(*v).into()
 }
 
 fn sum_terms(terms: &[&[(f64, f64, f64)]], tau: f64) -> f64 {
@@ -66,7 +290,7 @@ fn sum_terms(terms: &[&[(f64, f64, f64)]], tau: f64) -> f64 {
         .map(|(power_terms, power)| {
             power_terms
                 .iter()
-                .map(|(a, b, c)| a * (b + c * tau).cos() * tau.powi(power as i32))
+                .map(|(a, b, c)| a * (b + c * tau).cos() * tau.powi(power))
                 .sum::<f64>()
         })
         .sum::<f64>()
@@ -75,6 +299,8 @@ fn sum_terms(terms: &[&[(f64, f64, f64)]], tau: f64) -> f64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::angle::{DegreesMinutesSeconds, HoursMinutesSeconds, Sign};
+    use crate::coords::TrueOfDate;
     use assert_approx_eq::assert_approx_eq;
 
     #[test]
@@ -428,4 +654,115 @@ mod tests {
         assert_approx_eq!(position.latitude.as_radians(), 0.0040125142);
         assert_approx_eq!(position.radius, 30.0653694889);
     }
+
+    #[test]
+    fn venus_geocentric_position() {
+        // Example 33.a, page 225
+        let t = JD::from(2448976.5);
+        let position = Planet::Venus.get_geocentric_position(&t, Aberration::Stellar);
+        assert_approx_eq!(position.light_time, 0.0052612, 1e-7);
+        assert_approx_eq!(position.distance, 0.910947, 1e-5);
+
+        // The example finds α = 21h04m41.50s and δ = -18°53′16.8″, referred to the true equinox
+        // of date. It uses the truncated series of the appendix, so the positions only agree to
+        // within an arcsecond.
+        let equatorial = position.equatorial().to_equinox(TrueOfDate(t));
+        let expected_ra = HoursMinutesSeconds::new(Sign::Positive, 21, 4, 41.50).unwrap();
+        let expected_dec = DegreesMinutesSeconds::new(Sign::Negative, 18, 53, 16.8).unwrap();
+        assert_approx_eq!(
+            (equatorial.ra().angle() - expected_ra.as_angle()).as_arcseconds(),
+            0.0,
+            1.0
+        );
+        assert_approx_eq!(
+            (equatorial.dec().angle() - expected_dec.as_angle()).as_arcseconds(),
+            0.0,
+            1.0
+        );
+    }
+
+    #[test]
+    fn mars_opposition_aberration() {
+        // Mars was at opposition on 2003 August 28
+        let t = JD::from(2452880.25);
+        let geometric = Planet::Mars.get_geocentric_position(&t, Aberration::None);
+        let stellar = Planet::Mars.get_geocentric_position(&t, Aberration::Stellar);
+        let planetary = Planet::Mars.get_geocentric_position(&t, Aberration::Planetary);
+
+        // The two approaches agree to first order, only differing by a few milliarcseconds ...
+        let arcsec = |a: Angle, b: Angle| (a - b).abs().as_arcseconds();
+        assert!(
+            arcsec(
+                stellar.ecliptical.lon().angle(),
+                planetary.ecliptical.lon().angle()
+            ) < 0.02
+        );
+        assert!(
+            arcsec(
+                stellar.ecliptical.lat().angle(),
+                planetary.ecliptical.lat().angle()
+            ) < 0.02
+        );
+        assert_approx_eq!(stellar.light_time, planetary.light_time, 1e-6);
+
+        // ... but both differ noticeably from the geometric position
+        assert!(
+            arcsec(
+                geometric.ecliptical.lon().angle(),
+                planetary.ecliptical.lon().angle()
+            ) > 1.5
+        );
+        assert_eq!(geometric.light_time, 0.0);
+        assert_approx_eq!(geometric.distance, 0.372848, 1e-6);
+    }
+
+    #[test]
+    fn frames() {
+        let t = JD::from(2_451_545.0 + 3_652.5);
+        let mars = |t: &JD, frame| Planet::Mars.get_position(t, frame);
+        let spherical = Planet::Mars.get_location(&t);
+
+        // The rectangular and spherical coordinates are the same position
+        let rectangular = mars(&t, Frame::RectangularJ2000).to_spherical();
+        assert_approx_eq!(
+            rectangular.longitude.as_radians(),
+            spherical.longitude.as_radians(),
+            1e-12
+        );
+        assert_approx_eq!(
+            rectangular.latitude.as_radians(),
+            spherical.latitude.as_radians(),
+            1e-12
+        );
+        assert_approx_eq!(rectangular.radius, spherical.radius, 1e-12);
+
+        // Ten years of general precession moves the longitudes forward by about 502.9"
+        let of_date = mars(&t, Frame::SphericalOfDate).to_spherical();
+        let precession = (of_date.longitude - spherical.longitude).as_arcseconds();
+        assert!((precession - 502.9).abs() < 1.0);
+        assert_approx_eq!(of_date.radius, spherical.radius, 1e-12);
+        let rectangular_of_date = mars(&t, Frame::RectangularOfDate).to_spherical();
+        assert_approx_eq!(
+            rectangular_of_date.longitude.as_radians(),
+            of_date.longitude.as_radians(),
+            1e-12
+        );
+
+        // At J2000.0 the two equinoxes are the same
+        let j2000 = JD::from(2_451_545.0);
+        assert_approx_eq!(
+            mars(&j2000, Frame::SphericalOfDate)
+                .to_spherical()
+                .longitude
+                .as_radians(),
+            Planet::Mars.get_location(&j2000).longitude.as_radians(),
+            1e-10
+        );
+
+        // The Sun is never more than about two solar radii from the barycenter
+        let offset = (mars(&t, Frame::RectangularJ2000).to_rectangular()
+            - mars(&t, Frame::BarycentricJ2000).to_rectangular())
+        .norm();
+        assert!(offset > 0.0 && offset < 0.01);
+    }
 }
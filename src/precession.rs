@@ -0,0 +1,104 @@
+//! Precession of the equinoxes.
+//!
+//! The slow wobble of Earth's axis moves the equator, and with it the equinox, along the
+//! ecliptic. Coordinates are always referred to the equator and equinox of some epoch, so they
+//! need to be precessed before being compared with coordinates referred to a different epoch.
+//! These implement the rigorous method described in chapter 21.
+use crate::angle::Angle;
+use crate::linalg::Mat3;
+use crate::time::{JulianCenturies, JD};
+
+/// The three angles describing the precession between two epochs.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PrecessionAngles {
+    pub zeta: Angle,
+    pub z: Angle,
+    pub theta: Angle,
+}
+
+impl PrecessionAngles {
+    /// Computes the angles ζ, z and θ for precessing from the mean equinox of `from` to the mean
+    /// equinox of `to`. Both epochs are Julian Ephemeris Days.
+    pub fn new(from: &JD, to: &JD) -> PrecessionAngles {
+        let big_t = JulianCenturies::from(*from).0;
+        let t = (*to - *from) / 36525.0;
+
+        let common = 2306.2181 + 1.39656 * big_t - 0.000_139 * big_t * big_t;
+        let zeta = common * t + (0.30188 - 0.000_344 * big_t) * t * t + 0.017_998 * t * t * t;
+        let z = common * t + (1.09468 + 0.000_066 * big_t) * t * t + 0.018_203 * t * t * t;
+        let theta = (2004.3109 - 0.85330 * big_t - 0.000_217 * big_t * big_t) * t
+            - (0.42665 + 0.000_217 * big_t) * t * t
+            - 0.041_833 * t * t * t;
+
+        PrecessionAngles {
+            zeta: Angle::from_arcseconds(zeta),
+            z: Angle::from_arcseconds(z),
+            theta: Angle::from_arcseconds(theta),
+        }
+    }
+
+    /// Builds the rotation matrix P(ζ, z, θ) that carries rectangular equatorial coordinates from
+    /// the starting equinox to the ending equinox.
+    pub fn to_matrix(&self) -> Mat3 {
+        Mat3::rotation_z(self.z) * Mat3::rotation_y(-self.theta) * Mat3::rotation_z(self.zeta)
+    }
+}
+
+/// Computes the rotation matrix that precesses rectangular equatorial coordinates from the mean
+/// equinox of `from` to the mean equinox of `to`.
+///
+/// Working with the matrix rather than the angle formulas makes it easy to combine precession
+/// with the other rotations of a reduction (nutation, frame bias, ...) into a single matrix, and
+/// to invert it by taking the transpose.
+pub fn matrix(from: &JD, to: &JD) -> Mat3 {
+    PrecessionAngles::new(from, to).to_matrix()
+}
+
+/// Computes the mean obliquity of the ecliptic ε0, the angle between the ecliptic and the mean
+/// equator of `t`, using formula 22.2.
+///
+/// The formula is valid for a few thousand years around J2000.0.
+pub fn mean_obliquity(t: &JD) -> Angle {
+    let big_t = JulianCenturies::from(*t).0;
+    let seconds =
+        21.448 - 46.8150 * big_t - 0.000_59 * big_t * big_t + 0.001_813 * big_t * big_t * big_t;
+    Angle::from_degrees(23.0 + 26.0 / 60.0 + seconds / 3600.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coords::{Equatorial, J2000};
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn precess_rectangular() {
+        // Example 21.b, page 135, θ Persei with its proper motion already applied
+        let start = Equatorial::<J2000>::new(
+            Angle::from_degrees(41.054_063),
+            Angle::from_degrees(49.227_750),
+        )
+        .unwrap();
+        let p = matrix(&JD::from(2_451_545.0), &JD::from(2_462_088.69));
+        let end = Equatorial::<J2000>::from_rectangular(&(p * start.to_rectangular())).unwrap();
+        assert_approx_eq!(end.ra().angle().as_degrees(), 41.547_214, 1e-5);
+        assert_approx_eq!(end.dec().angle().as_degrees(), 49.348_483, 1e-5);
+
+        // Going back should undo the rotation
+        let back =
+            Equatorial::<J2000>::from_rectangular(&(p.transpose() * end.to_rectangular())).unwrap();
+        assert_approx_eq!(back.ra().angle().as_degrees(), 41.054_063, 1e-9);
+        assert_approx_eq!(back.dec().angle().as_degrees(), 49.227_750, 1e-9);
+    }
+
+    #[test]
+    fn obliquity() {
+        // Example 22.a, page 148: 1987 April 10 at 0h TD, 23°26'27.407"
+        let epsilon = mean_obliquity(&JD::from(2_446_895.5));
+        assert_approx_eq!(
+            epsilon.as_degrees(),
+            23.0 + 26.0 / 60.0 + 27.407 / 3600.0,
+            1e-3 / 3600.0
+        );
+    }
+}
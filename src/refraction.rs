@@ -0,0 +1,151 @@
+//! Atmospheric refraction.
+//!
+//! The atmosphere bends the light of a body towards the zenith, so it appears higher above the
+//! horizon than it really is. The effect is over half a degree at the horizon, but only about a
+//! minute of arc at an altitude of 45°. These implement the formulas of chapter 16.
+//!
+//! The formulas diverge a few degrees below the horizon, so they are only applied down to
+//! `LOWEST_ALTITUDE`, below which a body can't be seen anyway.
+use crate::angle::Angle;
+
+/// The lowest altitude the refraction is computed for
+pub const LOWEST_ALTITUDE: Angle = Angle::from_degrees(-1.0);
+
+/// The corrections making formulas 16.3 and 16.4 give no refraction at the zenith, in minutes of
+/// arc
+const BENNETT_ZENITH_CORRECTION: f64 = 0.001_351_5;
+const SAEMUNDSSON_ZENITH_CORRECTION: f64 = 0.001_927_9;
+
+/// The conditions of the atmosphere at the observer, which scale the refraction.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Atmosphere {
+    /// The air pressure, in millibars
+    pub pressure: f64,
+    /// The air temperature, in degrees Celsius
+    pub temperature: f64,
+}
+
+impl Default for Atmosphere {
+    /// The standard conditions the formulas are given for, 1010 millibars and 10 °C
+    fn default() -> Atmosphere {
+        Atmosphere {
+            pressure: 1010.0,
+            temperature: 10.0,
+        }
+    }
+}
+
+impl Atmosphere {
+    pub fn new(pressure: f64, temperature: f64) -> Atmosphere {
+        Atmosphere {
+            pressure,
+            temperature,
+        }
+    }
+
+    /// Converts the true altitude of a body into the altitude it appears at, using Sæmundsson's
+    /// formula 16.4 with the correction making it zero at the zenith.
+    ///
+    /// Returns `None` if the altitude is below `LOWEST_ALTITUDE`, or NaN.
+    pub fn true_to_apparent(&self, altitude: Angle) -> Option<Angle> {
+        if altitude.as_radians().is_nan() || altitude < LOWEST_ALTITUDE {
+            return None;
+        }
+        let h = altitude.as_degrees();
+        let arcminutes =
+            1.02 / Angle::from_degrees(h + 10.3 / (h + 5.11)).tan() + SAEMUNDSSON_ZENITH_CORRECTION;
+        Some(altitude + Angle::from_degrees(self.scale(arcminutes) / 60.0))
+    }
+
+    /// Converts the altitude a body appears at into its true altitude, using Bennett's formula
+    /// 16.3 with the correction making it zero at the zenith.
+    ///
+    /// Returns `None` if the altitude is below `LOWEST_ALTITUDE`, or NaN.
+    pub fn apparent_to_true(&self, altitude: Angle) -> Option<Angle> {
+        if altitude.as_radians().is_nan() || altitude < LOWEST_ALTITUDE {
+            return None;
+        }
+        let h = altitude.as_degrees();
+        let arcminutes =
+            1.0 / Angle::from_degrees(h + 7.31 / (h + 4.4)).tan() + BENNETT_ZENITH_CORRECTION;
+        Some(altitude - Angle::from_degrees(self.scale(arcminutes) / 60.0))
+    }
+
+    /// Scales the refraction under the standard conditions to these conditions
+    fn scale(&self, refraction: f64) -> f64 {
+        refraction * self.pressure / 1010.0 * 283.0 / (273.0 + self.temperature)
+    }
+}
+
+/// Converts the true altitude of a body into the altitude it appears at, under the standard
+/// conditions of the atmosphere.
+///
+/// The result is consistent with `apparent_to_true` to about 4". Returns `None` if the altitude is
+/// below `LOWEST_ALTITUDE`, or NaN.
+pub fn true_to_apparent(altitude: Angle) -> Option<Angle> {
+    Atmosphere::default().true_to_apparent(altitude)
+}
+
+/// Converts the altitude a body appears at into its true altitude, under the standard conditions
+/// of the atmosphere.
+///
+/// The result is accurate to about 0.07' for any altitude above the horizon. Returns `None` if the
+/// altitude is below `LOWEST_ALTITUDE`, or NaN.
+pub fn apparent_to_true(altitude: Angle) -> Option<Angle> {
+    Atmosphere::default().apparent_to_true(altitude)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn refraction_at_the_horizon() {
+        // Example 16.a, page 107: the lower limb of the Sun appears at 0°30', with a refraction
+        // of 28.754' before the zenith correction
+        let apparent = Angle::from_degrees(0.5);
+        let refraction = apparent - apparent_to_true(apparent).unwrap();
+        assert_approx_eq!(
+            refraction.as_degrees() * 60.0,
+            28.754 + BENNETT_ZENITH_CORRECTION,
+            1e-3
+        );
+
+        let back = true_to_apparent(apparent_to_true(apparent).unwrap()).unwrap();
+        assert_approx_eq!(back.as_degrees(), apparent.as_degrees(), 4.0 / 3600.0);
+    }
+
+    #[test]
+    fn valid_altitudes() {
+        // There is no refraction at the zenith
+        let zenith = Angle::RIGHT_ANGLE;
+        assert_approx_eq!(true_to_apparent(zenith).unwrap().as_degrees(), 90.0, 1e-9);
+        assert_approx_eq!(apparent_to_true(zenith).unwrap().as_degrees(), 90.0, 1e-9);
+
+        // The formulas diverge near -5.1° and -4.4°, so they aren't used that low
+        assert!(true_to_apparent(LOWEST_ALTITUDE).is_some());
+        assert!(apparent_to_true(LOWEST_ALTITUDE).is_some());
+        assert_eq!(true_to_apparent(Angle::from_degrees(-5.11)), None);
+        assert_eq!(apparent_to_true(Angle::from_degrees(-4.4)), None);
+        assert_eq!(true_to_apparent(Angle::from_degrees(f64::NAN)), None);
+    }
+
+    #[test]
+    fn weather() {
+        let apparent = Angle::from_degrees(10.0);
+        let standard = (apparent - apparent_to_true(apparent).unwrap()).as_degrees();
+        assert_eq!(Atmosphere::default(), Atmosphere::new(1010.0, 10.0));
+
+        // Denser air bends the light more
+        let cold = Atmosphere::new(1030.0, -20.0);
+        let refraction = (apparent - cold.apparent_to_true(apparent).unwrap()).as_degrees();
+        assert_approx_eq!(
+            refraction,
+            standard * 1030.0 / 1010.0 * 283.0 / 253.0,
+            1e-12
+        );
+        assert!(cold.true_to_apparent(apparent) > true_to_apparent(apparent));
+        assert_eq!(cold.true_to_apparent(Angle::from_degrees(-3.0)), None);
+    }
+}
@@ -0,0 +1,187 @@
+//! The instants of the equinoxes and solstices.
+//!
+//! The seasons start when the apparent longitude of the Sun is a multiple of 90°. Chapter 27 gives
+//! polynomials for the mean instants of each season and a series of periodic terms correcting
+//! them, which together give the instants to within about a minute for the years 1951 to 2050,
+//! and a few minutes outside of that.
+use crate::angle::Angle;
+use crate::time::{JulianCenturies, JD, TT};
+
+/// The equinoxes and solstices, named after the month they happen in.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Season {
+    MarchEquinox,
+    JuneSolstice,
+    SeptemberEquinox,
+    DecemberSolstice,
+}
+
+/// The coefficients of the mean instants for the years -1000 to +1000, table 27.A
+#[rustfmt::skip]
+const MEAN_BEFORE_1000: [[f64; 5]; 4] = [
+    [1_721_139.291_89, 365_242.137_40, 0.061_34, 0.001_11, -0.000_71],
+    [1_721_233.254_01, 365_241.725_62, -0.053_23, 0.009_07, 0.000_25],
+    [1_721_325.704_55, 365_242.495_58, -0.116_77, -0.002_97, 0.000_74],
+    [1_721_414.399_87, 365_242.882_57, -0.007_69, -0.009_33, -0.000_06],
+];
+
+/// The coefficients of the mean instants for the years +1000 to +3000, table 27.B
+#[rustfmt::skip]
+const MEAN_AFTER_1000: [[f64; 5]; 4] = [
+    [2_451_623.809_84, 365_242.374_04, 0.051_69, -0.004_11, -0.000_57],
+    [2_451_716.567_67, 365_241.626_03, 0.003_25, 0.008_88, -0.000_30],
+    [2_451_810.217_15, 365_242.017_67, -0.115_75, 0.003_37, 0.000_78],
+    [2_451_900.059_52, 365_242.740_49, -0.062_23, -0.008_23, 0.000_32],
+];
+
+/// The periodic terms of table 27.C, as the amplitude A and the angle B + CT in degrees
+const PERIODIC_TERMS: [(f64, f64, f64); 24] = [
+    (485.0, 324.96, 1_934.136),
+    (203.0, 337.23, 32_964.467),
+    (199.0, 342.08, 20.186),
+    (182.0, 27.85, 445_267.112),
+    (156.0, 73.14, 45_036.886),
+    (136.0, 171.52, 22_518.443),
+    (77.0, 222.54, 65_928.934),
+    (74.0, 296.72, 3_034.906),
+    (70.0, 243.58, 9_037.513),
+    (58.0, 119.81, 33_718.147),
+    (52.0, 297.17, 150.678),
+    (50.0, 21.02, 2_281.226),
+    (45.0, 247.54, 29_929.562),
+    (44.0, 325.15, 31_555.956),
+    (29.0, 60.93, 4_443.417),
+    (18.0, 155.12, 67_555.328),
+    (17.0, 288.79, 4_562.452),
+    (16.0, 198.04, 62_894.029),
+    (14.0, 199.76, 31_436.921),
+    (12.0, 95.39, 14_577.848),
+    (12.0, 287.11, 31_931.756),
+    (12.0, 320.81, 34_777.259),
+    (9.0, 227.73, 1_222.114),
+    (8.0, 15.45, 16_859.074),
+];
+
+/// Computes the instant of the start of `season` in the given (astronomical) year, in dynamical
+/// time.
+///
+/// The polynomials are only valid for the years -1000 to +3000.
+pub fn season(year: i32, season: Season) -> JD {
+    let index = match season {
+        Season::MarchEquinox => 0,
+        Season::JuneSolstice => 1,
+        Season::SeptemberEquinox => 2,
+        Season::DecemberSolstice => 3,
+    };
+    let (coefficients, y) = if year < 1000 {
+        (&MEAN_BEFORE_1000[index], year as f64 / 1000.0)
+    } else {
+        (&MEAN_AFTER_1000[index], (year - 2000) as f64 / 1000.0)
+    };
+    let jde0: f64 = coefficients
+        .iter()
+        .rev()
+        .fold(0.0, |sum, coefficient| sum * y + coefficient);
+
+    let t = JulianCenturies::from(JD::<TT>::from(jde0)).0;
+    let w = Angle::from_degrees(35_999.373 * t - 2.47);
+    let delta_lambda = 1.0 + 0.0334 * w.cos() + 0.0007 * (w * 2.0).cos();
+    let s: f64 = PERIODIC_TERMS
+        .iter()
+        .map(|(a, b, c)| a * Angle::from_degrees(b + c * t).cos())
+        .sum();
+
+    JD::from(jde0) + 0.000_01 * s / delta_lambda
+}
+
+/// Computes the instant of the March equinox, when the Sun's apparent longitude is 0°
+pub fn march_equinox(year: i32) -> JD {
+    season(year, Season::MarchEquinox)
+}
+
+/// Computes the instant of the June solstice, when the Sun's apparent longitude is 90°
+pub fn june_solstice(year: i32) -> JD {
+    season(year, Season::JuneSolstice)
+}
+
+/// Computes the instant of the September equinox, when the Sun's apparent longitude is 180°
+pub fn september_equinox(year: i32) -> JD {
+    season(year, Season::SeptemberEquinox)
+}
+
+/// Computes the instant of the December solstice, when the Sun's apparent longitude is 270°
+pub fn december_solstice(year: i32) -> JD {
+    season(year, Season::DecemberSolstice)
+}
+
+/// Computes the length in days of the astronomical season starting with `season` in the given
+/// year, until the start of the next one. For example the season starting at the March equinox is
+/// the northern spring.
+///
+/// Because the orbit of the Earth is elliptic and its perihelion slowly moves, the lengths of the
+/// seasons differ by several days and change over the millennia, see table 27.E.
+pub fn length_of(season: Season, year: i32) -> f64 {
+    let next = match season {
+        Season::MarchEquinox => june_solstice(year),
+        Season::JuneSolstice => september_equinox(year),
+        Season::SeptemberEquinox => december_solstice(year),
+        Season::DecemberSolstice => march_equinox(year + 1),
+    };
+    next - self::season(year, season)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn june_solstice_1962() {
+        // Example 27.a, 1962 June 21 at 21h25m08s TD
+        assert_approx_eq!(june_solstice(1962).as_f64(), 2_437_837.392_45, 1e-5);
+    }
+
+    #[test]
+    fn seasons_of_2000() {
+        // The same instants as the event calendar finds, to within a minute
+        assert_approx_eq!(march_equinox(2000).as_f64(), 2_451_623.816_7, 7e-4);
+        assert_approx_eq!(june_solstice(2000).as_f64(), 2_451_716.575_7, 7e-4);
+        assert_approx_eq!(september_equinox(2000).as_f64(), 2_451_810.227_8, 7e-4);
+        assert_approx_eq!(december_solstice(2000).as_f64(), 2_451_900.068_1, 7e-4);
+    }
+
+    #[test]
+    fn season_lengths() {
+        // Table 27.E, for the year 2000
+        assert_approx_eq!(length_of(Season::MarchEquinox, 2000), 92.76, 0.01);
+        assert_approx_eq!(length_of(Season::JuneSolstice, 2000), 93.65, 0.01);
+        assert_approx_eq!(length_of(Season::SeptemberEquinox, 2000), 89.84, 0.01);
+        assert_approx_eq!(length_of(Season::DecemberSolstice, 2000), 88.99, 0.01);
+
+        // And for the year 2500, when the March equinox has moved closer to the perihelion
+        assert_approx_eq!(length_of(Season::MarchEquinox, 2500), 92.37, 0.01);
+        assert_approx_eq!(length_of(Season::JuneSolstice, 2500), 93.81, 0.01);
+        assert_approx_eq!(length_of(Season::SeptemberEquinox, 2500), 90.22, 0.01);
+        assert_approx_eq!(length_of(Season::DecemberSolstice, 2500), 88.84, 0.01);
+
+        // The seasons add up to a tropical year
+        let year: f64 = [
+            Season::MarchEquinox,
+            Season::JuneSolstice,
+            Season::SeptemberEquinox,
+            Season::DecemberSolstice,
+        ]
+        .iter()
+        .map(|season| length_of(*season, 2000))
+        .sum();
+        assert_approx_eq!(year, 365.242, 0.01);
+    }
+
+    #[test]
+    fn before_the_year_1000() {
+        // Both tables agree where they meet
+        let before = season(999, Season::MarchEquinox);
+        let after = season(1000, Season::MarchEquinox);
+        assert_approx_eq!(after - before, 365.242, 0.01);
+    }
+}
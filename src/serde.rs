@@ -0,0 +1,279 @@
+//! Serialization of the angle types with `serde`, enabled by the `serde` feature.
+//!
+//! An `Angle` serializes as a bare number of degrees, the unit configuration files and catalogs
+//! are usually written in. A field can be serialized in radians instead with the `radians`
+//! module:
+//!
+//! ```
+//! use astro_algos::angle::Angle;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Site {
+//!     latitude: Angle,
+//!     #[serde(with = "astro_algos::serde::radians")]
+//!     longitude: Angle,
+//! }
+//! ```
+//!
+//! `DegreesMinutesSeconds` and `HoursMinutesSeconds` serialize as structs of their components.
+//!
+//! The coordinate types serialize as structs of their components, with the equinox as a tag such
+//! as `"J2000"`. The mean and true equinoxes of date tag the Julian Ephemeris Day of their epoch,
+//! as in `{"MeanOfDate":2462088.5}`, so the two can't be mixed up.
+use crate::angle::Angle;
+use crate::coords::{EquinoxValue, MeanOfDate, TrueOfDate, B1950, ICRS, J2000};
+use crate::time::JD;
+
+use ::serde::de::{self, Unexpected};
+use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+impl Serialize for Angle {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        degrees::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Angle {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        degrees::deserialize(deserializer)
+    }
+}
+
+/// Serializes an `Angle` as a number of degrees, the default
+pub mod degrees {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(angle: &Angle, serializer: S) -> Result<S::Ok, S::Error> {
+        angle.as_degrees().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Angle, D::Error> {
+        f64::deserialize(deserializer).map(Angle::from_degrees)
+    }
+}
+
+/// Serializes an `Angle` as a number of radians
+pub mod radians {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(angle: &Angle, serializer: S) -> Result<S::Ok, S::Error> {
+        angle.as_radians().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Angle, D::Error> {
+        f64::deserialize(deserializer).map(Angle::from_radians)
+    }
+}
+
+/// The serialized form shared by all the equinoxes, so that any of them can be read back as an
+/// `EquinoxValue`. The standard equinoxes serialize as their name, and the equinoxes of date as
+/// their name tagging the Julian Ephemeris Day of their epoch.
+#[derive(Serialize, Deserialize)]
+#[allow(clippy::upper_case_acronyms)]
+enum EquinoxTag {
+    J2000,
+    B1950,
+    ICRS,
+    MeanOfDate(f64),
+    TrueOfDate(f64),
+}
+
+impl EquinoxTag {
+    fn name(&self) -> &'static str {
+        match self {
+            EquinoxTag::J2000 => "J2000",
+            EquinoxTag::B1950 => "B1950",
+            EquinoxTag::ICRS => "ICRS",
+            EquinoxTag::MeanOfDate(_) => "MeanOfDate",
+            EquinoxTag::TrueOfDate(_) => "TrueOfDate",
+        }
+    }
+}
+
+/// The error for a tag of another equinox than the one being read
+fn wrong_equinox<E: de::Error>(tag: &EquinoxTag, expected: &'static str) -> E {
+    E::invalid_value(Unexpected::Other(tag.name()), &expected)
+}
+
+macro_rules! equinox_tag {
+    ($name:ident, $equinox:ident => $to_tag:expr, $from_tag:pat => $value:expr) => {
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let $equinox = self;
+                $to_tag.serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                match EquinoxTag::deserialize(deserializer)? {
+                    $from_tag => Ok($value),
+                    tag => Err(wrong_equinox(&tag, stringify!($name))),
+                }
+            }
+        }
+    };
+}
+
+equinox_tag!(J2000, _equinox => EquinoxTag::J2000, EquinoxTag::J2000 => J2000 {});
+equinox_tag!(B1950, _equinox => EquinoxTag::B1950, EquinoxTag::B1950 => B1950 {});
+equinox_tag!(ICRS, _equinox => EquinoxTag::ICRS, EquinoxTag::ICRS => ICRS {});
+equinox_tag!(
+    MeanOfDate,
+    equinox => EquinoxTag::MeanOfDate(equinox.0.as_f64()),
+    EquinoxTag::MeanOfDate(epoch) => MeanOfDate(JD::from(epoch))
+);
+equinox_tag!(
+    TrueOfDate,
+    equinox => EquinoxTag::TrueOfDate(equinox.0.as_f64()),
+    EquinoxTag::TrueOfDate(epoch) => TrueOfDate(JD::from(epoch))
+);
+
+/// An `EquinoxValue` serializes the same way as the matching equinox type, and reads any of them
+impl Serialize for EquinoxValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            EquinoxValue::J2000 => EquinoxTag::J2000,
+            EquinoxValue::B1950 => EquinoxTag::B1950,
+            EquinoxValue::ICRS => EquinoxTag::ICRS,
+            EquinoxValue::MeanOfDate(t) => EquinoxTag::MeanOfDate(t.as_f64()),
+            EquinoxValue::TrueOfDate(t) => EquinoxTag::TrueOfDate(t.as_f64()),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for EquinoxValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match EquinoxTag::deserialize(deserializer)? {
+            EquinoxTag::J2000 => EquinoxValue::J2000,
+            EquinoxTag::B1950 => EquinoxValue::B1950,
+            EquinoxTag::ICRS => EquinoxValue::ICRS,
+            EquinoxTag::MeanOfDate(epoch) => EquinoxValue::MeanOfDate(JD::from(epoch)),
+            EquinoxTag::TrueOfDate(epoch) => EquinoxValue::TrueOfDate(JD::from(epoch)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::angle::{DegreesMinutesSeconds, Sign};
+    use crate::coords::{Ecliptical, Equatorial, HeliocentricSpherical};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Site {
+        latitude: Angle,
+        #[serde(with = "radians")]
+        longitude: Angle,
+    }
+
+    #[test]
+    fn angles() {
+        let site = Site {
+            latitude: Angle::from_degrees(45.5),
+            longitude: Angle::from_radians(-1.25),
+        };
+        let json = serde_json::to_string(&site).unwrap();
+        assert_eq!(json, r#"{"latitude":45.5,"longitude":-1.25}"#);
+        assert_eq!(serde_json::from_str::<Site>(&json).unwrap(), site);
+    }
+
+    #[test]
+    fn sexagesimal() {
+        let dms = DegreesMinutesSeconds {
+            sign: Sign::Negative,
+            degrees: 0,
+            minutes: 30,
+            seconds: 1.5,
+        };
+        let json = serde_json::to_string(&dms).unwrap();
+        assert_eq!(
+            json,
+            r#"{"sign":"Negative","degrees":0,"minutes":30,"seconds":1.5}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<DegreesMinutesSeconds>(&json).unwrap(),
+            dms
+        );
+    }
+
+    #[test]
+    fn coordinates() {
+        let star = Equatorial::<J2000>::new(Angle::from_degrees(41.5), Angle::from_degrees(-49.25))
+            .unwrap();
+        let json = serde_json::to_string(&star).unwrap();
+        assert_eq!(
+            json,
+            r#"{"right_ascension":41.5,"declination":-49.25,"equinox":"J2000"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Equatorial<J2000>>(&json).unwrap(),
+            star
+        );
+        assert!(serde_json::from_str::<Equatorial<B1950>>(&json).is_err());
+        let out_of_range = r#"{"right_ascension":41.5,"declination":95.0,"equinox":"J2000"}"#;
+        assert!(serde_json::from_str::<Equatorial<J2000>>(out_of_range).is_err());
+
+        let of_date =
+            Ecliptical::mean_of_date(Angle::ZERO, Angle::ZERO, &JD::from(2_462_088.5)).unwrap();
+        let json = serde_json::to_string(&of_date).unwrap();
+        assert_eq!(
+            json,
+            r#"{"longitude":0.0,"latitude":0.0,"equinox":{"MeanOfDate":2462088.5}}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Ecliptical<MeanOfDate>>(&json).unwrap(),
+            of_date
+        );
+
+        // The mean and true equinoxes of the same date are told apart
+        assert!(serde_json::from_str::<Ecliptical<TrueOfDate>>(&json).is_err());
+        let true_of_date = TrueOfDate(JD::from(2_462_088.5));
+        let tag = serde_json::to_string(&true_of_date).unwrap();
+        assert_eq!(tag, r#"{"TrueOfDate":2462088.5}"#);
+        assert_eq!(
+            serde_json::from_str::<TrueOfDate>(&tag).unwrap(),
+            true_of_date
+        );
+        assert!(serde_json::from_str::<MeanOfDate>(&tag).is_err());
+        assert!(serde_json::from_str::<MeanOfDate>("2462088.5").is_err());
+
+        // The equinox chosen at runtime reads both forms
+        let dynamic = serde_json::from_str::<Equatorial<EquinoxValue>>(
+            r#"{"right_ascension":41.5,"declination":-49.25,"equinox":"J2000"}"#,
+        )
+        .unwrap();
+        assert_eq!(dynamic.equinox(), EquinoxValue::J2000);
+        let dynamic = serde_json::from_str::<Ecliptical<EquinoxValue>>(&json).unwrap();
+        assert_eq!(
+            dynamic.equinox(),
+            EquinoxValue::MeanOfDate(JD::from(2_462_088.5))
+        );
+        assert_eq!(serde_json::to_string(&dynamic).unwrap(), json);
+        assert!(serde_json::from_str::<EquinoxValue>(r#""B1875""#).is_err());
+        let apparent = EquinoxValue::from(true_of_date);
+        assert_eq!(serde_json::to_string(&apparent).unwrap(), tag);
+        assert_eq!(
+            serde_json::from_str::<EquinoxValue>(&tag).unwrap(),
+            apparent
+        );
+        assert_eq!(
+            serde_json::from_str::<EquinoxValue>(r#""ICRS""#).unwrap(),
+            EquinoxValue::ICRS
+        );
+
+        let position = HeliocentricSpherical {
+            latitude: Angle::from_degrees(-2.5),
+            longitude: Angle::from_degrees(26.0),
+            radius: 0.75,
+        };
+        let json = serde_json::to_string(&position).unwrap();
+        assert_eq!(json, r#"{"latitude":-2.5,"longitude":26.0,"radius":0.75}"#);
+        assert_eq!(
+            serde_json::from_str::<HeliocentricSpherical>(&json).unwrap(),
+            position
+        );
+    }
+}
@@ -0,0 +1,281 @@
+//! Catalog positions of stars and their motion through space.
+//!
+//! Star catalogs give the position of a star at the catalog epoch, along with how fast it moves
+//! across the sky. Before the position can be precessed to another date it has to be moved along
+//! its proper motion to that date, as described in chapter 21.
+use crate::angle::Angle;
+use crate::coords::{Equatorial, J2000};
+use crate::linalg::Vec3;
+use crate::time::JD;
+
+/// The number of kilometers per second in one parsec per Julian year
+const KM_PER_S_IN_PARSEC_PER_YEAR: f64 = 977_792.0;
+
+/// The number of astronomical units in one parsec
+const AU_PER_PARSEC: f64 = 206_264.806;
+
+/// The number of days in a Julian year
+const DAYS_PER_YEAR: f64 = 365.25;
+
+/// The position and velocity of a body in rectangular coordinates.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StateVector {
+    /// The position, in astronomical units
+    pub position: Vec3,
+    /// The velocity, in astronomical units per day
+    pub velocity: Vec3,
+}
+
+/// A star from a catalog, referred to the equator and equinox of J2000.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Star {
+    /// The position at the catalog epoch
+    pub position: Equatorial<J2000>,
+    /// The epoch of the position, as a Julian Ephemeris Day
+    pub epoch: JD,
+    /// The yearly change of the right ascension. Many catalogs give this multiplied by the cosine
+    /// of the declination instead, which has to be divided out.
+    pub proper_motion_ra: Angle,
+    /// The yearly change of the declination
+    pub proper_motion_dec: Angle,
+    /// The annual parallax, zero if it isn't known
+    pub parallax: Angle,
+    /// The radial velocity in kilometers per second, positive when the star recedes
+    pub radial_velocity: f64,
+}
+
+impl Star {
+    /// Builds a star from its position and proper motion at the epoch J2000.0, with no known
+    /// parallax or radial velocity
+    pub fn new(
+        position: Equatorial<J2000>,
+        proper_motion_ra: Angle,
+        proper_motion_dec: Angle,
+    ) -> Star {
+        Star {
+            position,
+            epoch: JD::J2000,
+            proper_motion_ra,
+            proper_motion_dec,
+            parallax: Angle::ZERO,
+            radial_velocity: 0.0,
+        }
+    }
+
+    /// Moves the star along its proper motion to the Julian Ephemeris Day `t`, by simply adding the
+    /// yearly changes of the coordinates.
+    ///
+    /// This is accurate enough for a few centuries, except for stars close to the celestial poles.
+    pub fn at_epoch_linear(&self, t: &JD) -> Equatorial<J2000> {
+        let years = self.years_to(t);
+        Equatorial::from_angles(
+            self.position.ra().angle() + self.proper_motion_ra * years,
+            self.position.dec().angle() + self.proper_motion_dec * years,
+        )
+    }
+
+    /// Moves the star through space to the Julian Ephemeris Day `t`, using the rigorous method of
+    /// chapter 21.
+    ///
+    /// The motion is a straight line in rectangular coordinates, which stays correct close to the
+    /// poles and over long spans of time. When the parallax is known the radial velocity is
+    /// accounted for too, as it changes the apparent proper motion as the star's distance changes.
+    pub fn at_epoch(&self, t: &JD) -> Equatorial<J2000> {
+        let years = self.years_to(t);
+        let (distance, distance_rate) = if self.parallax > Angle::ZERO {
+            (
+                1.0 / self.parallax.as_arcseconds(),
+                self.radial_velocity / KM_PER_S_IN_PARSEC_PER_YEAR,
+            )
+        } else {
+            (1.0, 0.0)
+        };
+
+        let (sin_ra, cos_ra) = self.position.ra().angle().sin_cos();
+        let cos_dec = self.position.dec().angle().cos();
+        let r = self.position.to_rectangular() * distance;
+        let ra_rate = self.proper_motion_ra.as_radians();
+        let dec_rate = self.proper_motion_dec.as_radians();
+
+        let velocity = Vec3::new(
+            r.x / distance * distance_rate - r.z * cos_ra * dec_rate - r.y * ra_rate,
+            r.y / distance * distance_rate - r.z * sin_ra * dec_rate + r.x * ra_rate,
+            r.z / distance * distance_rate + distance * cos_dec * dec_rate,
+        );
+
+        Equatorial::from_vector(&(r + velocity * years))
+    }
+
+    /// Builds a star from its position and velocity relative to the solar system at `epoch`,
+    /// referred to the equator and equinox of J2000
+    pub fn from_state(state: &StateVector, epoch: JD) -> Star {
+        let StateVector { position, velocity } = *state;
+        let distance = position.norm();
+        let equatorial_squared = position.x * position.x + position.y * position.y;
+        let ra_rate = (position.x * velocity.y - position.y * velocity.x) / equatorial_squared;
+        let dec_rate = (velocity.z * equatorial_squared
+            - position.z * (position.x * velocity.x + position.y * velocity.y))
+            / (distance * distance * equatorial_squared.sqrt());
+        let radial = position.dot(&velocity) / distance;
+
+        Star {
+            position: Equatorial::from_vector(&position),
+            epoch,
+            proper_motion_ra: Angle::from_radians(ra_rate * DAYS_PER_YEAR),
+            proper_motion_dec: Angle::from_radians(dec_rate * DAYS_PER_YEAR),
+            parallax: Angle::from_arcseconds(AU_PER_PARSEC / distance),
+            radial_velocity: radial / AU_PER_PARSEC * DAYS_PER_YEAR * KM_PER_S_IN_PARSEC_PER_YEAR,
+        }
+    }
+
+    /// Computes the position and velocity of the star at the catalog epoch, relative to the solar
+    /// system and referred to the equator and equinox of J2000.
+    ///
+    /// Returns `None` when the parallax isn't known, since the distance is needed to place the
+    /// star in space.
+    pub fn state(&self) -> Option<StateVector> {
+        if self.parallax <= Angle::ZERO {
+            return None;
+        }
+
+        let distance = AU_PER_PARSEC / self.parallax.as_arcseconds();
+        let radial =
+            self.radial_velocity / KM_PER_S_IN_PARSEC_PER_YEAR * AU_PER_PARSEC / DAYS_PER_YEAR;
+        let ra_rate = self.proper_motion_ra.as_radians() / DAYS_PER_YEAR;
+        let dec_rate = self.proper_motion_dec.as_radians() / DAYS_PER_YEAR;
+
+        // The unit vectors towards the star, and towards increasing right ascension and declination
+        let (sin_ra, cos_ra) = self.position.ra().angle().sin_cos();
+        let (sin_dec, cos_dec) = self.position.dec().angle().sin_cos();
+        let towards = self.position.to_rectangular();
+        let east = Vec3::new(-sin_ra, cos_ra, 0.0);
+        let north = Vec3::new(-sin_dec * cos_ra, -sin_dec * sin_ra, cos_dec);
+
+        Some(StateVector {
+            position: towards * distance,
+            velocity: towards * radial
+                + east * (distance * cos_dec * ra_rate)
+                + north * (distance * dec_rate),
+        })
+    }
+
+    /// The number of Julian years from the catalog epoch to `t`
+    fn years_to(&self, t: &JD) -> f64 {
+        (*t - self.epoch) / DAYS_PER_YEAR
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::angle::{DegreesMinutesSeconds, HoursMinutesSeconds, Sign};
+    use assert_approx_eq::assert_approx_eq;
+
+    fn theta_persei() -> Star {
+        // Example 21.b, page 135
+        Star::new(
+            Equatorial::new(
+                HoursMinutesSeconds::new(Sign::Positive, 2, 44, 11.986)
+                    .unwrap()
+                    .as_angle(),
+                DegreesMinutesSeconds::new(Sign::Positive, 49, 13, 42.48)
+                    .unwrap()
+                    .as_angle(),
+            )
+            .unwrap(),
+            Angle::from_hours(0.034_25 / 3600.0),
+            Angle::from_arcseconds(-0.0895),
+        )
+    }
+
+    #[test]
+    fn linear_proper_motion() {
+        // Example 21.b: the position on 2028 November 13.19 TD before precessing, which Meeus
+        // rounds to a thousandth of a second of time
+        let moved = theta_persei().at_epoch_linear(&JD::from(2_462_088.69));
+        assert_approx_eq!(moved.ra().angle().as_degrees(), 41.054_063, 3e-6);
+        assert_approx_eq!(moved.dec().angle().as_degrees(), 49.227_750, 3e-6);
+    }
+
+    #[test]
+    fn space_motion() {
+        // Over a few decades the rigorous method agrees with the linear one
+        let t = JD::from(2_462_088.69);
+        let mut star = theta_persei();
+        let moved = star.at_epoch(&t);
+        assert_approx_eq!(moved.ra().angle().as_degrees(), 41.054_063, 3e-6);
+        assert_approx_eq!(moved.dec().angle().as_degrees(), 49.227_750, 3e-6);
+
+        // A known distance without any radial velocity doesn't change the direction
+        star.parallax = Angle::from_arcseconds(0.089);
+        let with_distance = star.at_epoch(&t);
+        assert_approx_eq!(
+            with_distance.ra().angle().as_degrees(),
+            moved.ra().angle().as_degrees(),
+            1e-12
+        );
+
+        // A star receding from us slows down across the sky, by a fraction that grows with the
+        // distance travelled relative to its own distance
+        star.radial_velocity = 25.0;
+        let receding = star.at_epoch(&t);
+        let linear = (moved.dec().angle() - star.position.dec().angle()).as_arcseconds();
+        let slowed = (receding.dec().angle() - star.position.dec().angle()).as_arcseconds();
+        let years = (t - star.epoch) / 365.25;
+        let fraction = 25.0 / KM_PER_S_IN_PARSEC_PER_YEAR * years * 0.089;
+        assert_approx_eq!(slowed / linear, 1.0 / (1.0 + fraction), 1e-6);
+    }
+
+    #[test]
+    fn state_vector() {
+        assert!(theta_persei().state().is_none());
+
+        let mut star = theta_persei();
+        star.parallax = Angle::from_arcseconds(0.089);
+        star.radial_velocity = 25.0;
+        let state = star.state().unwrap();
+        assert_approx_eq!(state.position.norm(), AU_PER_PARSEC / 0.089, 1e-6);
+
+        // Moving the state in a straight line is the rigorous space motion
+        let t = JD::from(2_462_088.69);
+        let moved = Equatorial::<J2000>::from_rectangular(
+            &(state.position + state.velocity * (t - star.epoch)),
+        )
+        .unwrap();
+        let expected = star.at_epoch(&t);
+        assert_approx_eq!(
+            moved.ra().angle().as_degrees(),
+            expected.ra().angle().as_degrees(),
+            1e-12
+        );
+        assert_approx_eq!(
+            moved.dec().angle().as_degrees(),
+            expected.dec().angle().as_degrees(),
+            1e-12
+        );
+
+        let back = Star::from_state(&state, star.epoch);
+        assert_approx_eq!(
+            back.position.ra().angle().as_degrees(),
+            star.position.ra().angle().as_degrees(),
+            1e-12
+        );
+        assert_approx_eq!(
+            back.position.dec().angle().as_degrees(),
+            star.position.dec().angle().as_degrees(),
+            1e-12
+        );
+        assert_approx_eq!(
+            back.proper_motion_ra.as_arcseconds(),
+            star.proper_motion_ra.as_arcseconds(),
+            1e-12
+        );
+        assert_approx_eq!(
+            back.proper_motion_dec.as_arcseconds(),
+            star.proper_motion_dec.as_arcseconds(),
+            1e-12
+        );
+        assert_approx_eq!(back.parallax.as_arcseconds(), 0.089, 1e-12);
+        assert_approx_eq!(back.radial_velocity, 25.0, 1e-9);
+    }
+}
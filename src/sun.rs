@@ -0,0 +1,449 @@
+//! The position of the Sun as seen from the Earth.
+//!
+//! The geocentric position of the Sun is simply the heliocentric position of the Earth seen the
+//! other way around, so it is computed from the same VSOP87 theory as the planets.
+//!
+//! This module also provides the quantities needed for physical observations of the Sun, described
+//! in chapter 29.
+use crate::angle::Angle;
+use crate::coords::{Ecliptical, EclipticalPosition, Equinox, MeanOfDate, TrueOfDate, J2000};
+use crate::frames;
+use crate::linalg::Vec3;
+use crate::nutation;
+use crate::planets::{self, Aberration, GeocentricPosition};
+use crate::precession;
+use crate::time::{JulianCenturies, JulianMillennia, JD};
+
+/// The semidiameter of the Sun at a distance of one astronomical unit, in arcseconds
+pub const SEMIDIAMETER_AT_1_AU: f64 = 959.63;
+
+/// Computes the position of the Sun as seen from the center of the Earth, for the J2000.0 equinox.
+///
+/// The `aberration` parameter has the same meaning as for `Planet::get_geocentric_position`. Both
+/// `Aberration::Stellar` and `Aberration::Planetary` give the apparent position of the Sun, about
+/// 20.5″ behind its geometric longitude.
+pub fn get_geocentric_position(t: &JD, aberration: Aberration) -> GeocentricPosition {
+    planets::geocentric_position(|_| Vec3::new(0.0, 0.0, 0.0), t, aberration)
+}
+
+/// Computes the apparent longitude of the Sun, referred to the ecliptic and mean equinox of date.
+///
+/// No correction for nutation is made, so this differs from the true apparent longitude by up to
+/// about 17″.
+pub fn apparent_longitude(t: &JD) -> Angle {
+    let position = get_geocentric_position(t, Aberration::Planetary);
+    longitude_of_date(&position.ecliptical, t)
+}
+
+/// Computes the apparent position of the Sun at `t`, referred to the ecliptic and true equinox of
+/// date, following the method of chapter 25.
+///
+/// The position is corrected for the aberration, converted to the FK5 system and nutated, and is
+/// what is published in almanacs. Converting it to equatorial coordinates uses the true obliquity.
+/// The distance is the geometric one at `t`.
+pub fn apparent_position(t: &JD) -> EclipticalPosition<TrueOfDate> {
+    let position = get_geocentric_position(t, Aberration::Stellar);
+    let direction = frames::vsop87_to_fk5(&position.ecliptical)
+        .to_equatorial()
+        .to_equinox(TrueOfDate(*t))
+        .to_ecliptical();
+
+    EclipticalPosition::new(
+        Ecliptical::from_angles_with_equinox(
+            direction.lon().angle().normalize_0_360(),
+            direction.lat().angle(),
+            direction.equinox(),
+        ),
+        position.distance,
+    )
+}
+
+/// Computes the geometric rectangular coordinates of the Sun at `t`, referred to the equator of
+/// `equinox` with the origin at the center of the Earth, following chapter 26.
+///
+/// The X axis points towards the equinox and the Z axis towards the north pole, in astronomical
+/// units. The position is converted to the FK5 system, but neither aberration nor nutation is
+/// applied, which is what the reduction of comets and minor planets from their orbital elements
+/// expects.
+pub fn geocentric_rectangular<E: Equinox>(t: &JD, equinox: E) -> Vec3 {
+    let position = get_geocentric_position(t, Aberration::None);
+    let direction = frames::vsop87_to_fk5(&position.ecliptical)
+        .to_equatorial()
+        .to_equinox(equinox);
+    direction.to_rectangular() * position.distance
+}
+
+/// Computes the equation of time at `t`, the difference between apparent and mean solar time,
+/// using formula 28.3.
+///
+/// The result is positive when the Sun transits before noon by the mean solar clock. An angle of
+/// 1° corresponds to 4 minutes of time. Nutation is left out of both the right ascension and the
+/// correction to the mean longitude, where it nearly cancels, so the result is good to a fraction
+/// of a second.
+pub fn equation_of_time(t: &JD) -> Angle {
+    let tau = JulianMillennia::from(*t).0;
+    let mean_longitude = 280.466_456_7
+        + 360_007.698_277_9 * tau
+        + 0.030_320_28 * tau.powi(2)
+        + tau.powi(3) / 49_931.0
+        - tau.powi(4) / 15_300.0
+        - tau.powi(5) / 2_000_000.0;
+
+    let position = get_geocentric_position(t, Aberration::Planetary);
+    let v = equatorial_of_date(&position.ecliptical, t);
+    let right_ascension = Angle::atan2(v.y, v.x);
+
+    Angle::from_degrees(mean_longitude - 0.005_718_3).difference(&right_ascension)
+}
+
+/// Computes the geometric position of the Sun at `t` with the low accuracy method of chapter 25,
+/// referred to the ecliptic and mean equinox of date.
+///
+/// This is a short series for the Earth's elliptical orbit instead of the full VSOP87 theory. The
+/// longitude is accurate to 0.01°, and the latitude, which never exceeds 1.2", is taken as zero.
+pub fn position_low_accuracy(t: &JD) -> EclipticalPosition<MeanOfDate> {
+    let big_t = JulianCenturies::from(*t).0;
+    let e = 0.016_708_634 - 0.000_042_037 * big_t - 0.000_000_126_7 * big_t * big_t;
+    let center = equation_of_center(t);
+    let true_anomaly = mean_anomaly(t) + center;
+    let radius = 1.000_001_018 * (1.0 - e * e) / (1.0 + e * true_anomaly.cos());
+
+    EclipticalPosition::new(
+        Ecliptical::from_angles_with_equinox(
+            mean_longitude(t) + center,
+            Angle::ZERO,
+            MeanOfDate(*t),
+        ),
+        radius,
+    )
+}
+
+/// Computes the geometric mean longitude of the Sun at `t`, referred to the mean equinox of date,
+/// using formula 25.2. The result is in the range [0°, 360°).
+pub fn mean_longitude(t: &JD) -> Angle {
+    let big_t = JulianCenturies::from(*t).0;
+    Angle::from_degrees(280.466_46 + 36_000.769_83 * big_t + 0.000_303_2 * big_t * big_t)
+        .normalize_0_360()
+}
+
+/// Computes the mean anomaly of the Sun at `t`, using formula 25.3. The result is in the range
+/// [0°, 360°).
+pub fn mean_anomaly(t: &JD) -> Angle {
+    let big_t = JulianCenturies::from(*t).0;
+    Angle::from_degrees(357.529_11 + 35_999.050_29 * big_t - 0.000_153_7 * big_t * big_t)
+        .normalize_0_360()
+}
+
+/// Computes the Sun's equation of the center at `t`, the difference between its true and mean
+/// anomalies. It never exceeds about 1.92°.
+pub fn equation_of_center(t: &JD) -> Angle {
+    let big_t = JulianCenturies::from(*t).0;
+    let m = mean_anomaly(t);
+    Angle::from_degrees(
+        (1.914_602 - 0.004_817 * big_t - 0.000_014 * big_t * big_t) * m.sin()
+            + (0.019_993 - 0.000_101 * big_t) * (m * 2.0).sin()
+            + 0.000_289 * (m * 3.0).sin(),
+    )
+}
+
+/// Precesses a J2000.0 ecliptical direction to the mean equator and equinox of `t`, as a
+/// rectangular vector
+fn equatorial_of_date(ecliptical: &Ecliptical<J2000>, t: &JD) -> Vec3 {
+    precession::matrix(&JD::J2000, t) * ecliptical.to_equatorial().to_rectangular()
+}
+
+/// Precesses a J2000.0 ecliptical direction to the ecliptic and mean equinox of `t`, keeping only
+/// the longitude
+fn longitude_of_date(ecliptical: &Ecliptical<J2000>, t: &JD) -> Angle {
+    let epsilon = precession::mean_obliquity(t);
+    let v = equatorial_of_date(ecliptical, t);
+    Angle::atan2(v.y * epsilon.cos() + v.z * epsilon.sin(), v.x).normalize_0_360()
+}
+
+/// The orientation of the solar disk as seen from the Earth, see chapter 29.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PhysicalEphemeris {
+    /// The position angle of the northern extremity of the Sun's axis of rotation, measured
+    /// eastwards from the north point of the disk
+    pub p: Angle,
+    /// The heliographic latitude of the center of the disk
+    pub b0: Angle,
+    /// The heliographic longitude of the center of the disk
+    pub l0: Angle,
+    /// The apparent semidiameter of the disk
+    pub semidiameter: Angle,
+}
+
+/// A position on the surface of the Sun, in the heliographic (Carrington) system.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Heliographic {
+    pub latitude: Angle,
+    pub longitude: Angle,
+}
+
+/// Computes the orientation of the solar disk at `t`, following chapter 29.
+pub fn physical_ephemeris(t: &JD) -> PhysicalEphemeris {
+    // The rotation of the Sun since the Carrington longitudes were defined
+    let theta = Angle::from_degrees(((*t - JD::from(2_398_220.0)) * 360.0 / 25.38) % 360.0);
+    let inclination = Angle::from_degrees(7.25);
+    let node = Angle::from_degrees(73.666_7 + 1.395_833_3 * (*t - JD::from(2_396_758.0)) / 36525.0);
+
+    let position = get_geocentric_position(t, Aberration::Planetary);
+    let lambda = longitude_of_date(&position.ecliptical, t);
+    let nutation = nutation::nutation(t);
+    let epsilon = precession::mean_obliquity(t) + nutation.delta_epsilon;
+
+    // The position angle is measured from the true pole of date
+    let from_node = lambda - node;
+    let x = Angle::atan(-(lambda + nutation.delta_psi).cos() * epsilon.tan());
+    let y = Angle::atan(-from_node.cos() * inclination.tan());
+    let eta = Angle::atan2(-from_node.sin() * inclination.cos(), -from_node.cos());
+
+    PhysicalEphemeris {
+        p: x + y,
+        b0: Angle::asin(from_node.sin() * inclination.sin()),
+        l0: (eta - theta).normalize_0_360(),
+        semidiameter: Angle::from_arcseconds(SEMIDIAMETER_AT_1_AU / position.distance),
+    }
+}
+
+/// Finds the heliographic coordinates of a feature seen on the solar disk at `t`.
+///
+/// The feature is located by its angular distance from the center of the disk and its position
+/// angle measured eastwards from the north point of the disk. Returns `None` if the distance is
+/// more than the semidiameter, which puts the feature off the disk.
+pub fn heliographic_position(
+    t: &JD,
+    distance: Angle,
+    position_angle: Angle,
+) -> Option<Heliographic> {
+    let ephemeris = physical_ephemeris(t);
+    let ratio = distance.as_radians() / ephemeris.semidiameter.as_radians();
+    if ratio.is_nan() || ratio > 1.0 {
+        return None;
+    }
+
+    // The angle at the center of the Sun between the feature and the center of the disk
+    let rho = Angle::from_radians(ratio.asin()) - distance;
+    let azimuth = ephemeris.p - position_angle;
+
+    let latitude = Angle::asin(
+        ephemeris.b0.sin() * rho.cos() + ephemeris.b0.cos() * rho.sin() * azimuth.cos(),
+    );
+    let longitude = ephemeris.l0
+        + Angle::atan2(
+            rho.sin() * azimuth.sin(),
+            rho.cos() * ephemeris.b0.cos() - rho.sin() * ephemeris.b0.sin() * azimuth.cos(),
+        );
+
+    Some(Heliographic {
+        latitude,
+        longitude: longitude.normalize_0_360(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::planets::Planet;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn opposite_of_the_earth() {
+        let t = JD::from(2_448_908.5);
+        let earth = Planet::Earth.get_location(&t);
+        let sun = get_geocentric_position(&t, Aberration::None);
+        assert_approx_eq!(
+            sun.ecliptical.lon().angle().as_degrees(),
+            earth.longitude.as_degrees() + 180.0,
+            1e-9
+        );
+        assert_approx_eq!(
+            sun.ecliptical.lat().angle().as_degrees(),
+            -earth.latitude.as_degrees(),
+            1e-12
+        );
+        assert_approx_eq!(sun.distance, earth.radius, 1e-12);
+
+        // The apparent Sun lags behind by the constant of aberration, scaled by the distance
+        let apparent = get_geocentric_position(&t, Aberration::Planetary);
+        let lag =
+            (sun.ecliptical.lon().angle() - apparent.ecliptical.lon().angle()).as_arcseconds();
+        assert_approx_eq!(lag, 20.4898 / sun.distance, 0.05);
+    }
+
+    #[test]
+    fn apparent_sun() {
+        // Example 25.b, page 169: 199°54'21.818", less the nutation in longitude of +15.908". The
+        // example also converts to the FK5 system, which isn't done here.
+        let lambda = apparent_longitude(&JD::from(2_448_908.5));
+        assert_approx_eq!(
+            lambda.as_degrees(),
+            199.0 + 54.0 / 60.0 + (21.818 - 15.908) / 3600.0,
+            0.2 / 3600.0
+        );
+    }
+
+    #[test]
+    fn orbital_quantities() {
+        // Example 25.a, page 165: 1992 October 13.0 TD
+        let t = JD::from(2_448_908.5);
+        assert_approx_eq!(mean_longitude(&t).as_degrees(), 201.807_20, 1e-5);
+        assert_approx_eq!(mean_anomaly(&t).as_degrees(), 278.993_97, 1e-5);
+        assert_approx_eq!(equation_of_center(&t).as_degrees(), -1.897_32, 1e-5);
+    }
+
+    #[test]
+    fn low_accuracy() {
+        // Example 25.a, page 165: 1992 October 13.0 TD
+        let t = JD::from(2_448_908.5);
+        let sun = position_low_accuracy(&t);
+        assert_approx_eq!(sun.direction.lon().angle().as_degrees(), 199.909_88, 1e-5);
+        assert_eq!(sun.direction.lat().angle(), Angle::ZERO);
+        assert_approx_eq!(sun.distance, 0.997_66, 1e-5);
+        assert_eq!(sun.direction.equinox(), MeanOfDate(t));
+
+        // Within 0.01° of the full theory
+        let precise = longitude_of_date(
+            &get_geocentric_position(&t, Aberration::None).ecliptical,
+            &t,
+        );
+        assert_approx_eq!(
+            sun.direction.lon().angle().as_degrees(),
+            precise.as_degrees(),
+            0.01
+        );
+    }
+
+    #[test]
+    fn apparent_position_1992() {
+        // Example 25.b, page 169: 1992 October 13.0 TD. The example uses a shortened VSOP87 and
+        // finds 199°54'21.818", but also gives 199°54'21.56", +0.721" and 0.99760853 AU from the
+        // complete theory.
+        let sun = apparent_position(&JD::from(2_448_908.5));
+        assert_approx_eq!(
+            sun.direction.lon().angle().as_degrees(),
+            199.0 + 54.0 / 60.0 + 21.56 / 3600.0,
+            0.02 / 3600.0
+        );
+        assert_approx_eq!(sun.direction.lat().angle().as_arcseconds(), 0.721, 0.005);
+        assert_approx_eq!(sun.distance, 0.997_608_53, 1e-8);
+
+        // 13h13m30.749s and -7°47'01.74" from the shortened theory, which is a quarter of an
+        // arcsecond ahead along the ecliptic
+        let equatorial = sun.to_equatorial().direction;
+        assert_approx_eq!(
+            equatorial.ra().angle().normalize_0_360().as_hours(),
+            13.0 + 13.0 / 60.0 + 30.749 / 3600.0,
+            0.02 / 3600.0
+        );
+        assert_approx_eq!(
+            equatorial.dec().angle().as_degrees(),
+            -(7.0 + 47.0 / 60.0 + 1.74 / 3600.0),
+            0.15 / 3600.0
+        );
+    }
+
+    #[test]
+    fn rectangular_coordinates() {
+        // Example 26.a, page 172: 1992 October 13.0 TD, referred to the mean equinox of date. The
+        // example uses the shortened VSOP87 of example 25.b, which is off by about 1e-6 AU.
+        let t = JD::from(2_448_908.5);
+        let v = geocentric_rectangular(&t, MeanOfDate(t));
+        assert_approx_eq!(v.x, -0.937_995_2, 2e-6);
+        assert_approx_eq!(v.y, -0.311_654_4, 2e-6);
+        assert_approx_eq!(v.z, -0.135_121_5, 2e-6);
+
+        // Referred to the equinox of J2000 the vector is the one of date precessed back
+        let j2000 = geocentric_rectangular(&t, J2000 {});
+        let precessed = precession::matrix(&t, &JD::J2000) * v;
+        assert_approx_eq!(j2000.x, precessed.x, 1e-12);
+        assert_approx_eq!(j2000.y, precessed.y, 1e-12);
+        assert_approx_eq!(j2000.z, precessed.z, 1e-12);
+        assert_approx_eq!(j2000.norm(), 0.997_608_53, 1e-8);
+    }
+
+    #[test]
+    fn equation_of_time_1992() {
+        // Example 28.a, 1992 October 13 at 0h TD: +13m42.6s
+        let e = equation_of_time(&JD::from(2_448_908.5));
+        assert_approx_eq!(e.as_degrees() * 240.0, 13.0 * 60.0 + 42.6, 0.5);
+
+        // In mid February the Sun transits about 14 minutes after mean noon
+        let e = equation_of_time(&JD::from(2_451_589.5));
+        assert_approx_eq!(e.as_degrees() * 4.0, -14.2, 0.1);
+    }
+
+    #[test]
+    fn disk_orientation() {
+        // Example 29.a, page 191: 1992 October 13.0 TD. The example works from a rounded value of
+        // the Sun's longitude, which puts its L0 slightly further off.
+        let ephemeris = physical_ephemeris(&JD::from(2_448_908.5));
+        assert_approx_eq!(ephemeris.p.as_degrees(), 26.27, 0.01);
+        assert_approx_eq!(ephemeris.b0.as_degrees(), 5.99, 0.01);
+        assert_approx_eq!(ephemeris.l0.as_degrees(), 238.63, 0.02);
+
+        // P is measured from the true pole of date. Nutation turns it 2.31″ from the angle found
+        // with the mean obliquity and longitude, well below the precision of the example.
+        let t = JD::from(2_448_908.5);
+        let position = get_geocentric_position(&t, Aberration::Planetary);
+        let lambda = longitude_of_date(&position.ecliptical, &t);
+        let mean_x = Angle::atan(-lambda.cos() * precession::mean_obliquity(&t).tan());
+        let from_node = lambda
+            - Angle::from_degrees(73.666_7 + 1.395_833_3 * (t - JD::from(2_396_758.0)) / 36525.0);
+        let y = Angle::atan(-from_node.cos() * Angle::from_degrees(7.25).tan());
+        assert_approx_eq!(
+            (ephemeris.p - (mean_x + y)).as_degrees() * 3600.0,
+            -2.31,
+            0.01
+        );
+    }
+
+    #[test]
+    fn features_on_the_disk() {
+        let t = JD::from(2_448_908.5);
+        let ephemeris = physical_ephemeris(&t);
+
+        // The center of the disk is at (B0, L0)
+        let center =
+            heliographic_position(&t, Angle::from_degrees(0.0), Angle::from_degrees(0.0)).unwrap();
+        assert_approx_eq!(
+            center.latitude.as_degrees(),
+            ephemeris.b0.as_degrees(),
+            1e-12
+        );
+        assert_approx_eq!(
+            center.longitude.as_degrees(),
+            ephemeris.l0.as_degrees(),
+            1e-12
+        );
+
+        // Half way to the limb, towards the north pole of the Sun, the feature is 30° north of the
+        // center less the angle it subtends as seen from the Earth
+        let half = Angle::from_radians(ephemeris.semidiameter.as_radians() / 2.0);
+        let north = heliographic_position(&t, half, ephemeris.p).unwrap();
+        assert_approx_eq!(
+            north.latitude.as_degrees(),
+            ephemeris.b0.as_degrees() + 30.0 - half.as_degrees(),
+            1e-9
+        );
+        assert_approx_eq!(
+            north.longitude.as_degrees(),
+            ephemeris.l0.as_degrees(),
+            1e-9
+        );
+
+        // Features towards the east limb haven't crossed the central meridian yet, so they have
+        // smaller longitudes
+        let east =
+            heliographic_position(&t, half, ephemeris.p + Angle::from_degrees(90.0)).unwrap();
+        assert!(east.longitude < ephemeris.l0);
+        let west =
+            heliographic_position(&t, half, ephemeris.p - Angle::from_degrees(90.0)).unwrap();
+        assert!(west.longitude > ephemeris.l0);
+
+        // Just past the limb there is no feature to find
+        let outside = Angle::from_radians(ephemeris.semidiameter.as_radians() * 1.001);
+        assert!(heliographic_position(&t, outside, ephemeris.p).is_none());
+    }
+}
@@ -0,0 +1,294 @@
+//! The design of planar sundials.
+//!
+//! Chapter 58 describes how to draw the hour lines and declination curves of a sundial on a plane
+//! of any orientation, with a straight stylus perpendicular to the plane whose tip casts the
+//! shadow. The same shadows can be cast by a polar stylus, parallel to the Earth's axis, from the
+//! tip of the straight stylus to the center of the sundial, where all the hour lines meet.
+//!
+//! Positions on the plane are measured from the foot of the straight stylus, in the same unit as
+//! its length. As seen by someone facing the plane, the x axis points to the right along the
+//! horizontal, and the y axis points up the line of greatest slope. On a horizontal plane there is
+//! no slope, so the y axis points the way the plane is turned to, north for the usual orientation.
+use crate::angle::Angle;
+use crate::linalg::Vec3;
+
+/// A point on the plane of a sundial.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DialPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// A planar sundial with a straight stylus perpendicular to the plane.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Sundial {
+    /// The latitude of the site
+    pub latitude: Angle,
+    /// The gnomonic declination, the azimuth of the perpendicular to the plane, measured from the
+    /// south towards the west
+    pub declination: Angle,
+    /// The zenith distance of the perpendicular to the plane, 0° for a horizontal sundial and 90°
+    /// for a vertical one
+    pub zenith_distance: Angle,
+    /// The length of the straight stylus
+    pub stylus_length: f64,
+}
+
+impl Sundial {
+    pub fn new(
+        latitude: Angle,
+        declination: Angle,
+        zenith_distance: Angle,
+        stylus_length: f64,
+    ) -> Sundial {
+        Sundial {
+            latitude,
+            declination,
+            zenith_distance,
+            stylus_length,
+        }
+    }
+
+    /// A sundial on a horizontal plane
+    pub fn horizontal(latitude: Angle, stylus_length: f64) -> Sundial {
+        Sundial::new(latitude, Angle::ZERO, Angle::ZERO, stylus_length)
+    }
+
+    /// A sundial on a vertical wall facing the direction given by `declination`, measured from the
+    /// south towards the west
+    pub fn vertical(latitude: Angle, declination: Angle, stylus_length: f64) -> Sundial {
+        Sundial::new(latitude, declination, Angle::RIGHT_ANGLE, stylus_length)
+    }
+
+    /// Finds the shadow of the tip of the stylus when the Sun is at the given hour angle
+    /// (negative in the morning) and declination.
+    ///
+    /// Returns `None` when the Sun is below the horizon or behind the plane, so that the plane is
+    /// not lit.
+    pub fn shadow(&self, hour_angle: Angle, sun_declination: Angle) -> Option<DialPoint> {
+        let sun = self.sun_direction(hour_angle, sun_declination);
+        let normal = self.normal();
+        let q = sun.dot(&normal);
+        if sun.z <= 0.0 || q <= 0.0 {
+            return None;
+        }
+
+        let (x_axis, y_axis) = self.axes();
+        Some(DialPoint {
+            x: -self.stylus_length * sun.dot(&x_axis) / q,
+            y: -self.stylus_length * sun.dot(&y_axis) / q,
+        })
+    }
+
+    /// Finds the center of the sundial, where the hour lines meet and the polar stylus touches the
+    /// plane.
+    ///
+    /// Returns `None` for a polar sundial, whose plane is parallel to the Earth's axis so that the
+    /// hour lines are parallel.
+    pub fn center(&self) -> Option<DialPoint> {
+        let p = self.pole().dot(&self.normal());
+        if p.abs() < 1e-12 {
+            return None;
+        }
+
+        let (x_axis, y_axis) = self.axes();
+        Some(DialPoint {
+            x: -self.stylus_length * self.pole().dot(&x_axis) / p,
+            y: -self.stylus_length * self.pole().dot(&y_axis) / p,
+        })
+    }
+
+    /// The length of the polar stylus, from the tip of the straight stylus to the center. This is
+    /// infinite for a polar sundial.
+    pub fn polar_stylus_length(&self) -> f64 {
+        self.stylus_length / self.pole().dot(&self.normal()).abs()
+    }
+
+    /// The angle between the polar stylus and the plane
+    pub fn polar_stylus_angle(&self) -> Angle {
+        Angle::asin(self.pole().dot(&self.normal()).abs())
+    }
+
+    /// Finds the angle at the center between the noon line and the hour line of the given hour
+    /// angle, both taken in the direction the shadow falls. The angle is measured clockwise as
+    /// seen facing the plane, from the y axis towards the x axis.
+    ///
+    /// Returns `None` for a polar sundial, which has no center.
+    pub fn hour_line_angle(&self, hour_angle: Angle) -> Option<Angle> {
+        let noon = self.hour_line_direction(Angle::ZERO)?;
+        let line = self.hour_line_direction(hour_angle)?;
+        Some(Angle::atan2(
+            line.x * noon.y - line.y * noon.x,
+            line.x * noon.x + line.y * noon.y,
+        ))
+    }
+
+    /// The direction from the center along the hour line of the given hour angle, towards where
+    /// the shadow falls when the plane is lit
+    fn hour_line_direction(&self, hour_angle: Angle) -> Option<DialPoint> {
+        let p = self.pole().dot(&self.normal());
+        if p.abs() < 1e-12 {
+            return None;
+        }
+
+        // The hour line is where the plane of the hour circle meets the plane of the sundial
+        let sun = self.sun_direction(hour_angle, Angle::ZERO);
+        let direction = (self.pole() * sun.dot(&self.normal()) - sun * p) * p.signum();
+        let (x_axis, y_axis) = self.axes();
+        Some(DialPoint {
+            x: direction.dot(&x_axis),
+            y: direction.dot(&y_axis),
+        })
+    }
+
+    // The vectors below are in the horizontal frame, with x towards the south, y towards the west
+    // and z towards the zenith.
+
+    /// The direction of the Sun
+    fn sun_direction(&self, hour_angle: Angle, declination: Angle) -> Vec3 {
+        let (sin_phi, cos_phi) = self.latitude.sin_cos();
+        let (sin_d, cos_d) = declination.sin_cos();
+        Vec3::new(
+            sin_phi * cos_d * hour_angle.cos() - cos_phi * sin_d,
+            cos_d * hour_angle.sin(),
+            cos_phi * cos_d * hour_angle.cos() + sin_phi * sin_d,
+        )
+    }
+
+    /// The direction of the north celestial pole
+    fn pole(&self) -> Vec3 {
+        Vec3::new(-self.latitude.cos(), 0.0, self.latitude.sin())
+    }
+
+    /// The perpendicular to the plane, on the side of the stylus
+    fn normal(&self) -> Vec3 {
+        let z = self.zenith_distance;
+        Vec3::new(
+            z.sin() * self.declination.cos(),
+            z.sin() * self.declination.sin(),
+            z.cos(),
+        )
+    }
+
+    /// The x and y axes of the plane
+    fn axes(&self) -> (Vec3, Vec3) {
+        let x_axis = Vec3::new(self.declination.sin(), -self.declination.cos(), 0.0);
+        (x_axis, x_axis.cross(&self.normal()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn horizontal_sundial() {
+        let latitude = Angle::from_degrees(40.0);
+        let dial = Sundial::horizontal(latitude, 1.0);
+
+        // The center is south of the stylus, and the polar stylus makes an angle equal to the
+        // latitude with the plane
+        let center = dial.center().unwrap();
+        assert_approx_eq!(center.x, 0.0, 1e-12);
+        assert_approx_eq!(center.y, -1.0 / latitude.tan(), 1e-12);
+        assert_approx_eq!(dial.polar_stylus_angle().as_degrees(), 40.0, 1e-9);
+        assert_approx_eq!(dial.polar_stylus_length(), 1.0 / latitude.sin(), 1e-12);
+
+        // The classical formula tan θ = sin φ tan H
+        for hours in 1..6 {
+            let h = Angle::from_hours(hours as f64);
+            let expected = (latitude.sin() * h.tan()).atan().to_degrees();
+            let angle = dial.hour_line_angle(h).unwrap();
+            assert_approx_eq!(angle.as_degrees(), expected, 1e-9);
+        }
+
+        // At noon on the equinoxes the shadow falls north of the stylus
+        let noon = dial
+            .shadow(Angle::from_degrees(0.0), Angle::from_degrees(0.0))
+            .unwrap();
+        assert_approx_eq!(noon.x, 0.0, 1e-12);
+        assert_approx_eq!(noon.y, latitude.tan(), 1e-12);
+
+        // There is no shadow at night
+        let midnight = dial.shadow(Angle::from_degrees(180.0), Angle::from_degrees(0.0));
+        assert_eq!(midnight, None);
+    }
+
+    #[test]
+    fn vertical_sundial() {
+        let latitude = Angle::from_degrees(40.0);
+        let dial = Sundial::vertical(latitude, Angle::from_degrees(0.0), 1.0);
+
+        // The hour lines of a wall facing south follow tan θ = cos φ tan H, turning the other way
+        // than on a horizontal sundial because the shadows fall downwards
+        for hours in 1..6 {
+            let h = Angle::from_hours(hours as f64);
+            let expected = (latitude.cos() * h.tan()).atan().to_degrees();
+            let angle = dial.hour_line_angle(h).unwrap();
+            assert_approx_eq!(angle.as_degrees(), -expected, 1e-9);
+        }
+        assert_approx_eq!(dial.polar_stylus_angle().as_degrees(), 50.0, 1e-9);
+
+        // The plane faces south, so it isn't lit when the Sun is north of the east-west line
+        let evening = dial.shadow(Angle::from_degrees(100.0), Angle::from_degrees(20.0));
+        assert_eq!(evening, None);
+    }
+
+    #[test]
+    fn example_58_a() {
+        // Example 58.a: a plane at latitude 40° north, with a gnomonic declination of 70° and a
+        // zenith distance of 50°, and a straight stylus of unit length
+        let dial = Sundial::new(
+            Angle::from_degrees(40.0),
+            Angle::from_degrees(70.0),
+            Angle::from_degrees(50.0),
+            1.0,
+        );
+        let center = dial.center().unwrap();
+        assert_approx_eq!(center.x, 3.3880, 1e-4);
+        assert_approx_eq!(center.y, -3.1101, 1e-4);
+        assert_approx_eq!(dial.polar_stylus_length(), 4.7065, 1e-4);
+        assert_approx_eq!(dial.polar_stylus_angle().as_degrees(), 12.2672, 1e-4);
+    }
+
+    #[test]
+    fn shadows_fall_on_the_hour_lines() {
+        let dial = Sundial::new(
+            Angle::from_degrees(40.0),
+            Angle::from_degrees(70.0),
+            Angle::from_degrees(50.0),
+            1.0,
+        );
+        let center = dial.center().unwrap();
+
+        for &hours in &[-1.0, 0.0, 1.0, 2.0, 3.0, 4.0] {
+            let h = Angle::from_hours(hours);
+            let noon = dial.hour_line_direction(Angle::from_degrees(0.0)).unwrap();
+            let line = dial.hour_line_angle(h).unwrap();
+            let noon = Angle::atan2(noon.x, noon.y);
+            for &declination in &[-23.44, -10.0, 0.0, 10.0, 23.44] {
+                if let Some(shadow) = dial.shadow(h, Angle::from_degrees(declination)) {
+                    let (dx, dy) = (shadow.x - center.x, shadow.y - center.y);
+                    let bearing = Angle::atan2(dx, dy) - noon;
+                    let difference = bearing.difference(&line);
+                    assert_approx_eq!(difference.as_degrees(), 0.0, 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn polar_sundial() {
+        // A plane facing south and tilted to be parallel to the Earth's axis
+        let dial = Sundial::new(
+            Angle::from_degrees(40.0),
+            Angle::from_degrees(0.0),
+            Angle::from_degrees(40.0),
+            1.0,
+        );
+        assert_eq!(dial.center(), None);
+        assert_eq!(dial.hour_line_angle(Angle::from_hours(1.0)), None);
+        assert!(dial.polar_stylus_angle().abs().as_degrees() < 1e-9);
+    }
+}
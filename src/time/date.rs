@@ -1,5 +1,5 @@
 //! Conversions to and from calendar dates
-use crate::time::JD;
+use crate::time::{TimeScale, JD};
 
 use core::fmt::{self, Display};
 
@@ -51,36 +51,283 @@ pub struct Date {
     fraction: f64,
 }
 
+/// The reasons a date can be invalid.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DateError {
+    /// The day doesn't exist in the given month
+    InvalidDay { month: Month, day: u8 },
+    /// The fraction of the day is not in the range [0, 1)
+    InvalidFraction(f64),
+    /// The time of day is not between 00:00:00 and 23:59:60
+    InvalidTime { hour: u8, minute: u8, second: f64 },
+}
+
+impl Display for DateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DateError::InvalidDay { month, day } => write!(f, "{:?} has no day {}", month, day),
+            DateError::InvalidFraction(fraction) => {
+                write!(f, "Invalid fraction of a day: {}", fraction)
+            }
+            DateError::InvalidTime {
+                hour,
+                minute,
+                second,
+            } => write!(f, "Invalid time of day: {}:{}:{}", hour, minute, second),
+        }
+    }
+}
+
+impl std::error::Error for DateError {}
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+const MICROSECONDS_PER_DAY: f64 = SECONDS_PER_DAY * 1e6;
+
+/// A time of day, as an alternative to the raw fraction of a day kept in a `Date`
+#[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
+pub struct TimeOfDay {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: f64,
+}
+
+impl TimeOfDay {
+    /// Builds a time of day, checking that each component is in range. At 23:59 the second may be
+    /// up to (but not including) 61 to allow for a leap second, every other minute ends at 60.
+    pub fn new(hour: u8, minute: u8, second: f64) -> Result<TimeOfDay, DateError> {
+        let last_second = if (hour, minute) == (23, 59) {
+            61.0
+        } else {
+            60.0
+        };
+        if hour > 23 || minute > 59 || !(0.0..last_second).contains(&second) {
+            return Err(DateError::InvalidTime {
+                hour,
+                minute,
+                second,
+            });
+        }
+
+        Ok(TimeOfDay {
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    /// Splits a fraction of a day, in the range [0, 1), into hours, minutes and seconds.
+    ///
+    /// The time is rounded to the nearest microsecond first, so that whole seconds don't come out
+    /// a hair short. A fraction a microsecond or less before midnight gives 23:59:59.999999.
+    pub fn from_fraction(fraction: f64) -> TimeOfDay {
+        let microseconds = ((fraction * MICROSECONDS_PER_DAY).round() as i64)
+            .clamp(0, MICROSECONDS_PER_DAY as i64 - 1);
+        let seconds = microseconds / 1_000_000;
+        TimeOfDay {
+            hour: (seconds / 3600) as u8,
+            minute: (seconds % 3600 / 60) as u8,
+            second: (seconds % 60) as f64 + (microseconds % 1_000_000) as f64 / 1e6,
+        }
+    }
+
+    /// The fraction of the day elapsed since midnight.
+    ///
+    /// A fraction of an ordinary day can't express a time within a leap second, so from 23:59:60
+    /// on this gives the last fraction before midnight.
+    pub fn to_fraction(&self) -> f64 {
+        let seconds = self.hour as f64 * 3600.0 + self.minute as f64 * 60.0 + self.second;
+        (seconds / SECONDS_PER_DAY).min(1.0 - f64::EPSILON)
+    }
+}
+
+impl From<TimeOfDay> for f64 {
+    fn from(item: TimeOfDay) -> Self {
+        item.to_fraction()
+    }
+}
+
+/// Checks whether a year is a leap year in the given calendar.
+///
+/// In the Julian calendar every fourth year is a leap year. The Gregorian calendar skips the
+/// centurial years, except those divisible by 400.
+pub fn is_leap_year(year: i32, calendar: Calendar) -> bool {
+    match calendar {
+        Calendar::Julian => year.rem_euclid(4) == 0,
+        Calendar::Gregorian => {
+            year.rem_euclid(4) == 0 && (year.rem_euclid(100) != 0 || year.rem_euclid(400) == 0)
+        }
+    }
+}
+
+/// Gets the number of days in a month of the given year and calendar
+pub fn days_in_month(year: i32, month: Month, calendar: Calendar) -> u8 {
+    match month {
+        Month::February if is_leap_year(year, calendar) => 29,
+        Month::February => 28,
+        Month::April | Month::June | Month::September | Month::November => 30,
+        _ => 31,
+    }
+}
+
 impl Date {
+    /// Builds a date, checking that the day exists in the month and that the fraction of the day
+    /// is in the range [0, 1).
+    ///
+    /// Both calendars are proleptic, i.e. they can be used for any year regardless of when they
+    /// were actually in use.
+    pub fn new(
+        cal: Calendar,
+        year: i32,
+        month: Month,
+        day: u8,
+        fraction: f64,
+    ) -> Result<Date, DateError> {
+        if day < 1 || day > days_in_month(year, month, cal) {
+            return Err(DateError::InvalidDay { month, day });
+        }
+        if !(0.0..1.0).contains(&fraction) {
+            return Err(DateError::InvalidFraction(fraction));
+        }
+
+        Ok(Date {
+            cal,
+            year: Year(year),
+            month,
+            day: DayOfMonth(day),
+            fraction,
+        })
+    }
+
+    /// Gets the current date in UTC from the system clock, with the fraction of the day set to
+    /// `0.0`
+    #[cfg(feature = "clock")]
+    pub fn today() -> Date {
+        let now = JD::<crate::time::UTC>::now();
+        Date::from(JD::<crate::time::UTC>::from_parts(now.midnight(), 0.0))
+    }
+
+    /// The calendar the date is expressed in
+    pub fn calendar(&self) -> Calendar {
+        self.cal
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year.0
+    }
+
+    pub fn month(&self) -> Month {
+        self.month
+    }
+
+    /// The day of the month, starting at 1
+    pub fn day(&self) -> u8 {
+        self.day.0
+    }
+
+    /// The fraction of the day elapsed since midnight
+    pub fn fraction(&self) -> f64 {
+        self.fraction
+    }
+
+    /// The time of day elapsed since midnight
+    pub fn time_of_day(&self) -> TimeOfDay {
+        TimeOfDay::from_fraction(self.fraction)
+    }
+
+    /// Replaces the fraction of the day with the given time of day
+    pub fn with_time(self, time: TimeOfDay) -> Date {
+        Date {
+            fraction: time.to_fraction(),
+            ..self
+        }
+    }
+
+    /// Formats the date following a template, in the style of `strftime`. The supported
+    /// specifiers are:
+    ///
+    /// - `%Y`: the year, which can be zero or negative
+    /// - `%m`, `%d`: the month and the day of the month as two digits
+    /// - `%B`, `%b`: the full and abbreviated name of the month
+    /// - `%A`, `%a`: the full and abbreviated name of the day of the week
+    /// - `%j`: the day of the year as three digits
+    /// - `%H`, `%M`, `%S`: the hour, minute and whole second of the time of day, as two digits
+    /// - `%f`: the fraction of the day, as a decimal point and four decimals, to follow `%d`. When
+    ///   it rounds up to a whole day, the date is formatted as the midnight that follows.
+    /// - `%C`: the name of the calendar
+    /// - `%%`: a literal `%`
+    ///
+    /// Any other character is copied as is.
+    pub fn format(&self, template: &str) -> String {
+        if template.replace("%%", "").contains("%f") && (self.fraction * 1e4).round() >= 1e4 {
+            let next = JD::from_parts(self.to_jd().midnight(), 1.0);
+            return Date::from_jd(next).format(template);
+        }
+
+        // The time of day is rounded to the microsecond, so whole seconds are exact and carried
+        let time = self.time_of_day();
+        let second = time.second.floor() as u8;
+
+        let mut output = String::new();
+        let mut chars = template.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                output.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('Y') => output += &self.year.0.to_string(),
+                Some('m') => output += &format!("{:02}", self.month as u8),
+                Some('d') => output += &format!("{:02}", self.day.0),
+                Some('B') => output += &format!("{:?}", self.month),
+                Some('b') => output += &format!("{:?}", self.month)[..3],
+                Some('A') => output += &format!("{:?}", self.get_day_of_week()),
+                Some('a') => output += &format!("{:?}", self.get_day_of_week())[..3],
+                Some('j') => output += &format!("{:03}", self.get_day_of_year()),
+                Some('H') => output += &format!("{:02}", time.hour),
+                Some('M') => output += &format!("{:02}", time.minute),
+                Some('S') => output += &format!("{:02}", second),
+                Some('f') => output += &format!("{:.4}", self.fraction)[1..],
+                Some('C') => output += &format!("{:?}", self.cal),
+                Some('%') => output.push('%'),
+                Some(other) => {
+                    output.push('%');
+                    output.push(other);
+                }
+                None => output.push('%'),
+            }
+        }
+        output
+    }
+
     pub fn to_jd(&self) -> super::JD {
         let (y, m) = match self.month {
             Month::January | Month::February => (self.year.0 as f64 - 1.0, self.month as i32 + 12),
             _ => (self.year.0 as f64, self.month as i32),
         };
 
-        let a = (y as f64 / 100.0).floor();
+        let a = (y / 100.0).floor();
         let b = match self.cal {
             Calendar::Julian => 0.0,
             Calendar::Gregorian => 2.0 - a + (a / 4.0).floor(),
         };
 
-        JD::from(
+        JD::from_parts(
             (365.25f64 * (y + 4716.0)).floor()
                 + (30.6001f64 * (m as f64 + 1.0)).floor()
                 + (self.day.0 as f64)
-                + self.fraction
                 + b
                 - 1524.5,
+            self.fraction,
         )
     }
 
-    pub fn from_jd(jd: super::JD) -> Date {
-        let z = (jd.as_f64() + 0.5).floor();
-        let f = (jd.as_f64() + 0.5) - z;
-        let a = if z < 2299_161.0 {
+    pub fn from_jd(jd: JD) -> Date {
+        let z = jd.midnight() + 0.5;
+        let f = jd.fraction();
+        let a = if z < 2_299_161.0 {
             z
         } else {
-            let alpha = ((z - 1867_216.25) / 36524.25).floor();
+            let alpha = ((z - 1_867_216.25) / 36524.25).floor();
             z + 1.0 + alpha - (alpha / 4.0).floor()
         };
 
@@ -112,21 +359,39 @@ impl Date {
         }
     }
 
+    /// The number of days from this date to `other`, negative if `other` is earlier. Each date
+    /// is taken in its own calendar, so the interval can span the Julian/Gregorian reform.
+    pub fn days_until(&self, other: &Date) -> f64 {
+        other.to_jd() - self.to_jd()
+    }
+
     pub fn get_day_of_week(&self) -> DayOfWeek {
-        let jd = self.to_jd().as_f64().round() - 0.5; // Rounded to the nearest day at 0h UTC
-        let day_num = ((jd + 1.5) % 7.0).round() as i32;
+        let jd = self.to_jd().midnight();
+        let day_num = (jd + 1.5).rem_euclid(7.0) as i32;
         DayOfWeek::from(day_num)
     }
 
+    /// Gets the ISO 8601 week of the date, as the ISO year and the week number.
+    ///
+    /// ISO weeks start on Monday, and the first week of a year is the one containing its first
+    /// Thursday. The ISO year can therefore differ from the calendar year for the first and last
+    /// few days of a year.
+    pub fn get_iso_week(&self) -> (i32, u8) {
+        // Every day belongs to the same ISO week and year as the Thursday of its week
+        let days_from_monday = (self.get_day_of_week() as i32 + 6) % 7;
+        let midnight = self.to_jd().midnight();
+        let thursday = Date::from_jd(JD::from(midnight + (3 - days_from_monday) as f64));
+        let week = (thursday.get_day_of_year() - 1) / 7 + 1;
+        (thursday.year.0, week as u8)
+    }
+
     pub fn get_day_of_year(&self) -> u16 {
-        let leap_year = if let Calendar::Julian = self.cal {
-            (self.year.0 % 4) == 0
+        let k = if is_leap_year(self.year.0, self.cal) {
+            1
         } else {
-            ((self.year.0 % 4) == 0) && !((self.year.0 % 400) == 0)
+            2
         };
 
-        let k = if leap_year { 1 } else { 2 };
-
         let m = self.month as u16;
         let d = self.day.0 as u16;
 
@@ -134,9 +399,18 @@ impl Date {
     }
 }
 
+/// Formats the date as for example "October 4, 1957", followed by the time of day to the second
+/// if the date has a fraction of a day. The alternate form `{:#}` also names the calendar.
 impl Display for Date {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?} {}, {}", self.month, self.day.0, self.year.0)
+        write!(f, "{:?} {}, {}", self.month, self.day.0, self.year.0)?;
+        if self.fraction != 0.0 {
+            write!(f, " {}", self.format("%H:%M:%S"))?;
+        }
+        if f.alternate() {
+            write!(f, " ({:?})", self.cal)?;
+        }
+        Ok(())
     }
 }
 
@@ -155,10 +429,7 @@ impl From<i32> for Month {
             10 => Month::October,
             11 => Month::November,
             12 => Month::December,
-            _ => {
-                assert!(false, "Invalid month number: {}", item);
-                Month::January
-            }
+            _ => panic!("Invalid month number: {}", item),
         }
     }
 }
@@ -173,10 +444,7 @@ impl From<i32> for DayOfWeek {
             4 => DayOfWeek::Thursday,
             5 => DayOfWeek::Friday,
             6 => DayOfWeek::Saturday,
-            _ => {
-                assert!(false, "Invalid day number: {}", item);
-                DayOfWeek::Sunday
-            }
+            _ => panic!("Invalid day number: {}", item),
         }
     }
 }
@@ -193,9 +461,50 @@ impl From<Date> for JD {
     }
 }
 
-impl From<JD> for Date {
-    fn from(item: JD) -> Self {
-        Date::from_jd(item)
+/// The number of days between two dates
+impl std::ops::Sub for Date {
+    type Output = f64;
+
+    fn sub(self, other: Date) -> f64 {
+        other.days_until(&self)
+    }
+}
+
+/// Converts a `JD` in any time scale into a date in that same time scale
+impl<S: TimeScale> From<JD<S>> for Date {
+    fn from(item: JD<S>) -> Self {
+        Date::from_jd(item.relabel())
+    }
+}
+
+/// Finds the `n`th occurrence of a day of the week in a month of the Gregorian calendar, counting
+/// from 1. For example the fourth Thursday of November.
+///
+/// Returns `None` if `n` is zero or the month doesn't have that many of the day.
+pub fn nth_weekday_of_month(year: Year, month: Month, weekday: DayOfWeek, n: u8) -> Option<Date> {
+    let first = Date::new(Calendar::Gregorian, year.0, month, 1, 0.0).ok()?;
+    let offset = (weekday as i32 - first.get_day_of_week() as i32).rem_euclid(7) as u8;
+    if n == 0 || n > 5 {
+        return None;
+    }
+    let day = (n - 1) * 7 + offset + 1;
+    Date::new(Calendar::Gregorian, year.0, month, day, 0.0).ok()
+}
+
+/// Finds the last occurrence of a day of the week in a month of the Gregorian calendar
+pub fn last_weekday_of_month(year: Year, month: Month, weekday: DayOfWeek) -> Date {
+    let last = days_in_month(year.0, month, Calendar::Gregorian);
+    let last_date = Date {
+        cal: Calendar::Gregorian,
+        year,
+        month,
+        day: DayOfMonth(last),
+        fraction: 0.0,
+    };
+    let offset = (last_date.get_day_of_week() as i32 - weekday as i32).rem_euclid(7) as u8;
+    Date {
+        day: DayOfMonth(last - offset),
+        ..last_date
     }
 }
 
@@ -247,6 +556,15 @@ pub fn find_gregorian_easter(year: Year) -> Date {
     }
 }
 
+/// Calculates the date of Orthodox Easter for a given year, which is reckoned in the Julian
+/// calendar, expressed as a date in the Gregorian calendar.
+///
+/// Before the Gregorian calendar was introduced in 1582 the date is left in the Julian calendar,
+/// like `find_easter_by_year` does.
+pub fn find_orthodox_easter(year: Year) -> Date {
+    Date::from_jd(find_julian_easter(year).to_jd())
+}
+
 /// Calculates the date of Easter for a given year in the Julian calendar
 pub fn find_julian_easter(year: Year) -> Date {
     let a = year.0 % 4;
@@ -307,6 +625,7 @@ pub fn find_gregorian_passover(greg_year: Year) -> Date {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use assert_approx_eq::assert_approx_eq;
 
     fn fraction_eq(frac1: f64, frac2: f64) -> bool {
         // 1 microsecond expressed in terms of fractions of a day, should be good enough for us
@@ -326,7 +645,7 @@ mod tests {
                 fraction: 0.81,
             }
             .to_jd(),
-            2436116.31.into()
+            JD::from_parts(2_436_115.5, 0.81)
         );
 
         // Example 7.b, page 61
@@ -339,7 +658,7 @@ mod tests {
                 fraction: 0.5,
             }
             .to_jd(),
-            1842713.0.into()
+            JD::from_parts(1_842_712.5, 0.5)
         );
 
         // Unlabeled table, page 62
@@ -378,7 +697,7 @@ mod tests {
                 fraction: 0.9,
             }
             .to_jd(),
-            1355671.4.into()
+            JD::from_parts(1_355_670.5, 0.9)
         );
 
         // Unlabeled table, page 62
@@ -398,7 +717,7 @@ mod tests {
     #[test]
     fn jd_to_date() {
         // Example 7.c, page 64
-        let d1 = Date::from_jd(JD::from(2436_116.31));
+        let d1 = Date::from_jd(JD::from(2_436_116.31));
         assert_eq!(d1.cal, Calendar::Gregorian);
         assert_eq!(d1.year, Year(1957));
         assert_eq!(d1.month, Month::October);
@@ -406,7 +725,7 @@ mod tests {
         assert!(fraction_eq(d1.fraction, 0.81));
 
         // Exercise, page 64
-        let d1 = Date::from_jd(JD::from(1842_713.0));
+        let d1 = Date::from_jd(JD::from(1_842_713.0));
         assert_eq!(d1.cal, Calendar::Julian);
         assert_eq!(d1.year, Year(333));
         assert_eq!(d1.month, Month::January);
@@ -414,12 +733,25 @@ mod tests {
         assert!(fraction_eq(d1.fraction, 0.5));
 
         // Exercise, page 64
-        let d1 = Date::from_jd(JD::from(1507_900.13));
+        let d1 = Date::from_jd(JD::from(1_507_900.13));
         assert_eq!(d1.cal, Calendar::Julian);
         assert_eq!(d1.year, Year(-584));
         assert_eq!(d1.month, Month::May);
         assert_eq!(d1.day, DayOfMonth(28));
         assert!(fraction_eq(d1.fraction, 0.63));
+
+        // Negative Julian Days continue the proleptic Julian calendar
+        let d1 = Date::from_jd(JD::from(-365.0));
+        assert_eq!(d1.cal, Calendar::Julian);
+        assert_eq!(d1.year, Year(-4713));
+        assert_eq!(d1.month, Month::January);
+        assert_eq!(d1.day, DayOfMonth(1));
+        assert!(fraction_eq(d1.fraction, 0.5));
+        assert_eq!(d1.to_jd(), JD::from(-365.0));
+
+        let d1 = Date::from_jd(JD::from(-1_000_000.3));
+        assert_eq!(d1.year, Year(-7450));
+        assert_eq!(d1.to_jd(), JD::from(-1_000_000.3));
     }
 
     #[test]
@@ -492,6 +824,20 @@ mod tests {
             .get_day_of_week(),
             DayOfWeek::Saturday
         );
+
+        // JD 0 was a Monday, and the days keep cycling before it
+        assert_eq!(
+            Date::from_jd(JD::from(0.0)).get_day_of_week(),
+            DayOfWeek::Monday
+        );
+        assert_eq!(
+            Date::from_jd(JD::from(-1.0)).get_day_of_week(),
+            DayOfWeek::Sunday
+        );
+        assert_eq!(
+            Date::from_jd(JD::from(-7.0)).get_day_of_week(),
+            DayOfWeek::Monday
+        );
     }
 
     #[test]
@@ -521,6 +867,205 @@ mod tests {
             .get_day_of_year(),
             113
         );
+
+        // 2000 is a leap year in the Gregorian calendar, 1900 isn't
+        let march_1 = |year| Date::new(Calendar::Gregorian, year, Month::March, 1, 0.0).unwrap();
+        assert_eq!(march_1(2000).get_day_of_year(), 61);
+        assert_eq!(march_1(1900).get_day_of_year(), 60);
+    }
+
+    #[test]
+    fn accessors() {
+        // Example 7.c
+        let date = Date::from_jd(JD::from(2_436_116.31));
+        assert_eq!(date.calendar(), Calendar::Gregorian);
+        assert_eq!(date.year(), 1957);
+        assert_eq!(date.month(), Month::October);
+        assert_eq!(date.day(), 4);
+        assert_approx_eq!(date.fraction(), 0.81, 1e-9);
+
+        let date = Date::from_jd(JD::from(1_842_713.0));
+        assert_eq!(date.calendar(), Calendar::Julian);
+        assert_eq!(
+            (date.year(), date.month(), date.day()),
+            (333, Month::January, 27)
+        );
+        assert_approx_eq!(date.fraction(), 0.5, 1e-9);
+    }
+
+    #[test]
+    fn time_of_day() {
+        // Example 7.c, 1957 October 4.81 is at 19:26:24
+        let date = Date::from_jd(JD::from(2_436_116.31));
+        let time = date.time_of_day();
+        assert_eq!((time.hour, time.minute), (19, 26));
+        assert_approx_eq!(time.second, 24.0, 1e-4);
+
+        let midnight = Date::new(Calendar::Gregorian, 1957, Month::October, 4, 0.0).unwrap();
+        let time = TimeOfDay::new(19, 26, 24.0).unwrap();
+        let date = midnight.with_time(time);
+        assert_approx_eq!(date.fraction(), 0.81, 1e-12);
+        assert_approx_eq!(date.to_jd().as_f64(), 2_436_116.31, 1e-9);
+        assert_approx_eq!(f64::from(TimeOfDay::new(12, 0, 0.0).unwrap()), 0.5, 1e-12);
+
+        assert!(TimeOfDay::new(24, 0, 0.0).is_err());
+        assert!(TimeOfDay::new(12, 60, 0.0).is_err());
+        assert!(TimeOfDay::new(23, 59, 60.5).is_ok());
+        assert!(TimeOfDay::new(23, 59, 61.0).is_err());
+        assert!(TimeOfDay::new(12, 0, 60.0).is_err());
+        assert!(TimeOfDay::new(23, 58, 60.5).is_err());
+        assert!(TimeOfDay::new(12, 0, f64::NAN).is_err());
+        assert!(TimeOfDay::new(12, 0, -1.0).is_err());
+        assert!(TimeOfDay::new(23, 59, 60.5).unwrap().to_fraction() < 1.0);
+
+        // Every whole second of the day splits back into itself
+        for seconds in 0..86_400 {
+            let time = TimeOfDay::from_fraction(seconds as f64 / SECONDS_PER_DAY);
+            let expected = (
+                (seconds / 3600) as u8,
+                (seconds % 3600 / 60) as u8,
+                (seconds % 60) as f64,
+            );
+            assert_eq!((time.hour, time.minute, time.second), expected);
+        }
+        let time = TimeOfDay::from_fraction(1.0 - 1e-12);
+        assert_eq!((time.hour, time.minute), (23, 59));
+        assert_approx_eq!(time.second, 59.999_999, 1e-9);
+    }
+
+    #[test]
+    fn intervals() {
+        // Example 7.d, between the perihelion passages of Halley's comet
+        let d1 = Date::new(Calendar::Gregorian, 1910, Month::April, 20, 0.0).unwrap();
+        let d2 = Date::new(Calendar::Gregorian, 1986, Month::February, 9, 0.0).unwrap();
+        assert_eq!(d1.days_until(&d2), 27_689.0);
+        assert_eq!(d2.days_until(&d1), -27_689.0);
+        assert_eq!(d2 - d1, 27_689.0);
+
+        // Across the calendar reform, 1582 October 4 (Julian) was followed by October 15
+        let d1 = Date::new(Calendar::Julian, 1582, Month::October, 4, 0.0).unwrap();
+        let d2 = Date::new(Calendar::Gregorian, 1582, Month::October, 15, 0.0).unwrap();
+        assert_eq!(d1.days_until(&d2), 1.0);
+
+        // Fractions of a day are kept
+        let d2 = Date::new(Calendar::Julian, 1582, Month::October, 5, 0.25).unwrap();
+        assert_approx_eq!(d2 - d1, 1.25, 1e-9);
+    }
+
+    #[test]
+    fn iso_weeks() {
+        let week = |year, month, day| {
+            Date::new(Calendar::Gregorian, year, month, day, 0.0)
+                .unwrap()
+                .get_iso_week()
+        };
+        assert_eq!(week(1978, Month::November, 14), (1978, 46));
+        assert_eq!(week(2005, Month::January, 1), (2004, 53));
+        assert_eq!(week(2008, Month::December, 29), (2009, 1));
+        assert_eq!(week(2010, Month::January, 3), (2009, 53));
+        assert_eq!(week(2020, Month::December, 31), (2020, 53));
+    }
+
+    #[test]
+    fn weekdays_of_month() {
+        let date = |month, day| Date::new(Calendar::Gregorian, 2019, month, day, 0.0).unwrap();
+
+        let thanksgiving =
+            nth_weekday_of_month(Year(2019), Month::November, DayOfWeek::Thursday, 4);
+        assert_eq!(thanksgiving, Some(date(Month::November, 28)));
+        let first = nth_weekday_of_month(Year(2019), Month::November, DayOfWeek::Friday, 1);
+        assert_eq!(first, Some(date(Month::November, 1)));
+        assert_eq!(
+            nth_weekday_of_month(Year(2019), Month::February, DayOfWeek::Friday, 5),
+            None
+        );
+        assert_eq!(
+            nth_weekday_of_month(Year(2019), Month::February, DayOfWeek::Friday, 0),
+            None
+        );
+
+        assert_eq!(
+            last_weekday_of_month(Year(2019), Month::May, DayOfWeek::Monday),
+            date(Month::May, 27)
+        );
+        assert_eq!(
+            last_weekday_of_month(Year(2019), Month::August, DayOfWeek::Saturday),
+            date(Month::August, 31)
+        );
+    }
+
+    #[cfg(feature = "clock")]
+    #[test]
+    fn today() {
+        let today = Date::today();
+        assert_eq!(today.fraction(), 0.0);
+        assert_eq!(today.calendar(), Calendar::Gregorian);
+        assert!(today.year() >= 2020);
+    }
+
+    #[test]
+    fn formatting() {
+        // Example 7.c, 1957 October 4.81
+        let date = Date::from_jd(JD::from(2_436_116.31));
+        assert_eq!(date.to_string(), "October 4, 1957 19:26:24");
+        assert_eq!(
+            format!("{:#}", date),
+            "October 4, 1957 19:26:24 (Gregorian)"
+        );
+        assert_eq!(
+            date.format("%Y-%m-%d %H:%M:%S"),
+            "1957-10-04 19:26:24".to_string()
+        );
+        assert_eq!(
+            date.format("%a %d%f %b %Y, day %j"),
+            "Fri 04.8100 Oct 1957, day 277"
+        );
+
+        // A fraction that rounds up to a whole day is carried into the date
+        let date = Date::from_jd(JD::from(2_436_116.499_9));
+        assert_eq!(date.format("%Y-%m-%d%f"), "1957-10-04.9999");
+        let date = Date::from_jd(JD::from(2_436_116.499_96));
+        assert_eq!(date.format("%Y-%m-%d%f %a"), "1957-10-05.0000 Sat");
+        assert_eq!(date.format("%d %%f"), "04 %f");
+        assert_eq!(
+            date.format("%A %B %d, 100%% %C %q"),
+            "Friday October 04, 100% Gregorian %q"
+        );
+
+        // Whole minutes aren't shown as the second before
+        let date = Date::from_jd(JD::from_parts(2_436_115.5, 780.0 / 86_400.0));
+        assert_eq!(date.format("%H:%M:%S"), "00:13:00");
+        assert_eq!(date.to_string(), "October 4, 1957 00:13:00");
+        let date = Date::from_jd(JD::from_parts(2_436_115.5, 0.5 - 1e-12));
+        assert_eq!(date.format("%H:%M:%S"), "12:00:00");
+
+        // Example 7.b, 333 January 27.5
+        let date = Date::from_jd(JD::from(1_842_713.0));
+        assert_eq!(format!("{:#}", date), "January 27, 333 12:00:00 (Julian)");
+        let midnight = Date::from_jd(JD::from(1_842_712.5));
+        assert_eq!(midnight.to_string(), "January 27, 333");
+    }
+
+    #[test]
+    fn validation() {
+        assert!(Date::new(Calendar::Gregorian, 2019, Month::February, 28, 0.5).is_ok());
+        assert_eq!(
+            Date::new(Calendar::Gregorian, 2019, Month::February, 29, 0.0),
+            Err(DateError::InvalidDay {
+                month: Month::February,
+                day: 29
+            })
+        );
+        assert!(Date::new(Calendar::Gregorian, 2020, Month::February, 29, 0.0).is_ok());
+        assert!(Date::new(Calendar::Gregorian, 2100, Month::February, 29, 0.0).is_err());
+        assert!(Date::new(Calendar::Julian, 1900, Month::February, 29, 0.0).is_ok());
+        assert!(Date::new(Calendar::Gregorian, 2019, Month::April, 31, 0.0).is_err());
+        assert!(Date::new(Calendar::Gregorian, 2019, Month::May, 0, 0.0).is_err());
+        assert_eq!(
+            Date::new(Calendar::Gregorian, 2019, Month::May, 1, 1.0),
+            Err(DateError::InvalidFraction(1.0))
+        );
+        assert!(Date::new(Calendar::Gregorian, 2019, Month::May, 1, -0.1).is_err());
     }
 
     #[test]
@@ -687,6 +1232,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn orthodox_easter() {
+        let gregorian =
+            |year, month, day| Date::new(Calendar::Gregorian, year, month, day, 0.0).unwrap();
+        assert_eq!(
+            find_orthodox_easter(Year(2019)),
+            gregorian(2019, Month::April, 28)
+        );
+        assert_eq!(
+            find_orthodox_easter(Year(2008)),
+            gregorian(2008, Month::April, 27)
+        );
+        assert_eq!(
+            find_orthodox_easter(Year(2023)),
+            gregorian(2023, Month::April, 16)
+        );
+        assert_eq!(
+            find_orthodox_easter(Year(2024)),
+            gregorian(2024, Month::May, 5)
+        );
+        // Sometimes both churches celebrate on the same day
+        assert_eq!(
+            find_orthodox_easter(Year(2010)),
+            find_gregorian_easter(Year(2010))
+        );
+        // Before the reform, it is the same as the Julian date
+        assert_eq!(
+            find_orthodox_easter(Year(179)),
+            find_julian_easter(Year(179))
+        );
+    }
+
     #[test]
     fn passover() {
         assert_eq!(
@@ -0,0 +1,206 @@
+//! Civil dates with a time of day, for reporting the moments of events
+use crate::time::date::{Date, TimeOfDay};
+use crate::time::{TimeScale, JD, TT};
+
+use core::fmt::{self, Display};
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// How finely a time of day is kept when converting a `JD` into a `DateTime`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Precision {
+    /// Keep the fractions of a second
+    Exact,
+    /// Round to the nearest second
+    Second,
+    /// Round to the nearest minute
+    Minute,
+}
+
+/// A calendar date with a time of day, optionally offset from UTC.
+///
+/// The rounding is applied to the moment as a whole, so the carry propagates all the way to the
+/// date. For example 23:59:59.7 rounded to the second is midnight of the following day.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct DateTime {
+    /// The date, with its fraction of a day set to zero
+    pub date: Date,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: f64,
+    /// The offset of the local time from UTC in minutes, positive east of Greenwich. `None` means
+    /// the time is UTC (or whatever time scale the `JD` was in).
+    pub utc_offset: Option<i32>,
+}
+
+impl DateTime {
+    /// Converts a `JD` into a date and time of day, rounded to the given precision
+    pub fn from_jd<S: TimeScale>(jd: &JD<S>, precision: Precision) -> DateTime {
+        DateTime::split(jd, None, precision)
+    }
+
+    /// Converts a `JD` in UTC into a local date and time of day, `utc_offset` minutes ahead of
+    /// UTC, rounded to the given precision
+    pub fn from_jd_with_offset<S: TimeScale>(
+        jd: &JD<S>,
+        utc_offset: i32,
+        precision: Precision,
+    ) -> DateTime {
+        DateTime::split(jd, Some(utc_offset), precision)
+    }
+
+    /// Converts the date and time back into a `JD`, removing the offset from UTC if there is one.
+    /// The date and time are taken to be in the time scale `S`.
+    pub fn to_jd<S: TimeScale>(&self) -> JD<S> {
+        let seconds = self.hour as f64 * 3600.0 + self.minute as f64 * 60.0 + self.second
+            - self.utc_offset.unwrap_or(0) as f64 * 60.0;
+        self.date.to_jd().relabel::<S>() + seconds / SECONDS_PER_DAY
+    }
+
+    fn split<S: TimeScale>(jd: &JD<S>, utc_offset: Option<i32>, precision: Precision) -> DateTime {
+        let seconds = jd.fraction() * SECONDS_PER_DAY + utc_offset.unwrap_or(0) as f64 * 60.0;
+        let seconds = match precision {
+            Precision::Exact => seconds,
+            Precision::Second => seconds.round(),
+            Precision::Minute => (seconds / 60.0).round() * 60.0,
+        };
+
+        // Carry whole days over to the date
+        let days = (seconds / SECONDS_PER_DAY).floor();
+        let seconds = seconds - days * SECONDS_PER_DAY;
+        let date = Date::from_jd(JD::from_parts(jd.midnight() + days, 0.0));
+
+        // Split the rounded seconds with whole numbers, so the rounding isn't undone
+        let time = match precision {
+            Precision::Exact => TimeOfDay::from_fraction(seconds / SECONDS_PER_DAY),
+            Precision::Second | Precision::Minute => {
+                let seconds = seconds as u32;
+                TimeOfDay {
+                    hour: (seconds / 3600) as u8,
+                    minute: (seconds % 3600 / 60) as u8,
+                    second: f64::from(seconds % 60),
+                }
+            }
+        };
+        DateTime {
+            date,
+            hour: time.hour,
+            minute: time.minute,
+            second: time.second,
+            utc_offset,
+        }
+    }
+}
+
+impl<S: TimeScale> From<DateTime> for JD<S> {
+    fn from(item: DateTime) -> Self {
+        item.to_jd()
+    }
+}
+
+impl Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Seconds that round up to a whole minute are carried, all the way to the date if needed
+        if self.second.fract() != 0.0 && (self.second * 1000.0).round() >= 60_000.0 {
+            let carried = DateTime::split(&self.to_jd::<TT>(), self.utc_offset, Precision::Second);
+            return carried.fmt(f);
+        }
+
+        write!(f, "{} {:02}:{:02}:", self.date, self.hour, self.minute)?;
+        if self.second.fract() == 0.0 {
+            write!(f, "{:02}", self.second)?;
+        } else {
+            write!(f, "{:06.3}", self.second)?;
+        }
+        match self.utc_offset {
+            Some(offset) => {
+                let sign = if offset < 0 { '-' } else { '+' };
+                write!(
+                    f,
+                    " UTC{}{:02}:{:02}",
+                    sign,
+                    offset.abs() / 60,
+                    offset.abs() % 60
+                )
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::UT;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn rounding_carries_to_the_date() {
+        // 1957 October 4 at 23:59:59.7
+        let jd: JD = JD::from_parts(2_436_115.5, 86_399.7 / SECONDS_PER_DAY);
+
+        let exact = DateTime::from_jd(&jd, Precision::Exact);
+        assert_eq!(exact.date, Date::from_jd(JD::from(2_436_115.5)));
+        assert_eq!((exact.hour, exact.minute), (23, 59));
+        assert_approx_eq!(exact.second, 59.7, 1e-6);
+
+        let rounded = DateTime::from_jd(&jd, Precision::Second);
+        assert_eq!(rounded.date, Date::from_jd(JD::from(2_436_116.5)));
+        assert_eq!((rounded.hour, rounded.minute, rounded.second), (0, 0, 0.0));
+        assert_eq!(rounded.to_string(), "October 5, 1957 00:00:00");
+
+        // Printing the seconds to the millisecond carries the same way
+        let jd: JD = JD::from_parts(2_436_115.5, 86_399.999_8 / SECONDS_PER_DAY);
+        let exact = DateTime::from_jd(&jd, Precision::Exact);
+        assert_eq!((exact.hour, exact.minute), (23, 59));
+        assert_eq!(exact.to_string(), "October 5, 1957 00:00:00");
+        let jd: JD = JD::from_parts(2_436_115.5, 45_599.999_8 / SECONDS_PER_DAY);
+        let exact = DateTime::from_jd_with_offset(&jd, 90, Precision::Exact);
+        assert_eq!(exact.to_string(), "October 4, 1957 14:10:00 UTC+01:30");
+
+        let jd: JD = JD::from_parts(2_436_115.5, 0.81);
+        let minutes = DateTime::from_jd(&jd, Precision::Minute);
+        assert_eq!(
+            (minutes.hour, minutes.minute, minutes.second),
+            (19, 26, 0.0)
+        );
+    }
+
+    #[test]
+    fn rounded_fields() {
+        // 00:13 and 00:00:11 are exact after rounding, whatever their fraction of a day gives
+        let jd: JD = JD::from_parts(2_436_115.5, 780.2 / SECONDS_PER_DAY);
+        let minutes = DateTime::from_jd(&jd, Precision::Minute);
+        assert_eq!((minutes.hour, minutes.minute, minutes.second), (0, 13, 0.0));
+        assert_eq!(minutes.to_string(), "October 4, 1957 00:13:00");
+
+        let jd: JD = JD::from_parts(2_436_115.5, 11.0 / SECONDS_PER_DAY);
+        let seconds = DateTime::from_jd(&jd, Precision::Second);
+        assert_eq!((seconds.hour, seconds.minute, seconds.second), (0, 0, 11.0));
+        assert_eq!(seconds.to_string(), "October 4, 1957 00:00:11");
+        for whole in (0..86_400).step_by(7) {
+            let jd: JD = JD::from_parts(2_436_115.5, whole as f64 / SECONDS_PER_DAY);
+            let time = DateTime::from_jd(&jd, Precision::Second);
+            assert_eq!(
+                time.hour as u32 * 3600 + time.minute as u32 * 60,
+                whole - whole % 60
+            );
+            assert_eq!(time.second, f64::from(whole % 60));
+        }
+    }
+
+    #[test]
+    fn offset_from_utc() {
+        // 22:30 UTC is the next day at UTC+2, and the previous evening stays put at UTC-5
+        let jd: JD<UT> = JD::from_parts(2_436_115.5, 22.5 / 24.0);
+        let east = DateTime::from_jd_with_offset(&jd, 120, Precision::Minute);
+        assert_eq!(east.date, Date::from_jd(JD::from(2_436_116.5)));
+        assert_eq!((east.hour, east.minute), (0, 30));
+        assert_eq!(east.to_string(), "October 5, 1957 00:30:00 UTC+02:00");
+
+        let west = DateTime::from_jd_with_offset(&jd, -300, Precision::Exact);
+        assert_eq!((west.hour, west.minute), (17, 30));
+        assert_approx_eq!(west.to_jd() - jd, 0.0, 1e-9);
+        assert_approx_eq!(JD::from(east) - jd, 0.0, 1e-9);
+    }
+}
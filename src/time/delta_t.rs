@@ -0,0 +1,96 @@
+//! The difference between Dynamical Time and Universal Time
+use crate::interpolation::interpolate3;
+use crate::time::{JD, UT};
+
+/// The first year of `OBSERVED`
+const FIRST_YEAR: f64 = 1620.0;
+
+/// Observed values of ΔT in seconds, every two years from 1620 to 1998, from table 10.A
+#[rustfmt::skip]
+const OBSERVED: [f64; 190] = [
+    121.0, 112.0, 103.0, 95.0, 88.0, 82.0, 77.0, 72.0, 68.0, 63.0,
+    60.0, 56.0, 53.0, 51.0, 48.0, 46.0, 44.0, 42.0, 40.0, 38.0,
+    35.0, 33.0, 31.0, 29.0, 26.0, 24.0, 22.0, 20.0, 18.0, 16.0,
+    14.0, 12.0, 11.0, 10.0, 9.0, 8.0, 7.0, 7.0, 7.0, 7.0,
+    7.0, 7.0, 8.0, 8.0, 9.0, 9.0, 9.0, 9.0, 9.0, 10.0,
+    10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 11.0, 11.0, 11.0,
+    11.0, 11.0, 12.0, 12.0, 12.0, 12.0, 13.0, 13.0, 13.0, 14.0,
+    14.0, 14.0, 14.0, 15.0, 15.0, 15.0, 15.0, 15.0, 16.0, 16.0,
+    16.0, 16.0, 16.0, 16.0, 16.0, 16.0, 15.0, 15.0, 14.0, 13.0,
+    13.1, 12.5, 12.2, 12.0, 12.0, 12.0, 12.0, 12.0, 12.0, 11.9,
+    11.6, 11.0, 10.2, 9.2, 8.2, 7.1, 6.2, 5.6, 5.4, 5.3,
+    5.4, 5.6, 5.9, 6.2, 6.5, 6.8, 7.1, 7.3, 7.5, 7.6,
+    7.7, 7.3, 6.2, 5.2, 2.7, 1.4, -1.2, -2.8, -3.8, -4.8,
+    -5.5, -5.3, -5.6, -5.7, -5.9, -6.0, -6.3, -6.5, -6.2, -4.7,
+    -2.8, -0.1, 2.6, 5.3, 7.7, 10.4, 13.3, 16.0, 18.2, 20.2,
+    21.1, 22.4, 23.5, 23.8, 24.3, 24.0, 23.9, 23.9, 23.7, 24.0,
+    24.3, 25.3, 26.2, 27.3, 28.2, 29.1, 30.0, 30.7, 31.4, 32.2,
+    33.1, 34.0, 35.0, 36.5, 38.3, 40.2, 42.2, 44.5, 46.5, 48.5,
+    50.5, 52.2, 53.8, 54.9, 55.8, 56.9, 58.3, 60.0, 61.6, 63.0,
+];
+
+/// Estimates ΔT = TD − UT in seconds at the given instant, following chapter 10.
+///
+/// Between 1620 and 1998 the observed values of table 10.A are interpolated. Outside of that range
+/// the polynomial expressions of chapter 10 are used, which are only rough extrapolations: the
+/// uncertainty grows to several minutes a couple of millennia away from the present. Between 1998
+/// and 2000 the last observed value is joined linearly to the expression for 2000 onwards.
+///
+/// To convert a Universal Time into Dynamical Time, add `delta_t / 86400.0` days.
+pub fn delta_t(t: &JD<UT>) -> f64 {
+    let year = 2000.0 + (t.as_f64() - 2_451_545.0) / 365.25;
+    let last_year = FIRST_YEAR + 2.0 * (OBSERVED.len() - 1) as f64;
+
+    if year < 948.0 {
+        let t = (year - 2000.0) / 100.0;
+        2177.0 + 497.0 * t + 44.1 * t * t
+    } else if year < FIRST_YEAR {
+        after_948(year)
+    } else if year <= last_year {
+        let offset = (year - FIRST_YEAR) / 2.0;
+        let center = (offset.round() as usize).max(1).min(OBSERVED.len() - 2);
+        let y = [OBSERVED[center - 1], OBSERVED[center], OBSERVED[center + 1]];
+        interpolate3(&y, offset - center as f64)
+    } else if year < 2000.0 {
+        let last = OBSERVED[OBSERVED.len() - 1];
+        last + (after_948(2000.0) - last) * (year - last_year) / (2000.0 - last_year)
+    } else {
+        after_948(year)
+    }
+}
+
+/// The expression for ΔT between 948 and 1600, which is also used after 2000 with a correction
+/// that makes it join the observed values
+fn after_948(year: f64) -> f64 {
+    let t = (year - 2000.0) / 100.0;
+    let delta_t = 102.0 + 102.0 * t + 25.3 * t * t;
+    if (2000.0..=2100.0).contains(&year) {
+        delta_t + 0.37 * (year - 2100.0)
+    } else {
+        delta_t
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn observed_values() {
+        // Example 10.a, page 78: 1977 February 18, ΔT = 48s
+        assert_approx_eq!(delta_t(&JD::from(2_443_192.5)), 48.0, 0.5);
+
+        // The tabulated values are reproduced exactly, 1900 January 1 is close to -2.8s
+        assert_approx_eq!(delta_t(&JD::from(2_415_020.5)), -2.8, 0.01);
+    }
+
+    #[test]
+    fn extrapolated_values() {
+        // Example 10.b, page 80: 333 February 6, ΔT = 6146s
+        assert_approx_eq!(delta_t(&JD::from(1_842_713.0)), 6146.0, 1.0);
+
+        // The expression after 2000 starts out at 65s
+        assert_approx_eq!(delta_t(&JD::from(2_451_545.0)), 65.0, 0.01);
+    }
+}
@@ -0,0 +1,110 @@
+//! Conversions between `JD` and `hifitime::Epoch`, enabled by the `hifitime` feature.
+//!
+//! Terrestrial Time, International Atomic Time and Barycentric Dynamical Time map directly onto
+//! the corresponding hifitime time scales. hifitime has no model of Universal Time (UT1), so days
+//! in `UT` are converted through Terrestrial Time using `delta_t`.
+//!
+//! The conversions keep the split representation of `JD`, so they are good to well below a
+//! microsecond.
+use crate::time::{TimeScale, JD, TAI, TDB, TT, UT};
+
+use ::hifitime::{Duration, Epoch, TimeScale as Scale, Unit};
+
+fn to_epoch<S: TimeScale>(jd: &JD<S>, scale: Scale) -> Epoch {
+    Epoch::from_jde_in_time_scale(jd.midnight(), scale) + Unit::Day * jd.fraction()
+}
+
+fn from_julian_days<S: TimeScale>(days: Duration) -> JD<S> {
+    let whole = (days.to_unit(Unit::Day) - 0.5).floor() + 0.5;
+    let fraction = (days - Unit::Day * whole).to_unit(Unit::Day);
+    JD::from_parts(whole, fraction)
+}
+
+impl From<JD<TT>> for Epoch {
+    fn from(item: JD<TT>) -> Self {
+        to_epoch(&item, Scale::TT)
+    }
+}
+
+impl From<JD<TAI>> for Epoch {
+    fn from(item: JD<TAI>) -> Self {
+        to_epoch(&item, Scale::TAI)
+    }
+}
+
+impl From<JD<TDB>> for Epoch {
+    fn from(item: JD<TDB>) -> Self {
+        to_epoch(&item, Scale::TDB)
+    }
+}
+
+impl From<JD<UT>> for Epoch {
+    fn from(item: JD<UT>) -> Self {
+        Epoch::from(item.to_scale::<TT>())
+    }
+}
+
+impl From<Epoch> for JD<TT> {
+    fn from(item: Epoch) -> Self {
+        from_julian_days(item.to_jde_tt_duration())
+    }
+}
+
+impl From<Epoch> for JD<TAI> {
+    fn from(item: Epoch) -> Self {
+        from_julian_days(item.to_jde_tai_duration())
+    }
+}
+
+impl From<Epoch> for JD<TDB> {
+    fn from(item: Epoch) -> Self {
+        from_julian_days(item.to_jde_tdb_duration())
+    }
+}
+
+impl From<Epoch> for JD<UT> {
+    fn from(item: Epoch) -> Self {
+        JD::<TT>::from(item).to_scale()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn round_trips() {
+        let jd: JD = JD::from_parts(2_460_000.5, 0.123_456_789_012);
+        let epoch = Epoch::from(jd);
+        assert_approx_eq!(epoch.to_jde_tt_days(), jd.as_f64(), 1e-9);
+        let back = JD::<TT>::from(epoch);
+        assert!((back - jd).abs() * 86_400.0 < 1e-8);
+
+        let tai: JD<TAI> = jd.to_scale();
+        let back = JD::<TAI>::from(Epoch::from(tai));
+        assert!((back - tai).abs() * 86_400.0 < 1e-8);
+
+        let tdb: JD<TDB> = jd.to_scale();
+        let back = JD::<TDB>::from(Epoch::from(tdb));
+        assert!((back - tdb).abs() * 86_400.0 < 1e-8);
+
+        let ut: JD<UT> = jd.to_scale();
+        let back = JD::<UT>::from(Epoch::from(ut));
+        assert!((back - ut).abs() * 86_400.0 < 1e-6);
+    }
+
+    #[test]
+    fn time_scales_agree() {
+        // The same instant has the same offsets between time scales in both crates
+        let jd: JD = JD::from_parts(2_451_544.5, 0.5);
+        let epoch = Epoch::from(jd);
+        let tai = JD::<TAI>::from(epoch);
+        assert_approx_eq!((jd.fraction() - tai.fraction()) * 86_400.0, 32.184, 1e-6);
+        assert_approx_eq!(epoch.to_jde_tai_days(), tai.as_f64(), 1e-9);
+
+        // The TDB models differ slightly, but agree to a few microseconds
+        let tdb = JD::<TDB>::from(epoch);
+        assert!((tdb - jd.to_scale::<TDB>()).abs() * 86_400.0 < 5e-6);
+    }
+}
@@ -0,0 +1,239 @@
+//! Leap seconds, the steps between Coordinated Universal Time and International Atomic Time
+use crate::time::{JD, TAI, UTC};
+
+use std::borrow::Cow;
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// A change in the offset TAI − UTC, which holds from the start of a day until the next change.
+///
+/// Since 1972 the offset has been a whole number of seconds, changed by inserting leap seconds.
+/// Between 1961 and 1972 UTC was also steered by slowly changing the length of its second, so the
+/// offset was `offset + (MJD − drift_epoch) × drift_rate`, where MJD is the Modified Julian Day in
+/// UTC.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LeapSecond {
+    /// The Modified Julian Day, in UTC, of the midnight the offset starts at
+    pub mjd: f64,
+    /// The offset TAI − UTC in seconds
+    pub offset: f64,
+    /// The Modified Julian Day the drift of the offset is counted from
+    pub drift_epoch: f64,
+    /// The drift of the offset, in seconds per day
+    pub drift_rate: f64,
+}
+
+impl LeapSecond {
+    /// A whole number of seconds of offset, starting at the midnight of the given Modified Julian
+    /// Day
+    pub const fn new(mjd: f64, offset: f64) -> LeapSecond {
+        LeapSecond {
+            mjd,
+            offset,
+            drift_epoch: 0.0,
+            drift_rate: 0.0,
+        }
+    }
+
+    const fn drifting(mjd: f64, offset: f64, drift_epoch: f64, drift_rate: f64) -> LeapSecond {
+        LeapSecond {
+            mjd,
+            offset,
+            drift_epoch,
+            drift_rate,
+        }
+    }
+}
+
+/// The offsets since the start of UTC in 1961, up to the leap second at the end of 2016
+const BUILTIN: [LeapSecond; 41] = [
+    LeapSecond::drifting(37_300.0, 1.422_818_0, 37_300.0, 0.001_296),
+    LeapSecond::drifting(37_512.0, 1.372_818_0, 37_300.0, 0.001_296),
+    LeapSecond::drifting(37_665.0, 1.845_858_0, 37_665.0, 0.001_123_2),
+    LeapSecond::drifting(38_334.0, 1.945_858_0, 37_665.0, 0.001_123_2),
+    LeapSecond::drifting(38_395.0, 3.240_130_0, 38_761.0, 0.001_296),
+    LeapSecond::drifting(38_486.0, 3.340_130_0, 38_761.0, 0.001_296),
+    LeapSecond::drifting(38_639.0, 3.440_130_0, 38_761.0, 0.001_296),
+    LeapSecond::drifting(38_761.0, 3.540_130_0, 38_761.0, 0.001_296),
+    LeapSecond::drifting(38_820.0, 3.640_130_0, 38_761.0, 0.001_296),
+    LeapSecond::drifting(38_942.0, 3.740_130_0, 38_761.0, 0.001_296),
+    LeapSecond::drifting(39_004.0, 3.840_130_0, 38_761.0, 0.001_296),
+    LeapSecond::drifting(39_126.0, 4.313_170_0, 39_126.0, 0.002_592),
+    LeapSecond::drifting(39_887.0, 4.213_170_0, 39_126.0, 0.002_592),
+    LeapSecond::new(41_317.0, 10.0),
+    LeapSecond::new(41_499.0, 11.0),
+    LeapSecond::new(41_683.0, 12.0),
+    LeapSecond::new(42_048.0, 13.0),
+    LeapSecond::new(42_413.0, 14.0),
+    LeapSecond::new(42_778.0, 15.0),
+    LeapSecond::new(43_144.0, 16.0),
+    LeapSecond::new(43_509.0, 17.0),
+    LeapSecond::new(43_874.0, 18.0),
+    LeapSecond::new(44_239.0, 19.0),
+    LeapSecond::new(44_786.0, 20.0),
+    LeapSecond::new(45_151.0, 21.0),
+    LeapSecond::new(45_516.0, 22.0),
+    LeapSecond::new(46_247.0, 23.0),
+    LeapSecond::new(47_161.0, 24.0),
+    LeapSecond::new(47_892.0, 25.0),
+    LeapSecond::new(48_257.0, 26.0),
+    LeapSecond::new(48_804.0, 27.0),
+    LeapSecond::new(49_169.0, 28.0),
+    LeapSecond::new(49_534.0, 29.0),
+    LeapSecond::new(50_083.0, 30.0),
+    LeapSecond::new(50_630.0, 31.0),
+    LeapSecond::new(51_179.0, 32.0),
+    LeapSecond::new(53_736.0, 33.0),
+    LeapSecond::new(54_832.0, 34.0),
+    LeapSecond::new(56_109.0, 35.0),
+    LeapSecond::new(57_204.0, 36.0),
+    LeapSecond::new(57_754.0, 37.0),
+];
+
+/// A table of the offsets between UTC and TAI.
+///
+/// The built in table is the one used by the `UTC` time scale. Leap seconds announced after this
+/// crate was released can be added to a copy of it, and the conversions done through the table.
+///
+/// Before 1961 there was no UTC, and the offset is taken to be zero. A leap second itself can't be
+/// represented in UTC Julian Days, which have no room for a 61st second, so it converts to the
+/// first second of the following day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeapSeconds {
+    entries: Cow<'static, [LeapSecond]>,
+}
+
+impl LeapSeconds {
+    /// The table built into this crate
+    pub fn builtin() -> LeapSeconds {
+        LeapSeconds {
+            entries: Cow::Borrowed(&BUILTIN),
+        }
+    }
+
+    /// Builds a table from a full list of offsets.
+    ///
+    /// # Panics
+    /// Panics if the entries are not sorted by date
+    pub fn from_entries(entries: Vec<LeapSecond>) -> LeapSeconds {
+        assert!(
+            entries.windows(2).all(|pair| pair[0].mjd < pair[1].mjd),
+            "Leap seconds must be sorted by date"
+        );
+        LeapSeconds {
+            entries: Cow::Owned(entries),
+        }
+    }
+
+    /// Adds an offset to the end of the table, for example a newly announced leap second.
+    ///
+    /// # Panics
+    /// Panics if the entry is not later than the last one in the table
+    pub fn push(&mut self, entry: LeapSecond) {
+        if let Some(last) = self.entries.last() {
+            assert!(entry.mjd > last.mjd, "Leap seconds must be sorted by date");
+        }
+        self.entries.to_mut().push(entry);
+    }
+
+    /// The entries of the table, sorted by date
+    pub fn entries(&self) -> &[LeapSecond] {
+        &self.entries
+    }
+
+    /// Gets the offset TAI − UTC in seconds at the given instant in UTC
+    pub fn tai_minus_utc(&self, utc: &JD<UTC>) -> f64 {
+        let mjd = utc.to_mjd();
+        let index = self.entries.partition_point(|entry| entry.mjd <= mjd);
+        match index {
+            0 => 0.0,
+            _ => {
+                let entry = &self.entries[index - 1];
+                entry.offset + (mjd - entry.drift_epoch) * entry.drift_rate
+            }
+        }
+    }
+
+    /// Converts an instant in UTC into TAI
+    pub fn utc_to_tai(&self, utc: &JD<UTC>) -> JD<TAI> {
+        utc.relabel() + self.tai_minus_utc(utc) / SECONDS_PER_DAY
+    }
+
+    /// Converts an instant in TAI into UTC
+    pub fn tai_to_utc(&self, tai: &JD<TAI>) -> JD<UTC> {
+        // The offset is a function of UTC, so start from the offset at the same day count
+        let guess = tai.relabel::<UTC>() - self.tai_minus_utc(&tai.relabel()) / SECONDS_PER_DAY;
+        tai.relabel::<UTC>() - self.tai_minus_utc(&guess) / SECONDS_PER_DAY
+    }
+}
+
+impl Default for LeapSeconds {
+    fn default() -> Self {
+        LeapSeconds::builtin()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::TT;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn offsets() {
+        let table = LeapSeconds::builtin();
+        let offset = |mjd: f64| table.tai_minus_utc(&JD::from_mjd(mjd));
+
+        assert_eq!(offset(30_000.0), 0.0);
+        assert_approx_eq!(offset(37_665.0), 1.845_858, 1e-9);
+        assert_approx_eq!(offset(39_887.0), 4.213_17 + 761.0 * 0.002_592, 1e-9);
+        assert_eq!(offset(41_317.0), 10.0);
+        assert_eq!(offset(57_753.999), 36.0);
+        assert_eq!(offset(57_754.0), 37.0);
+        assert_eq!(offset(60_000.0), 37.0);
+    }
+
+    #[test]
+    fn conversions() {
+        // 2017 January 1 at 0h UTC, just after the last leap second, was 69.184 s before TT
+        let utc: JD<UTC> = JD::from_mjd(57_754.0);
+        let tt = utc.to_scale::<TT>();
+        assert_approx_eq!((tt.to_mjd() - utc.to_mjd()) * SECONDS_PER_DAY, 69.184, 1e-6);
+        assert_approx_eq!((tt.to_scale::<UTC>() - utc) * SECONDS_PER_DAY, 0.0, 1e-6);
+
+        // A second before midnight the offset was still 36 s
+        let before = utc - 1.0 / SECONDS_PER_DAY;
+        let tai = before.to_scale::<TAI>();
+        assert_approx_eq!(
+            (tai.to_mjd() - before.to_mjd()) * SECONDS_PER_DAY,
+            36.0,
+            1e-6
+        );
+        assert_approx_eq!(
+            (tai.to_scale::<UTC>() - before) * SECONDS_PER_DAY,
+            0.0,
+            1e-6
+        );
+
+        // The leap second itself ends up at the start of the next day
+        let leap: JD<TAI> = JD::from_mjd(57_754.0) + 36.5 / SECONDS_PER_DAY;
+        let table = LeapSeconds::builtin();
+        assert_approx_eq!((table.tai_to_utc(&leap) - utc) * SECONDS_PER_DAY, 0.5, 1e-6);
+    }
+
+    #[test]
+    fn updated_table() {
+        // A hypothetical leap second at the end of 2030
+        let mut table = LeapSeconds::builtin();
+        table.push(LeapSecond::new(62_867.0, 38.0));
+        assert_eq!(table.entries().len(), BUILTIN.len() + 1);
+        assert_eq!(table.tai_minus_utc(&JD::from_mjd(62_867.0)), 38.0);
+        assert_eq!(
+            LeapSeconds::builtin().tai_minus_utc(&JD::from_mjd(62_867.0)),
+            37.0
+        );
+
+        let table = LeapSeconds::from_entries(vec![LeapSecond::new(41_317.0, 10.0)]);
+        assert_eq!(table.tai_minus_utc(&JD::from_mjd(57_754.0)), 10.0);
+    }
+}
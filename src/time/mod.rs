@@ -5,30 +5,478 @@
 //! For our purposes in this crate we like to represent time as a "Julian Day". The Julian Day
 //! representation is the number of days since Noon on Jan 1 in the year -4712 as a Real number.
 
+use std::cmp::Ordering;
 use std::convert::From;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
 
 pub mod date;
+pub mod datetime;
+mod delta_t;
+#[cfg(feature = "hifitime")]
+mod hifitime;
+mod leap_seconds;
+mod scales;
+pub mod sidereal;
+
+pub use self::delta_t::delta_t;
+pub use self::leap_seconds::{LeapSecond, LeapSeconds};
+pub use self::scales::{TimeScale, TAI, TDB, TT, UT, UTC};
+
+/// The Julian Day of the start of the Modified Julian Day count
+const MJD_EPOCH: f64 = 2_400_000.5;
+
+/// The Julian Day of the Unix epoch, 1970 January 1 at 0h UTC
+#[cfg(feature = "clock")]
+const UNIX_EPOCH: f64 = 2_440_587.5;
 
 /// Representation of a Julian Day
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub struct JD(f64);
+///
+/// Internally the Julian Day is split into the Julian Day of the preceding midnight and the
+/// fraction of a day elapsed since then. A single `f64` holding a modern Julian Day can only
+/// resolve about 40 microseconds, while the split representation keeps the full precision of the
+/// time of day. Converting to and from a bare `f64` is still supported, but will round the value to
+/// the precision of a single `f64`.
+///
+/// Negative Julian Days are valid, and refer to dates before the start of the Julian period in
+/// -4712.
+///
+/// The type parameter is the time scale the Julian Day is counted in. It defaults to Terrestrial
+/// Time, the uniform time scale the planetary theories are expressed in, while civil times and the
+/// rotation of the Earth are measured in Universal Time. Days in different time scales can't be
+/// mixed by accident, they have to be converted with `to_scale`.
+///
+/// `JD`s are totally ordered, so they can be sorted and used as keys of maps. The order is the
+/// one of `f64::total_cmp`, after turning every NaN into the positive one, so that a NaN sorts
+/// after every other value and is equal to any other NaN.
+#[derive(Debug, Copy, Clone)]
+pub struct JD<S: TimeScale = TT> {
+    midnight: f64,
+    fraction: f64,
+    scale: PhantomData<S>,
+}
+
+impl JD<TT> {
+    /// The standard epoch J2000.0, 2000 January 1.5 TT
+    pub const J2000: JD<TT> = JD {
+        midnight: 2_451_544.5,
+        fraction: 0.5,
+        scale: PhantomData,
+    };
+}
+
+impl<S: TimeScale> JD<S> {
+    /// Builds a `JD` from two parts whose sum is the Julian Day.
+    ///
+    /// The parts can be split in any way, for example a whole number and a fraction, and will be
+    /// normalized internally. The most precision is kept when the first part is the Julian Day of
+    /// a midnight and the second part is the fraction of the day.
+    pub fn from_parts(day: f64, fraction: f64) -> JD<S> {
+        let midnight = (day - 0.5).floor() + 0.5;
+        let fraction = (day - midnight) + fraction;
+        let mut carry = fraction.floor();
+        let mut fraction = fraction - carry;
+
+        // A tiny negative fraction rounds to a whole day once the carry is taken out of it
+        if fraction >= 1.0 {
+            carry += 1.0;
+            fraction = 0.0;
+        }
+
+        JD {
+            midnight: midnight + carry,
+            fraction,
+            scale: PhantomData,
+        }
+    }
+
+    /// Gets the Julian Day of the midnight preceding this instant
+    pub fn midnight(&self) -> f64 {
+        self.midnight
+    }
+
+    /// Gets the fraction of a day elapsed since the preceding midnight, in the range [0, 1)
+    pub fn fraction(&self) -> f64 {
+        self.fraction
+    }
 
-impl JD {
     /// Converts a `JD` into a bare `f64`
     pub fn as_f64(&self) -> f64 {
-        self.0
+        self.midnight + self.fraction
+    }
+
+    /// Builds a `JD` from a Modified Julian Day, the number of days since 1858 November 17 at 0h.
+    ///
+    /// The conversion is exact, converting back with `to_mjd` gives the same value.
+    pub fn from_mjd(mjd: f64) -> JD<S> {
+        JD::from_parts(MJD_EPOCH, mjd)
+    }
+
+    /// Converts a `JD` into a Modified Julian Day
+    pub fn to_mjd(&self) -> f64 {
+        (self.midnight - MJD_EPOCH) + self.fraction
+    }
+
+    /// Converts the instant into another time scale
+    pub fn to_scale<T: TimeScale>(&self) -> JD<T> {
+        T::from_tt(&S::to_tt(self))
+    }
+
+    /// Iterates over the instants from `start` to `end` inclusive, every `step` days.
+    ///
+    /// Each instant is computed as `start + i * step` rather than by repeatedly adding the step,
+    /// so the rounding errors don't accumulate over long tables. `end` is included when it falls
+    /// on the grid within a microsecond.
+    ///
+    /// # Panics
+    /// Panics if `step` is not positive
+    pub fn range(start: JD<S>, end: JD<S>, step: f64) -> JDRange<S> {
+        assert!(step > 0.0, "Invalid range step: {}", step);
+        let tolerance = 1e-6 / 86_400.0;
+        let count = ((end - start + tolerance) / step).floor() + 1.0;
+        JDRange {
+            start,
+            step,
+            index: 0,
+            count: count.max(0.0) as usize,
+        }
+    }
+
+    /// Moves the same day count into another time scale, without converting it. Only useful when
+    /// the difference between the time scales doesn't matter.
+    fn relabel<T: TimeScale>(&self) -> JD<T> {
+        JD {
+            midnight: self.midnight,
+            fraction: self.fraction,
+            scale: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "clock")]
+impl JD<UTC> {
+    /// Reads the current time from the system clock.
+    ///
+    /// The system clock counts days of exactly 86400 seconds since 1970, skipping over leap
+    /// seconds, which is the same as the days of UTC.
+    ///
+    /// # Panics
+    /// Panics if the system clock is set before 1970
+    pub fn now() -> JD<UTC> {
+        let elapsed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("The system clock is set before 1970");
+        let days = elapsed.as_secs() / 86_400;
+        let seconds = (elapsed.as_secs() % 86_400) as f64 + elapsed.subsec_nanos() as f64 * 1e-9;
+        JD::from_parts(UNIX_EPOCH + days as f64, seconds / 86_400.0)
+    }
+}
+
+/// A number of Julian centuries of 36525 days since J2000.0, the time argument of most of the
+/// formulae in the book.
+///
+/// The instant is counted in its own time scale, so the centuries from a `JD<TT>` are centuries of
+/// TT since J2000.0 TT.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct JulianCenturies(pub f64);
+
+/// A number of Julian millennia of 365250 days since J2000.0, the time argument of the VSOP87
+/// theory.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct JulianMillennia(pub f64);
+
+/// Gets the number of days since J2000.0, keeping the precision of the split representation
+fn days_since_j2000<S: TimeScale>(t: &JD<S>) -> f64 {
+    (t.midnight - JD::J2000.midnight) + (t.fraction - JD::J2000.fraction)
+}
+
+impl<S: TimeScale> From<JD<S>> for JulianCenturies {
+    fn from(item: JD<S>) -> Self {
+        JulianCenturies(days_since_j2000(&item) / 36_525.0)
+    }
+}
+
+impl<S: TimeScale> From<JD<S>> for JulianMillennia {
+    fn from(item: JD<S>) -> Self {
+        JulianMillennia(days_since_j2000(&item) / 365_250.0)
+    }
+}
+
+impl From<JulianMillennia> for JulianCenturies {
+    fn from(item: JulianMillennia) -> Self {
+        JulianCenturies(item.0 * 10.0)
+    }
+}
+
+impl From<JulianCenturies> for JulianMillennia {
+    fn from(item: JulianCenturies) -> Self {
+        JulianMillennia(item.0 / 10.0)
+    }
+}
+
+/// An iterator over equally spaced instants, created by `JD::range`
+#[derive(Debug, Clone)]
+pub struct JDRange<S: TimeScale = TT> {
+    start: JD<S>,
+    step: f64,
+    index: usize,
+    count: usize,
+}
+
+impl<S: TimeScale> Iterator for JDRange<S> {
+    type Item = JD<S>;
+
+    fn next(&mut self) -> Option<JD<S>> {
+        if self.index >= self.count {
+            return None;
+        }
+        let t = self.start + self.index as f64 * self.step;
+        self.index += 1;
+        Some(t)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.count - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<S: TimeScale> ExactSizeIterator for JDRange<S> {}
+
+impl<S: TimeScale> JD<S> {
+    /// The parts of the `JD`, with negative zeros made positive and every NaN made the same, so
+    /// that they compare equal
+    fn key(&self) -> (f64, f64) {
+        let canonical = |x: f64| if x.is_nan() { f64::NAN } else { x + 0.0 };
+        (canonical(self.midnight), canonical(self.fraction))
+    }
+}
+
+impl<S: TimeScale> PartialEq for JD<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S: TimeScale> Eq for JD<S> {}
+
+impl<S: TimeScale> PartialOrd for JD<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S: TimeScale> Ord for JD<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (midnight, fraction) = self.key();
+        let (other_midnight, other_fraction) = other.key();
+        midnight
+            .total_cmp(&other_midnight)
+            .then(fraction.total_cmp(&other_fraction))
     }
 }
 
-impl From<f64> for JD {
+impl<S: TimeScale> Hash for JD<S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let (midnight, fraction) = self.key();
+        midnight.to_bits().hash(state);
+        fraction.to_bits().hash(state);
+    }
+}
+
+/// Formats the Julian Day followed by its time scale, for example "2451545 TT". The precision, if
+/// given, is the number of decimals.
+impl<S: TimeScale> fmt::Display for JD<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*}", precision, self.as_f64())?,
+            None => write!(f, "{}", self.as_f64())?,
+        }
+        write!(f, " {}", S::NAME)
+    }
+}
+
+impl<S: TimeScale> From<f64> for JD<S> {
     fn from(item: f64) -> Self {
-        assert!(item >= 0.0, "Invalid JD value: {}", item);
-        JD(item)
+        JD::from_parts(item, 0.0)
+    }
+}
+
+impl<S: TimeScale> From<JD<S>> for f64 {
+    fn from(item: JD<S>) -> Self {
+        item.as_f64()
+    }
+}
+
+/// Moves a `JD` forward by a number of days
+impl<S: TimeScale> std::ops::Add<f64> for JD<S> {
+    type Output = Self;
+
+    fn add(self, rhs: f64) -> Self {
+        JD::from_parts(self.midnight, self.fraction + rhs)
+    }
+}
+
+/// Moves a `JD` backward by a number of days
+impl<S: TimeScale> std::ops::Sub<f64> for JD<S> {
+    type Output = Self;
+
+    fn sub(self, rhs: f64) -> Self {
+        JD::from_parts(self.midnight, self.fraction - rhs)
+    }
+}
+
+/// Gets the number of days between two `JD`s in the same time scale
+impl<S: TimeScale> std::ops::Sub for JD<S> {
+    type Output = f64;
+
+    fn sub(self, rhs: Self) -> f64 {
+        (self.midnight - rhs.midnight) + (self.fraction - rhs.fraction)
     }
 }
 
-impl From<JD> for f64 {
-    fn from(item: JD) -> Self {
-        item.0
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_representation() {
+        let jd: JD = JD::from_parts(2_451_545.0, 0.25);
+        assert_eq!(jd.midnight(), 2_451_544.5);
+        assert_eq!(jd.fraction(), 0.75);
+        assert_eq!(jd, JD::from_parts(2_451_544.5, 0.75));
+        assert_eq!(jd, JD::from_parts(2_451_546.5, -1.25));
+
+        // The fraction stays below a whole day even when it rounds up to one
+        let jd: JD = JD::from_parts(2_451_545.5, -1e-20);
+        assert_eq!(jd.midnight(), 2_451_545.5);
+        assert_eq!(jd.fraction(), 0.0);
+
+        assert_eq!(JD::from(2_451_545.0), JD::J2000);
+        assert_eq!(JD::<TT>::from(2_451_545.0).as_f64(), 2_451_545.0);
+    }
+
+    #[test]
+    fn negative_days() {
+        let jd: JD = JD::from(-1.25);
+        assert_eq!(jd.midnight(), -1.5);
+        assert_eq!(jd.fraction(), 0.25);
+        assert_eq!(jd.as_f64(), -1.25);
+        assert_eq!(jd + 1.25, JD::from(0.0));
+    }
+
+    #[test]
+    fn modified_julian_day() {
+        let jd: JD = JD::from_mjd(51_544.5);
+        assert_eq!(jd, JD::J2000);
+        assert_eq!(jd.to_mjd(), 51_544.5);
+
+        // Round trips are exact, even for values that can't be represented as a JD in one f64
+        for &mjd in &[0.0, -0.25, 58_849.123_456_789_01, 60_000.999_999_999_99] {
+            assert_eq!(JD::<TT>::from_mjd(mjd).to_mjd(), mjd);
+        }
+    }
+
+    #[test]
+    fn ranges() {
+        let start: JD = JD::from(2_451_545.0);
+        let days: Vec<JD> = JD::range(start, start + 3.0, 1.0).collect();
+        assert_eq!(days.len(), 4);
+        assert_eq!(days[3], start + 3.0);
+
+        // A step that isn't exact in binary doesn't drift or lose the last value
+        let range = JD::range(start, start + 1.0, 0.1);
+        assert_eq!(range.len(), 11);
+        let last = range.last().unwrap();
+        assert!((last - (start + 1.0)).abs() < 1e-12);
+
+        assert_eq!(JD::range(start, start + 0.5, 1.0).count(), 1);
+        assert_eq!(JD::range(start, start - 1.0, 1.0).count(), 0);
+    }
+
+    #[test]
+    fn julian_centuries() {
+        assert_eq!(JulianCenturies::from(JD::J2000), JulianCenturies(0.0));
+
+        // Example 12.a, 1987 April 10 at 0h UT
+        let t = JulianCenturies::from(JD::<UT>::from(2_446_895.5));
+        assert!((t.0 - -0.127_296_372_348).abs() < 1e-12);
+
+        // 1900 January 0.5 is exactly one century before J2000.0
+        let tau = JulianMillennia::from(JD::<TT>::from(2_415_020.0));
+        assert_eq!(tau, JulianMillennia(-0.1));
+        assert_eq!(JulianCenturies::from(tau), JulianCenturies(-1.0));
+        assert_eq!(
+            JulianMillennia::from(JulianCenturies(1.0)),
+            JulianMillennia(0.1)
+        );
+    }
+
+    #[cfg(feature = "clock")]
+    #[test]
+    fn now() {
+        // 2020 January 1
+        let now = JD::now();
+        assert!(now > JD::from_mjd(58_849.0));
+        assert!(JD::now() >= now);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(JD::J2000.to_string(), "2451545 TT");
+        assert_eq!(
+            format!("{:.3}", JD::<UT>::from(2_436_116.31)),
+            "2436116.310 UT"
+        );
+    }
+
+    #[test]
+    fn ordering() {
+        use std::collections::{BTreeMap, HashSet};
+
+        let mut days: Vec<JD> = vec![
+            JD::from(2_451_545.25),
+            JD::from(-1.0),
+            JD::from(2_451_545.0),
+            JD::from_parts(2_451_545.0, 1e-9),
+        ];
+        days.sort();
+        assert_eq!(days[0], JD::from(-1.0));
+        assert_eq!(days[1], JD::J2000);
+        assert!(days[2] > JD::J2000);
+        assert_eq!(days[3].as_f64(), 2_451_545.25);
+
+        let mut names = BTreeMap::new();
+        names.insert(JD::J2000, "J2000.0");
+        names.insert(JD::from(2_415_020.313_5), "B1900.0");
+        assert_eq!(names.keys().next(), Some(&JD::from(2_415_020.313_5)));
+
+        // Equal instants hash the same, whichever way they were built
+        let set: HashSet<JD> = [JD::J2000, JD::from_parts(2_451_546.0, -1.0)]
+            .iter()
+            .cloned()
+            .collect();
+        assert_eq!(set.len(), 1);
+
+        // A NaN sorts last, and is equal to itself, whatever its sign
+        let nan: JD = JD::from(f64::NAN);
+        assert_eq!(nan, nan);
+        assert!(nan > JD::from(1e9));
+        let negative_nan: JD = JD::from(-f64::NAN);
+        assert_eq!(negative_nan, nan);
+        assert!(negative_nan > JD::from(1e9));
+    }
+
+    #[test]
+    fn sub_millisecond_precision() {
+        // One microsecond is lost when stored in a single f64 this far from the epoch of JD, but
+        // the split representation keeps it
+        let microsecond = 1e-6 / 86400.0;
+        let start: JD = JD::from_parts(2_460_000.5, 0.0);
+        assert_eq!(start.as_f64() + microsecond, start.as_f64());
+        let later = start + microsecond;
+        assert!(later - start > 0.0);
+        assert!(((later - start) - microsecond).abs() < 1e-18);
     }
 }
@@ -0,0 +1,140 @@
+//! The time scales a Julian Day can be counted in
+use crate::angle::Angle;
+use crate::time::leap_seconds::LeapSeconds;
+use crate::time::{delta_t, JD};
+
+use core::fmt::Debug;
+
+/// The offset of Terrestrial Time from International Atomic Time, in seconds
+const TT_MINUS_TAI: f64 = 32.184;
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// A scale in which time is measured. Every time scale can be converted to and from Terrestrial
+/// Time, which makes it possible to convert between any two of them.
+pub trait TimeScale: Debug + Copy + Clone + PartialEq {
+    /// The abbreviated name of the time scale, for display
+    const NAME: &'static str;
+
+    /// Converts an instant in this time scale into Terrestrial Time
+    fn to_tt(jd: &JD<Self>) -> JD<TT>;
+
+    /// Converts an instant in Terrestrial Time into this time scale
+    fn from_tt(jd: &JD<TT>) -> JD<Self>;
+}
+
+/// Terrestrial Time (TT), the uniform time scale of ephemerides on the surface of the Earth. It
+/// is the modern name of the Dynamical Time (TD) used in the book.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TT;
+
+/// Universal Time (UT1), which follows the actual rotation of the Earth. Converting from and to
+/// Terrestrial Time uses the ΔT model of `delta_t`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct UT;
+
+/// International Atomic Time (TAI)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TAI;
+
+/// Coordinated Universal Time (UTC), the basis of civil time, which is kept within a second of UT1
+/// by leap seconds. Converting from and to Terrestrial Time uses the leap seconds built into
+/// `LeapSeconds`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct UTC;
+
+/// Barycentric Dynamical Time (TDB), which differs from Terrestrial Time by periodic terms of less
+/// than two milliseconds due to the motion of the Earth in the gravity field of the Sun.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TDB;
+
+impl TimeScale for TT {
+    const NAME: &'static str = "TT";
+
+    fn to_tt(jd: &JD<TT>) -> JD<TT> {
+        *jd
+    }
+
+    fn from_tt(jd: &JD<TT>) -> JD<TT> {
+        *jd
+    }
+}
+
+impl TimeScale for UT {
+    const NAME: &'static str = "UT";
+
+    fn to_tt(jd: &JD<UT>) -> JD<TT> {
+        jd.relabel() + delta_t(jd) / SECONDS_PER_DAY
+    }
+
+    fn from_tt(jd: &JD<TT>) -> JD<UT> {
+        // ΔT is a function of UT, but it changes so slowly that one iteration is plenty
+        let guess: JD<UT> = jd.relabel::<UT>() - delta_t(&jd.relabel()) / SECONDS_PER_DAY;
+        jd.relabel::<UT>() - delta_t(&guess) / SECONDS_PER_DAY
+    }
+}
+
+impl TimeScale for TAI {
+    const NAME: &'static str = "TAI";
+
+    fn to_tt(jd: &JD<TAI>) -> JD<TT> {
+        jd.relabel() + TT_MINUS_TAI / SECONDS_PER_DAY
+    }
+
+    fn from_tt(jd: &JD<TT>) -> JD<TAI> {
+        jd.relabel() - TT_MINUS_TAI / SECONDS_PER_DAY
+    }
+}
+
+impl TimeScale for UTC {
+    const NAME: &'static str = "UTC";
+
+    fn to_tt(jd: &JD<UTC>) -> JD<TT> {
+        LeapSeconds::builtin().utc_to_tai(jd).to_scale()
+    }
+
+    fn from_tt(jd: &JD<TT>) -> JD<UTC> {
+        LeapSeconds::builtin().tai_to_utc(&jd.to_scale())
+    }
+}
+
+impl TimeScale for TDB {
+    const NAME: &'static str = "TDB";
+
+    fn to_tt(jd: &JD<TDB>) -> JD<TT> {
+        jd.relabel() - tdb_minus_tt(&jd.relabel()) / SECONDS_PER_DAY
+    }
+
+    fn from_tt(jd: &JD<TT>) -> JD<TDB> {
+        jd.relabel() + tdb_minus_tt(jd) / SECONDS_PER_DAY
+    }
+}
+
+/// The difference TDB − TT in seconds, keeping the two largest periodic terms
+fn tdb_minus_tt(jd: &JD<TT>) -> f64 {
+    let g = Angle::from_degrees(357.53 + 0.985_600_28 * (*jd - JD::J2000));
+    0.001_657 * g.sin() + 0.000_014 * (g * 2.0).sin()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn conversions() {
+        // Example 10.a, page 78: ΔT was 48s on 1977 February 18
+        let ut = JD::<UT>::from_parts(2_443_192.5, 0.0);
+        let tt = ut.to_scale::<TT>();
+        assert_approx_eq!((tt.as_f64() - ut.as_f64()) * SECONDS_PER_DAY, 48.0, 0.5);
+        assert_approx_eq!((tt.to_scale::<UT>() - ut) * SECONDS_PER_DAY, 0.0, 1e-6);
+
+        let tai = tt.to_scale::<TAI>();
+        assert_approx_eq!((tt.as_f64() - tai.as_f64()) * SECONDS_PER_DAY, 32.184, 1e-4);
+        assert_approx_eq!((tai.to_scale::<UT>() - ut) * SECONDS_PER_DAY, 0.0, 1e-6);
+
+        let tdb = tt.to_scale::<TDB>();
+        assert!(((tdb.as_f64() - tt.as_f64()) * SECONDS_PER_DAY).abs() < 0.002);
+        assert_approx_eq!((tdb.to_scale::<TT>() - tt) * SECONDS_PER_DAY, 0.0, 1e-6);
+    }
+}
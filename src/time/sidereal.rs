@@ -0,0 +1,58 @@
+//! Sidereal time, the hour angle of the vernal equinox.
+//!
+//! Sidereal time ties the rotation of the Earth to the stars, and is needed to know which part of
+//! the sky is on the meridian of an observer. See chapter 12.
+use crate::angle::Angle;
+use crate::nutation;
+use crate::time::{JulianCenturies, JD, TT, UT};
+
+/// Computes the mean sidereal time at Greenwich at the given instant of UT, using formula 12.4.
+///
+/// The result is in the range [0°, 360°). Adding the observer's longitude (positive east) gives the
+/// local mean sidereal time.
+pub fn mean_sidereal_time(t: &JD<UT>) -> Angle {
+    let days = *t - JD::from_parts(2_451_545.0, 0.0);
+    let big_t = JulianCenturies::from(*t).0;
+    let degrees = 280.460_618_37 + 360.985_647_366_29 * days + 0.000_387_933 * big_t * big_t
+        - big_t * big_t * big_t / 38_710_000.0;
+
+    Angle::from_degrees(degrees.rem_euclid(360.0))
+}
+
+/// Computes the apparent sidereal time at Greenwich at the given instant of UT, the hour angle of
+/// the true equinox of date.
+///
+/// This is the mean sidereal time corrected by the equation of the equinoxes, and is the one to
+/// compare apparent right ascensions with. The result is in the range [0°, 360°).
+pub fn apparent_sidereal_time(t: &JD<UT>) -> Angle {
+    let equation = nutation::equation_of_the_equinoxes(&t.to_scale::<TT>());
+    (mean_sidereal_time(t) + equation).normalize_0_360()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn greenwich_mean_sidereal_time() {
+        // Example 12.a, page 88: 1987 April 10 at 0h UT, 13h10m46.3668s
+        let theta = mean_sidereal_time(&JD::from(2_446_895.5));
+        assert_approx_eq!(theta.as_degrees(), 197.693_195, 1e-6);
+
+        // Example 12.b, page 89: 1987 April 10 at 19h21m00s UT
+        let theta = mean_sidereal_time(&JD::from_parts(2_446_895.5, 19.35 / 24.0));
+        assert_approx_eq!(theta.as_degrees(), 128.737_873_4, 1e-6);
+    }
+
+    #[test]
+    fn greenwich_apparent_sidereal_time() {
+        // Example 12.a, page 88: 13h10m46.1351s
+        let theta = apparent_sidereal_time(&JD::from(2_446_895.5));
+        assert_approx_eq!(
+            theta.as_hours(),
+            13.0 + 10.0 / 60.0 + 46.1351 / 3600.0,
+            1e-4 / 3600.0
+        );
+    }
+}
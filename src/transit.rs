@@ -0,0 +1,220 @@
+//! The moments when bodies cross the local meridian.
+//!
+//! A body transits when its hour angle is zero, which is also when it is highest in the sky. The
+//! transit of the Sun is local apparent noon. This follows the method of chapter 15, but only the
+//! transit is computed, so there is no need for the observer's latitude. The altitude at transit
+//! also tells whether a body rises and sets at all, which matters at high latitudes.
+use crate::angle::Angle;
+use crate::bodies::Body;
+use crate::coords::{Equatorial, MeanOfDate};
+use crate::planets::Aberration;
+use crate::time::sidereal::mean_sidereal_time;
+use crate::time::{JD, TT, UT};
+
+/// Computes the moment, in UT, of the upper transit of `body` across the meridian at `longitude`,
+/// on the day starting at the midnight (UT) preceding `date`.
+///
+/// `longitude` is measured positive east of Greenwich, unlike chapter 15 which measures it
+/// positive west.
+///
+/// The apparent right ascension of the body is referred to the mean equinox of date, no correction
+/// for nutation is made. The result is accurate to a couple of seconds for the Sun and planets.
+/// Since the transit is found by iterating on the hour angle, it can fall a few seconds outside of
+/// the day when the body transits right at midnight.
+pub fn meridian_transit(body: Body, date: &JD<UT>, longitude: Angle) -> JD<UT> {
+    transit(body, date, longitude, Angle::ZERO).0
+}
+
+/// Whether a body can be seen to rise and set from a given latitude.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Visibility {
+    /// The body stays above the horizon all day
+    Circumpolar,
+    /// The body stays below the horizon all day
+    NeverRises,
+    /// The body crosses the horizon twice a day
+    RisesAndSets,
+}
+
+/// Classifies the daily motion of a body with the given declination, as seen from `latitude`.
+///
+/// `horizon` is the altitude the body has to reach to count as risen, for example -0.5667° to
+/// account for the refraction at the horizon for stars and planets, or 0° for the geometric
+/// horizon. The declination is assumed constant over the day.
+pub fn visibility(declination: Angle, latitude: Angle, horizon: Angle) -> Visibility {
+    if lower_altitude(declination, latitude) > horizon {
+        Visibility::Circumpolar
+    } else if upper_altitude(declination, latitude) < horizon {
+        Visibility::NeverRises
+    } else {
+        Visibility::RisesAndSets
+    }
+}
+
+/// A passage of a body across the local meridian.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Culmination {
+    /// The moment of the passage, in UT
+    pub time: JD<UT>,
+    /// The geometric altitude of the body at that moment, without refraction
+    pub altitude: Angle,
+    /// The declination of the body at that moment, referred to the mean equinox of date
+    pub declination: Angle,
+}
+
+/// Computes the upper culmination of `body`, when it is highest in the sky, as seen from
+/// `longitude` (positive east) and `latitude`. See `meridian_transit` for the details.
+pub fn upper_culmination(
+    body: Body,
+    date: &JD<UT>,
+    longitude: Angle,
+    latitude: Angle,
+) -> Culmination {
+    let (time, position) = transit(body, date, longitude, Angle::ZERO);
+    Culmination {
+        time,
+        altitude: upper_altitude(position.dec().angle(), latitude),
+        declination: position.dec().angle(),
+    }
+}
+
+/// Computes the lower culmination of `body`, when it crosses the meridian below the pole and is
+/// lowest in the sky, as seen from `longitude` (positive east) and `latitude`. See
+/// `meridian_transit` for the details.
+pub fn lower_culmination(
+    body: Body,
+    date: &JD<UT>,
+    longitude: Angle,
+    latitude: Angle,
+) -> Culmination {
+    let (time, position) = transit(body, date, longitude, Angle::STRAIGHT_ANGLE);
+    Culmination {
+        time,
+        altitude: lower_altitude(position.dec().angle(), latitude),
+        declination: position.dec().angle(),
+    }
+}
+
+/// The altitude of a body on the meridian, on the same side of the pole as the equator
+fn upper_altitude(declination: Angle, latitude: Angle) -> Angle {
+    Angle::asin((latitude - declination).cos())
+}
+
+/// The altitude of a body on the meridian, below the pole
+fn lower_altitude(declination: Angle, latitude: Angle) -> Angle {
+    Angle::asin(-(latitude + declination).cos())
+}
+
+/// Finds when the hour angle of `body` is `target` at `longitude`, on the day starting at the
+/// midnight preceding `date`, along with its equatorial position of date at that moment
+fn transit(
+    body: Body,
+    date: &JD<UT>,
+    longitude: Angle,
+    target: Angle,
+) -> (JD<UT>, Equatorial<MeanOfDate>) {
+    let midnight: JD<UT> = JD::from_parts(date.midnight(), 0.0);
+    let position = |t: &JD<UT>| {
+        let t = t.to_scale::<TT>();
+        body.get_geocentric_position(&t, Aberration::Planetary)
+            .ecliptical
+            .to_equatorial()
+            .to_equinox(MeanOfDate(t))
+    };
+
+    let offset = |t: &JD<UT>| {
+        (mean_sidereal_time(t) + longitude - position(t).ra().angle())
+            .difference(&target)
+            .as_degrees()
+    };
+
+    // First approximation, formula 15.2 with the position at midnight
+    let mut m = (-offset(&midnight) / 360.985_647).rem_euclid(1.0);
+    for _ in 0..10 {
+        let correction = -offset(&(midnight + m)) / 360.985_647;
+        m += correction;
+        if correction.abs() < 1e-8 {
+            break;
+        }
+    }
+
+    let time = midnight + m;
+    (time, position(&time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::planets::Planet;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn venus_transit() {
+        // Example 15.a, page 103: Venus at Boston on 1988 March 20, transit at 0.81965 day. The
+        // example interpolates positions tabulated to the nearest arcsecond, so only agreement to
+        // about ten seconds can be expected.
+        let date = JD::from(2_447_240.5);
+        let boston = Angle::from_degrees(-71.0833);
+        let transit = meridian_transit(Body::Planet(Planet::Venus), &date, boston);
+        assert_approx_eq!(transit - date, 0.819_65, 2e-4);
+    }
+
+    #[test]
+    fn local_apparent_noon() {
+        // Example 28.a, page 185: on 1992 October 13 the equation of time is +13m42.6s, so the Sun
+        // transits at Greenwich at about 11h46m17s UT. The equation of time is given for 0h
+        // dynamical time and keeps changing through the day, so this only holds to several seconds.
+        let date = JD::from(2_448_908.5);
+        let transit = meridian_transit(Body::Sun, &date, Angle::from_degrees(0.0));
+        assert_approx_eq!((transit - date) * 86400.0, 42_377.0, 10.0);
+
+        // 90° further east the Sun transits six hours earlier, less the change in the equation of
+        // time over those six hours
+        let transit = meridian_transit(Body::Sun, &date, Angle::from_degrees(90.0));
+        assert_approx_eq!((transit - date) * 86400.0, 42_377.0 - 21_600.0, 10.0);
+    }
+
+    #[test]
+    fn circumpolar() {
+        let horizon = Angle::from_degrees(-0.5667);
+        let latitude = Angle::from_degrees(60.0);
+        assert_eq!(
+            visibility(Angle::from_degrees(40.0), latitude, horizon),
+            Visibility::Circumpolar
+        );
+        assert_eq!(
+            visibility(Angle::from_degrees(-40.0), latitude, horizon),
+            Visibility::NeverRises
+        );
+        assert_eq!(
+            visibility(Angle::from_degrees(10.0), latitude, horizon),
+            Visibility::RisesAndSets
+        );
+        // The same in the southern hemisphere
+        assert_eq!(
+            visibility(
+                Angle::from_degrees(-40.0),
+                Angle::from_degrees(-60.0),
+                horizon
+            ),
+            Visibility::Circumpolar
+        );
+    }
+
+    #[test]
+    fn midnight_sun() {
+        // At the June solstice the Sun stays up all night at 70° north
+        let date = JD::from(2_451_716.5);
+        let longitude = Angle::from_degrees(20.0);
+        let latitude = Angle::from_degrees(70.0);
+        let upper = upper_culmination(Body::Sun, &date, longitude, latitude);
+        let lower = lower_culmination(Body::Sun, &date, longitude, latitude);
+        assert_approx_eq!(upper.altitude.as_degrees(), 90.0 - 70.0 + 23.44, 0.01);
+        assert_approx_eq!(lower.altitude.as_degrees(), 70.0 + 23.44 - 90.0, 0.01);
+        assert_approx_eq!((lower.time - upper.time).abs(), 0.5, 0.01);
+        assert_eq!(
+            visibility(upper.declination, latitude, Angle::from_degrees(-0.8333)),
+            Visibility::Circumpolar
+        );
+    }
+}